@@ -1,24 +1,1559 @@
 #define FFI_LIB "libsql_php_client.so"
 
 /**
- * Represents a pair of pointers to a database and a connection.
+ * The source is the database file of another remote replica of the same primary, matching
+ * `LIBSQLPHP_SEED_REPLICA` on the PHP side.
+ */
+#define LIBSQL_PHP_SEED_REPLICA 1
+
+/**
+ * The source is a snapshot file written by the primary, matching `LIBSQLPHP_SEED_SNAPSHOT` on the PHP side.
+ */
+#define LIBSQL_PHP_SEED_SNAPSHOT 2
+
+/**
+ * AES-256 in CBC mode, matching `LIBSQLPHP_CIPHER_AES256CBC` on the PHP side.
+ *
+ * This is the only cipher libsql can configure for encryption at rest.
+ */
+#define LIBSQL_PHP_CIPHER_AES256CBC 1
+
+/**
+ * The key bytes are handed to the cipher as they are, matching `LIBSQLPHP_KEY_RAW` on the PHP side.
+ */
+#define LIBSQL_PHP_KEY_RAW 1
+
+/**
+ * The key is a string of hex digits decoded into the key bytes, matching `LIBSQLPHP_KEY_HEX` on the PHP side.
+ */
+#define LIBSQL_PHP_KEY_HEX 2
+
+/**
+ * The key is a passphrase stretched into 32 key bytes with 100 000 rounds of PBKDF2-HMAC-SHA256
+ * salted with `libsql-php-passphrase`, matching `LIBSQLPHP_KEY_PASSPHRASE` on the PHP side.
+ */
+#define LIBSQL_PHP_KEY_PASSPHRASE 3
+
+/**
+ * Retries statements that failed with `SQLITE_BUSY`, matching `LIBSQLPHP_RETRY_BUSY` on the PHP side.
+ */
+#define LIBSQL_PHP_RETRY_BUSY 1
+
+/**
+ * Retries statements that failed with `SQLITE_LOCKED`, matching `LIBSQLPHP_RETRY_LOCKED` on the PHP side.
+ */
+#define LIBSQL_PHP_RETRY_LOCKED 2
+
+/**
+ * Retries statements that failed because the connection to the remote database could not be opened,
+ * matching `LIBSQLPHP_RETRY_REMOTE` on the PHP side. Only failures raised before the request was sent
+ * are retried, so a write that may have reached the database is never sent twice.
+ */
+#define LIBSQL_PHP_RETRY_REMOTE 4
+
+/**
+ * Tag of a `LibsqlPhpValue` holding an integer, matching `LIBSQLPHP_INTEGER` on the PHP side.
+ */
+#define LIBSQL_PHP_INTEGER 1
+
+/**
+ * Tag of a `LibsqlPhpValue` holding a float, matching `LIBSQLPHP_FLOAT` on the PHP side.
+ */
+#define LIBSQL_PHP_FLOAT 2
+
+/**
+ * Tag of a `LibsqlPhpValue` holding UTF-8 text, matching `LIBSQLPHP_TEXT` on the PHP side.
+ */
+#define LIBSQL_PHP_TEXT 3
+
+/**
+ * Tag of a `LibsqlPhpValue` holding raw bytes, matching `LIBSQLPHP_BLOB` on the PHP side.
+ */
+#define LIBSQL_PHP_BLOB 4
+
+/**
+ * Tag of a `LibsqlPhpValue` holding SQL NULL, matching `LIBSQLPHP_NULL` on the PHP side.
+ */
+#define LIBSQL_PHP_NULL 5
+
+/**
+ * Status returned by the exported functions when the call succeeded.
+ */
+#define LIBSQL_PHP_OK 0
+
+/**
+ * Opens the database read-only, matching `LIBSQLPHP_OPEN_READONLY` on the PHP side.
+ */
+#define LIBSQL_PHP_OPEN_READONLY 1
+
+/**
+ * Opens the database for reading and writing, matching `LIBSQLPHP_OPEN_READWRITE` on the PHP side.
+ */
+#define LIBSQL_PHP_OPEN_READWRITE 2
+
+/**
+ * Creates the database if it does not exist, matching `LIBSQLPHP_OPEN_CREATE` on the PHP side.
+ */
+#define LIBSQL_PHP_OPEN_CREATE 4
+
+/**
+ * Interprets the path as a URI filename, matching `LIBSQLPHP_OPEN_URI` on the PHP side.
+ */
+#define LIBSQL_PHP_OPEN_URI 64
+
+/**
+ * Opens the database in memory, matching `LIBSQLPHP_OPEN_MEMORY` on the PHP side.
+ */
+#define LIBSQL_PHP_OPEN_MEMORY 128
+
+/**
+ * Opens the connection in multi-thread mode, matching `LIBSQLPHP_OPEN_NOMUTEX` on the PHP side.
+ */
+#define LIBSQL_PHP_OPEN_NOMUTEX 32768
+
+/**
+ * Opens the connection in serialized mode, matching `LIBSQLPHP_OPEN_FULLMUTEX` on the PHP side.
+ */
+#define LIBSQL_PHP_OPEN_FULLMUTEX 65536
+
+/**
+ * Enables the shared cache, matching `LIBSQLPHP_OPEN_SHAREDCACHE` on the PHP side.
+ */
+#define LIBSQL_PHP_OPEN_SHAREDCACHE 131072
+
+/**
+ * Disables the shared cache, matching `LIBSQLPHP_OPEN_PRIVATECACHE` on the PHP side.
+ */
+#define LIBSQL_PHP_OPEN_PRIVATECACHE 262144
+
+/**
+ * Refuses to open a path that is a symbolic link, matching `LIBSQLPHP_OPEN_NOFOLLOW` on the PHP side.
+ */
+#define LIBSQL_PHP_OPEN_NOFOLLOW 16777216
+
+/**
+ * A bounded pool of connections to one database, kept alive between PHP requests.
+ *
+ * Connections are opened lazily up to `max_size`. A connection that is checked in waits in the
+ * pool until it is checked out again or has been idle longer than the idle timeout, and is
+ * health checked before it is handed out again.
+ */
+typedef struct ConnectionPool ConnectionPool;
+
+/**
+ * Represents an open database that connections can be made to.
+ *
+ * Returned by `libsql_php_db_open` and released with `libsql_php_db_close`.
+ * Connections share the database, so it stays open until the last of them is closed.
+ */
+typedef struct LibsqlDatabase LibsqlDatabase;
+
+/**
+ * Represents an open database together with its connection.
+ *
+ * Freeing the handle with `libsql_php_close` closes its connection, and the database too
+ * once no other connection or `LibsqlDatabase` holds it. C code only ever sees it as an opaque pointer.
+ */
+typedef struct LibsqlHandle LibsqlHandle;
+
+/**
+ * Represents a statement prepared on a connection together with the values bound to it.
+ *
+ * The statement is parsed once by `libsql_php_prepare` and can be executed many times,
+ * binding new values between executions.
+ */
+typedef struct PreparedStatement PreparedStatement;
+
+/**
+ * Represents an open query whose rows are read one at a time.
+ *
+ * The cursor owns the JSON of the row it returned last, so reading a row never
+ * requires the caller to free anything.
+ */
+typedef struct RowCursor RowCursor;
+
+/**
+ * Represents a transaction started by `libsql_php_transaction`.
+ *
+ * The transaction is consumed by the first commit or rollback. The handle stays valid afterwards
+ * so that later calls on it report an error, and is released by `libsql_php_transaction_free`.
+ *
+ * The handle also keeps the savepoints opened inside the transaction, innermost last.
+ */
+typedef struct TransactionHandle TransactionHandle;
+
+/**
+ * Represents a typed parameter value passed from C code.
+ *
+ * This struct is marked with #[repr(C)] to ensure its memory layout is compatible
+ * with C code. Only the field selected by `tag` is read.
+ */
+typedef struct LibsqlPhpValue {
+  /**
+   * One of the `LIBSQL_PHP_*` type tags.
+   */
+  int tag;
+  /**
+   * The value when `tag` is `LIBSQL_PHP_INTEGER`.
+   */
+  int64_t int_value;
+  /**
+   * The value when `tag` is `LIBSQL_PHP_FLOAT`.
+   */
+  double real_value;
+  /**
+   * A raw pointer to the bytes when `tag` is `LIBSQL_PHP_TEXT` or `LIBSQL_PHP_BLOB`.
+   */
+  const uint8_t *ptr;
+  /**
+   * The number of bytes pointed to by `ptr`.
+   */
+  uintptr_t len;
+} LibsqlPhpValue;
+
+/**
+ * Represents the encryption settings of a database passed from C code.
+ *
+ * This struct is marked with #[repr(C)] to ensure its memory layout is compatible
+ * with C code. The key is given with its length, so raw keys may contain null bytes.
+ */
+typedef struct LibsqlPhpEncryption {
+  /**
+   * One of the `LIBSQL_PHP_CIPHER_*` ciphers.
+   */
+  int cipher;
+  /**
+   * One of the `LIBSQL_PHP_KEY_*` formats `key` is given in.
+   */
+  int key_format;
+  /**
+   * A raw pointer to the key bytes.
+   */
+  const uint8_t *key;
+  /**
+   * The number of bytes pointed to by `key`.
+   */
+  uintptr_t key_len;
+} LibsqlPhpEncryption;
+
+/**
+ * The sync status of a remote replica connection, filled in by `libsql_php_replica_status`.
+ *
+ * Fields holding `-1` are unknown.
+ */
+typedef struct LibsqlPhpReplicaStatus {
+  /**
+   * `1` if the last attempt to sync reached the primary, `0` if it failed or none was made yet.
+   */
+  int online;
+  /**
+   * `1` if the connection reads the local replica file read-only because the primary could not
+   * be reached when it was opened and no sync made with `libsql_php_sync` or
+   * `libsql_php_sync_until` has succeeded since, `0` otherwise.
+   */
+  int read_only;
+  /**
+   * `1` if the replica may be missing writes made on the primary, `0` if it is up to date.
+   */
+  int stale;
+  /**
+   * The frame number the replica has committed locally.
+   */
+  int64_t frame_no;
+  /**
+   * How many frames the replica is behind the last frame number known for the primary.
+   */
+  int64_t frames_behind;
+  /**
+   * The number of seconds since the replica last synced with the primary.
+   */
+  int64_t seconds_since_sync;
+} LibsqlPhpReplicaStatus;
+
+/**
+ * Called after each background sync with the `user_data` it was registered with, the status of
+ * the sync (`0` or an error code) and the frame number the replica reached, or `-1`.
+ *
+ * It runs on a thread of the shared runtime, not on the thread that started the background sync,
+ * and is never called once the background sync has been stopped. It must not stop or restart the
+ * background sync itself.
+ */
+typedef void (*LibsqlPhpSyncCallback)(void *user_data, int status, int64_t frame_no);
+
+/**
+ * The state of the background sync of a remote replica connection, filled in by
+ * `libsql_php_background_sync_status`.
+ */
+typedef struct LibsqlPhpBackgroundSyncStatus {
+  /**
+   * `1` if the background sync is running, `0` otherwise.
+   */
+  int running;
+  /**
+   * When the replica last synced with the primary, in milliseconds since the Unix epoch, or `-1`.
+   */
+  int64_t last_sync_unix_ms;
+  /**
+   * The error of the most recent failed sync, or a null pointer if none failed. It is owned by
+   * the connection and stays valid until the next status call on it or until it is closed.
+   */
+  const char *last_error;
+  /**
+   * How many syncs failed in a row since the last one that succeeded.
+   */
+  uint32_t consecutive_failures;
+} LibsqlPhpBackgroundSyncStatus;
+
+/**
+ * How a connection retries statements that failed with a transient error, set with
+ * `libsql_php_set_retry_policy`.
+ *
+ * The delay before retry `n` is `initial_backoff_ms * 2^(n - 1)`, capped at `max_backoff_ms`,
+ * then shortened by a random share of up to `jitter_percent` percent.
+ */
+typedef struct LibsqlPhpRetryPolicy {
+  /**
+   * How many times a statement is run at most, counting the first attempt. `1` disables retries.
+   */
+  uint32_t max_attempts;
+  /**
+   * The delay before the first retry, in milliseconds.
+   */
+  uint32_t initial_backoff_ms;
+  /**
+   * The longest delay between two attempts, in milliseconds.
+   */
+  uint32_t max_backoff_ms;
+  /**
+   * How much of each delay, in percent from `0` to `100`, is randomly skipped.
+   */
+  uint32_t jitter_percent;
+  /**
+   * The `LIBSQL_PHP_RETRY_*` error classes that are retried.
+   */
+  int retry_on;
+} LibsqlPhpRetryPolicy;
+
+/**
+ * Represents one statement of a batch passed from C code to `libsql_php_batch`.
+ *
+ * This struct is marked with #[repr(C)] to ensure its memory layout is compatible
+ * with C code.
+ */
+typedef struct LibsqlPhpBatchStatement {
+  /**
+   * A pointer to a null-terminated C string holding the SQL statement.
+   */
+  const char *sql;
+  /**
+   * A pointer to an array of C-style strings holding the parameter names, or null for positional parameters.
+   */
+  const char *const *names;
+  /**
+   * A pointer to an array of `LibsqlPhpValue` holding the parameters of the statement.
+   */
+  const struct LibsqlPhpValue *values;
+  /**
+   * The length of the `values` array, and of the `names` array when it is given.
+   */
+  uintptr_t values_len;
+} LibsqlPhpBatchStatement;
+
+/**
+ * Retrieves the error code of the most recent failing LibSQL call on the current thread.
+ *
+ * # Returns
+ *
+ * Returns the error code reported by the failing call, or `0` if no error was recorded.
+ */
+int libsql_php_last_error_code(void);
+
+/**
+ * Retrieves the SQLite extended result code of the most recent failing LibSQL call on the current thread.
+ *
+ * # Returns
+ *
+ * Returns the SQLite extended result code, or `0` if the error did not come from SQLite.
+ */
+int libsql_php_last_error_extended_code(void);
+
+/**
+ * Retrieves the message of the most recent failing LibSQL call on the current thread.
+ *
+ * # Returns
+ *
+ * Returns a pointer to a C-style string containing the error message, or a null pointer if no error was recorded.
+ * The string is owned by the library and stays valid until the next error on the same thread.
+ */
+const char *libsql_php_last_error_message(void);
+
+/**
+ * Clears the error recorded on the current thread.
+ */
+void libsql_php_clear_error(void);
+
+/**
+ * Rolls back a transaction in a PHP extension.
+ *
+ * # Safety
+ *
+ * This function is marked as unsafe because it dereferences raw pointers.
+ * The handle stays valid and must still be released with `libsql_php_transaction_free`.
+ *
+ * # Arguments
+ *
+ * * `trx_ptr` - A raw pointer to the transaction handle returned by `libsql_php_transaction`.
+ *
+ * # Returns
+ *
+ * Returns `0` if the transaction is successfully rolled back. If an error occurs, a null pointer is provided or the
+ * transaction was already committed or rolled back, returns the error code and records the error for
+ * `libsql_php_last_error_message`.
+ */
+int libsql_php_transaction_rollback(void *trx_ptr);
+
+/**
+ * Commits a transaction in a PHP extension.
+ *
+ * # Safety
+ *
+ * This function is marked as unsafe because it dereferences raw pointers.
+ * The handle stays valid and must still be released with `libsql_php_transaction_free`.
+ *
+ * # Arguments
+ *
+ * * `trx_ptr` - A raw pointer to the transaction handle returned by `libsql_php_transaction`.
+ *
+ * # Returns
+ *
+ * Returns `0` if the transaction is successfully committed. If an error occurs, a null pointer is provided or the
+ * transaction was already committed or rolled back, returns the error code and records the error for
+ * `libsql_php_last_error_message`. A transaction whose commit fails is rolled back.
+ */
+int libsql_php_transaction_commit(void *trx_ptr);
+
+/**
+ * Executes a LibSQL query within a transaction in a PHP extension.
+ *
+ * # Safety
+ *
+ * This function is marked as unsafe because it dereferences raw pointers.
+ *
+ * # Arguments
+ *
+ * * `trx_ptr` - A raw pointer to the transaction handle returned by `libsql_php_transaction`.
+ * * `query` - A raw pointer to a C-style string representing the LibSQL query to execute.
+ * * `query_params` - A pointer to an array of raw pointers to C-style strings representing query parameters,
+ *   bound as text. A null or non-UTF-8 parameter is rejected with `InvalidParameter`.
+ * * `query_params_len` - The length of the `query_params` array.
+ *
+ * # Returns
+ *
+ * Returns `0` if the query was executed. If an error occurs or a null pointer is provided, returns the error code
+ * and records the error for `libsql_php_last_error_message`.
+ */
+int libsql_php_transaction_exec(void *trx_ptr,
+                                const char *query,
+                                const char *const *query_params,
+                                uintptr_t query_params_len);
+
+/**
+ * Executes a LibSQL query with typed positional parameters within a transaction in a PHP extension.
+ *
+ * # Safety
+ *
+ * This function is marked as unsafe because it dereferences raw pointers.
+ *
+ * # Arguments
+ *
+ * * `trx_ptr` - A raw pointer to the transaction handle returned by `libsql_php_transaction`.
+ * * `query` - A raw pointer to a C-style string representing the LibSQL query to execute.
+ * * `values` - A pointer to an array of `LibsqlPhpValue` representing query parameters.
+ * * `values_len` - The length of the `values` array.
+ *
+ * # Returns
+ *
+ * Returns `0` if the query was executed. If an error occurs or a null pointer is provided, returns the error code
+ * and records the error for `libsql_php_last_error_message`.
+ */
+int libsql_php_transaction_exec_values(void *trx_ptr,
+                                       const char *query,
+                                       const struct LibsqlPhpValue *values,
+                                       uintptr_t values_len);
+
+/**
+ * Executes a LibSQL query with named parameters (`:name`, `@name` or `$name`) within a transaction in a PHP extension.
+ *
+ * # Safety
+ *
+ * This function is marked as unsafe because it dereferences raw pointers.
+ *
+ * # Arguments
+ *
+ * * `trx_ptr` - A raw pointer to the transaction handle returned by `libsql_php_transaction`.
+ * * `query` - A raw pointer to a C-style string representing the LibSQL query to execute.
+ * * `names` - A pointer to an array of C-style strings holding the parameter names, with or without their prefix.
+ * * `values` - A pointer to an array of `LibsqlPhpValue` holding the value of each name.
+ * * `values_len` - The length of the `names` and `values` arrays.
+ *
+ * # Returns
+ *
+ * Returns `0` if the query was executed. If an error occurs or a null pointer is provided, returns the error code
+ * and records the error for `libsql_php_last_error_message`.
+ */
+int libsql_php_transaction_exec_named(void *trx_ptr,
+                                      const char *query,
+                                      const char *const *names,
+                                      const struct LibsqlPhpValue *values,
+                                      uintptr_t values_len);
+
+/**
+ * Initiates a LibSQL transaction in a PHP extension with the specified behavior.
+ *
+ * # Safety
+ *
+ * This function is marked as unsafe because it dereferences raw pointers.
+ *
+ * # Arguments
+ *
+ * * `client_ptr` - A raw pointer to the `LibsqlHandle` of the connection.
+ * * `behavior` - A raw pointer to a C-style string representing the transaction behavior. Can be "DEFERRED", "IMMEDIATE",
+ *   "EXCLUSIVE", "READONLY", or null for "DEFERRED". "WRITE" and "READ" are accepted for "IMMEDIATE" and "READONLY". Names are case-sensitive.
+ * * `out_trx` - A pointer where the raw pointer to the transaction handle is written. Release it with
+ *   `libsql_php_transaction_free`.
+ *
+ * # Returns
+ *
+ * Returns `0` if the transaction was started. If an error occurs, the behavior is unknown or the client pointer is null,
+ * returns the error code and records the error for `libsql_php_last_error_message`.
+ */
+int libsql_php_transaction(void *client_ptr,
+                           const char *behavior,
+                           struct TransactionHandle **out_trx);
+
+/**
+ * Retrieves the last inserted row ID from a SQL connection in a PHP extension.
+ *
+ * # Safety
+ *
+ * This function is marked as unsafe because it dereferences raw pointers.
+ *
+ * # Arguments
+ *
+ * * `client_ptr` - A raw pointer to the `LibsqlHandle` of the connection.
+ * * `out_rowid` - A pointer where the last inserted row ID is written.
+ *
+ * # Returns
+ *
+ * Returns `0` on success. If the client pointer or the output pointer is null, returns the error code
+ * and records the error for `libsql_php_last_error_message`.
+ */
+int libsql_php_last_insert_rowid(void *client_ptr,
+                                 int64_t *out_rowid);
+
+/**
+ * Checks if autocommit is enabled for a LibSQL connection in a PHP extension.
+ *
+ * # Safety
+ *
+ * This function is marked as unsafe because it dereferences raw pointers.
+ *
+ * # Arguments
+ *
+ * * `client_ptr` - A raw pointer to the `LibsqlHandle` of the connection.
+ * * `out_autocommit` - A pointer where `1` is written if autocommit is enabled, `0` otherwise.
+ *
+ * # Returns
+ *
+ * Returns `0` on success. If the client pointer or the output pointer is null, returns the error code
+ * and records the error for `libsql_php_last_error_message`.
+ */
+int libsql_php_is_autocommit(void *client_ptr,
+                             int *out_autocommit);
+
+/**
+ * Resets a LibSQL connection in a PHP extension.
+ *
+ * # Safety
+ *
+ * This function is marked as unsafe because it dereferences raw pointers.
+ *
+ * # Arguments
+ *
+ * * `client_ptr` - A raw pointer to the `LibsqlHandle` of the connection.
+ *
+ * # Returns
+ *
+ * Returns `0` if the connection was reset. If the client pointer is null, returns the error code
+ * and records the error for `libsql_php_last_error_message`.
+ */
+int libsql_php_reset(void *client_ptr);
+
+/**
+ * Executes a batch SQL query in a PHP extension, interfacing with a provided LibSQL connection.
+ *
+ * # Safety
+ *
+ * This function is marked as unsafe because it dereferences raw pointers and performs FFI operations.
+ *
+ * # Arguments
+ *
+ * * `client_ptr` - A raw pointer to the `LibsqlHandle` of the connection.
+ * * `query` - A raw pointer to a C-style string representing the batch SQL query to execute.
+ *
+ * # Returns
+ *
+ * Returns `0` if the batch was executed. If an error occurs, returns the error code and records the error for `libsql_php_last_error_message`.
+ */
+int libsql_php_execute_batch(void *client_ptr,
+                             const char *query);
+
+/**
+ * Executes a SQL query in a PHP extension, interfacing with a provided LibSQL connection.
+ *
+ * # Safety
+ *
+ * This function is marked as unsafe because it dereferences raw pointers and performs FFI operations.
+ *
+ * # Arguments
+ *
+ * * `client_ptr` - A raw pointer to the `LibsqlHandle` of the connection.
+ * * `query` - A raw pointer to a C-style string representing the SQL query to execute.
+ * * `query_params` - A pointer to an array of raw pointers to C-style strings representing query parameters,
+ *   bound as text. A null or non-UTF-8 parameter is rejected with `InvalidParameter`.
+ * * `query_params_len` - The length of the `query_params` array.
+ * * `out_rows_affected` - A pointer where the number of rows affected by the query is written. May be null.
+ *
+ * # Returns
+ *
+ * Returns `0` on success. If an error occurs, returns the error code and records the error for `libsql_php_last_error_message`.
+ */
+int libsql_php_exec(void *client_ptr,
+                    const char *query,
+                    const char *const *query_params,
+                    uintptr_t query_params_len,
+                    uint64_t *out_rows_affected);
+
+/**
+ * Executes a SQL query with typed positional parameters, interfacing with a provided LibSQL connection.
+ *
+ * # Safety
+ *
+ * This function is marked as unsafe because it dereferences raw pointers and performs FFI operations.
+ *
+ * # Arguments
+ *
+ * * `client_ptr` - A raw pointer to the `LibsqlHandle` of the connection.
+ * * `query` - A raw pointer to a C-style string representing the SQL query to execute.
+ * * `values` - A pointer to an array of `LibsqlPhpValue` representing query parameters.
+ * * `values_len` - The length of the `values` array.
+ * * `out_rows_affected` - A pointer where the number of rows affected by the query is written. May be null.
+ *
+ * # Returns
+ *
+ * Returns `0` on success. If an error occurs, returns the error code and records the error for `libsql_php_last_error_message`.
+ */
+int libsql_php_exec_values(void *client_ptr,
+                           const char *query,
+                           const struct LibsqlPhpValue *values,
+                           uintptr_t values_len,
+                           uint64_t *out_rows_affected);
+
+/**
+ * Executes a SQL query with named parameters (`:name`, `@name` or `$name`), interfacing with a provided LibSQL connection.
+ *
+ * # Safety
+ *
+ * This function is marked as unsafe because it dereferences raw pointers and performs FFI operations.
+ *
+ * # Arguments
+ *
+ * * `client_ptr` - A raw pointer to the `LibsqlHandle` of the connection.
+ * * `query` - A raw pointer to a C-style string representing the SQL query to execute.
+ * * `names` - A pointer to an array of C-style strings holding the parameter names, with or without their prefix.
+ * * `values` - A pointer to an array of `LibsqlPhpValue` holding the value of each name.
+ * * `values_len` - The length of the `names` and `values` arrays.
+ * * `out_rows_affected` - A pointer where the number of rows affected by the query is written. May be null.
+ *
+ * # Returns
+ *
+ * Returns `0` on success. If an error occurs, returns the error code and records the error for `libsql_php_last_error_message`.
+ */
+int libsql_php_exec_named(void *client_ptr,
+                          const char *query,
+                          const char *const *names,
+                          const struct LibsqlPhpValue *values,
+                          uintptr_t values_len,
+                          uint64_t *out_rows_affected);
+
+/**
+ * Executes an LibSQL query using the provided client pointer, query string, and query parameters,
+ * returning the query result in JSON format.
+ *
+ * This function is marked with #[no_mangle] to ensure its symbol is preserved for
+ * use in external C code.
+ *
+ * # Safety
+ *
+ * This function manipulates raw pointers and interfaces with asynchronous code,
+ * requiring careful handling to ensure memory safety and avoid undefined behavior.
+ *
+ * # Arguments
+ *
+ * * `client_ptr` - A raw pointer to the `LibsqlHandle` of the connection.
+ * * `query` - A pointer to a null-terminated C string representing the SQL query.
+ * * `query_params` - A pointer to an array of null-terminated C strings representing query parameters,
+ *   bound as text. A null or non-UTF-8 parameter is rejected with `InvalidParameter`.
+ * * `query_params_len` - The number of query parameters in the array.
+ * * `out_json` - A pointer where the null-terminated C string holding the JSON-formatted query result is written.
+ *   Free it with `libsql_php_free_string`.
+ *
+ * # Returns
+ *
+ * Returns `0` on success. If an error occurs, returns the error code and records the error
+ * for `libsql_php_last_error_message`.
+ *
+ * # Examples
+ *
+ * ```
+ * use std::ptr;
+ * use sql_php_client::libsqlphp::{
+ *     connection_close::libsql_php_close, free_string::libsql_php_free_string,
+ *     local::libsql_php_connect_local, query::libsql_php_query,
+ * };
+ *
+ * let mut conn_ptr = ptr::null_mut();
+ * let path = ":memory:\0".as_ptr().cast();
+ * let status = unsafe { libsql_php_connect_local(path, 0, ptr::null(), &mut conn_ptr) };
+ * assert_eq!(status, 0);
+ *
+ * let client_ptr = conn_ptr.cast();
+ * let query = "SELECT 1\0".as_ptr().cast();
+ * let mut result = ptr::null();
+ * let status = unsafe { libsql_php_query(client_ptr, query, ptr::null(), 0, &mut result) };
+ * assert_eq!(status, 0);
+ *
+ * unsafe {
+ *     libsql_php_free_string(result);
+ *     libsql_php_close(client_ptr);
+ * }
+ * ```
+ */
+int libsql_php_query(void *client_ptr,
+                     const char *query,
+                     const char *const *query_params,
+                     uintptr_t query_params_len,
+                     const char **out_json);
+
+/**
+ * Executes an LibSQL query with typed positional parameters, returning the query result in JSON format.
+ *
+ * # Safety
+ *
+ * This function manipulates raw pointers and interfaces with asynchronous code,
+ * requiring careful handling to ensure memory safety and avoid undefined behavior.
+ *
+ * # Arguments
+ *
+ * * `client_ptr` - A raw pointer to the `LibsqlHandle` of the connection.
+ * * `query` - A pointer to a null-terminated C string representing the SQL query.
+ * * `values` - A pointer to an array of `LibsqlPhpValue` representing query parameters.
+ * * `values_len` - The number of query parameters in the array.
+ * * `out_json` - A pointer where the null-terminated C string holding the JSON-formatted query result is written.
+ *   Free it with `libsql_php_free_string`.
+ *
+ * # Returns
+ *
+ * Returns `0` on success. If an error occurs, returns the error code and records the error
+ * for `libsql_php_last_error_message`.
+ */
+int libsql_php_query_values(void *client_ptr,
+                            const char *query,
+                            const struct LibsqlPhpValue *values,
+                            uintptr_t values_len,
+                            const char **out_json);
+
+/**
+ * Executes an LibSQL query with named parameters (`:name`, `@name` or `$name`), returning the query result in JSON format.
+ *
+ * # Safety
+ *
+ * This function manipulates raw pointers and interfaces with asynchronous code,
+ * requiring careful handling to ensure memory safety and avoid undefined behavior.
+ *
+ * # Arguments
+ *
+ * * `client_ptr` - A raw pointer to the `LibsqlHandle` of the connection.
+ * * `query` - A pointer to a null-terminated C string representing the SQL query.
+ * * `names` - A pointer to an array of C-style strings holding the parameter names, with or without their prefix.
+ * * `values` - A pointer to an array of `LibsqlPhpValue` holding the value of each name.
+ * * `values_len` - The length of the `names` and `values` arrays.
+ * * `out_json` - A pointer where the null-terminated C string holding the JSON-formatted query result is written.
+ *   Free it with `libsql_php_free_string`.
+ *
+ * # Returns
+ *
+ * Returns `0` on success. If an error occurs, returns the error code and records the error
+ * for `libsql_php_last_error_message`.
+ */
+int libsql_php_query_named(void *client_ptr,
+                           const char *query,
+                           const char *const *names,
+                           const struct LibsqlPhpValue *values,
+                           uintptr_t values_len,
+                           const char **out_json);
+
+/**
+ * Opens a cursor over the rows of a LibSQL query, so they can be read one at a time with `libsql_php_rows_next`.
+ *
+ * # Safety
+ *
+ * This function manipulates raw pointers and interfaces with asynchronous code,
+ * requiring careful handling to ensure memory safety and avoid undefined behavior.
+ *
+ * # Arguments
+ *
+ * * `client_ptr` - A raw pointer to the `LibsqlHandle` of the connection.
+ * * `query` - A pointer to a null-terminated C string representing the SQL query.
+ * * `names` - A pointer to an array of C-style strings holding the parameter names, or null for positional parameters.
+ * * `values` - A pointer to an array of `LibsqlPhpValue` representing query parameters.
+ * * `values_len` - The length of the `values` array, and of the `names` array when it is given.
+ * * `out_rows` - A pointer where the cursor handle is written.
+ *
+ * # Returns
+ *
+ * Returns `0` on success. If an error occurs, returns the error code and records the error
+ * for `libsql_php_last_error_message`. The cursor must be released with `libsql_php_rows_free`
+ * before the connection is closed.
+ */
+int libsql_php_query_open(void *client_ptr,
+                          const char *query,
+                          const char *const *names,
+                          const struct LibsqlPhpValue *values,
+                          uintptr_t values_len,
+                          struct RowCursor **out_rows);
+
+/**
+ * Reads the next row of a cursor opened with `libsql_php_query_open`, in JSON format.
+ *
+ * # Safety
+ *
+ * This function is marked as unsafe because it dereferences raw pointers.
+ *
+ * # Arguments
+ *
+ * * `rows_ptr` - A raw pointer to the cursor.
+ * * `out_json` - A pointer where the null-terminated C string holding the row as a JSON array of
+ *   `{"type": ..., "value": ...}` cells,
+ *   in the order given by `libsql_php_rows_columns`, is written, or a null pointer once every row has been read.
+ *   Every later call writes a null pointer too.
+ *
+ * # Returns
+ *
+ * Returns `0` on success. If an error occurs, returns the error code and records the error for `libsql_php_last_error_message`.
+ * The row is owned by the cursor and stays valid until the next call or until the cursor is freed.
+ */
+int libsql_php_rows_next(void *rows_ptr,
+                         const char **out_json);
+
+/**
+ * Retrieves the columns of a cursor opened with `libsql_php_query_open`, in JSON format.
+ *
+ * # Safety
+ *
+ * This function is marked as unsafe because it dereferences raw pointers.
+ *
+ * # Arguments
+ *
+ * * `rows_ptr` - A raw pointer to the cursor.
+ * * `out_json` - A pointer where the null-terminated C string holding the JSON array of columns,
+ *   in SELECT order, is written. Each column is an object with its `name`, `decltype`, `table` and `origin`.
+ *
+ * # Returns
+ *
+ * Returns `0` on success. If an error occurs, returns the error code and records the error for `libsql_php_last_error_message`.
+ * The columns are owned by the cursor and stay valid until the cursor is freed.
+ */
+int libsql_php_rows_columns(void *rows_ptr,
+                            const char **out_json);
+
+/**
+ * Frees a cursor opened with `libsql_php_query_open`, along with any row it still holds.
+ *
+ * # Safety
+ *
+ * This function is marked as unsafe because it takes ownership of a raw pointer.
+ * The cursor must not be used after this call.
+ *
+ * # Arguments
+ *
+ * * `rows_ptr` - A raw pointer to the cursor.
+ *
+ * # Returns
+ *
+ * Returns `0` if the cursor was freed. If the cursor pointer is null, returns the error code
+ * and records the error for `libsql_php_last_error_message`.
+ */
+int libsql_php_rows_free(void *rows_ptr);
+
+/**
+ * Prepares a SQL statement on a LibSQL connection so it can be executed many times without re-parsing.
+ *
+ * # Safety
+ *
+ * This function is marked as unsafe because it dereferences raw pointers.
+ *
+ * # Arguments
+ *
+ * * `client_ptr` - A raw pointer to the `LibsqlHandle` of the connection.
+ * * `query` - A raw pointer to a C-style string representing the SQL statement to prepare.
+ * * `out_stmt` - A pointer where the prepared statement handle is written.
+ *
+ * # Returns
+ *
+ * Returns `0` on success. If an error occurs, returns the error code and records the error for `libsql_php_last_error_message`.
+ * The statement must be released with `libsql_php_stmt_finalize`.
+ *
+ * # Examples
+ *
+ * ```c
+ * PreparedStatement *stmt = NULL;
+ * if (libsql_php_prepare(conn, "INSERT INTO users (name) VALUES (?)", &stmt) == 0) {
+ *     // Bind and execute the statement
+ *     libsql_php_stmt_finalize(stmt);
+ * }
+ * ```
+ */
+int libsql_php_prepare(void *client_ptr,
+                       const char *query,
+                       struct PreparedStatement **out_stmt);
+
+/**
+ * Binds a typed value to a positional parameter of a prepared statement.
+ *
+ * # Safety
+ *
+ * This function is marked as unsafe because it dereferences raw pointers.
+ *
+ * # Arguments
+ *
+ * * `stmt_ptr` - A raw pointer to the prepared statement.
+ * * `index` - The 1-based position of the parameter.
+ * * `value` - A pointer to the `LibsqlPhpValue` to bind.
+ *
+ * # Returns
+ *
+ * Returns `0` if the value was bound. If an error occurs, returns the error code and records the error
+ * for `libsql_php_last_error_message`. The value stays bound until it is replaced or the bindings are cleared.
+ */
+int libsql_php_stmt_bind(void *stmt_ptr,
+                         int index,
+                         const struct LibsqlPhpValue *value);
+
+/**
+ * Binds a typed value to a named parameter (`:name`, `@name` or `$name`) of a prepared statement.
+ *
+ * # Safety
+ *
+ * This function is marked as unsafe because it dereferences raw pointers.
+ *
+ * # Arguments
+ *
+ * * `stmt_ptr` - A raw pointer to the prepared statement.
+ * * `name` - A raw pointer to a C-style string holding the parameter name, with or without its prefix.
+ * * `value` - A pointer to the `LibsqlPhpValue` to bind.
+ *
+ * # Returns
+ *
+ * Returns `0` if the value was bound. If an error occurs, returns the error code and records the error
+ * for `libsql_php_last_error_message`. The value stays bound until it is replaced or the bindings are cleared.
+ */
+int libsql_php_stmt_bind_named(void *stmt_ptr,
+                               const char *name,
+                               const struct LibsqlPhpValue *value);
+
+/**
+ * Executes a prepared statement with its bound values.
+ *
+ * # Safety
+ *
+ * This function is marked as unsafe because it dereferences raw pointers.
+ *
+ * # Arguments
+ *
+ * * `stmt_ptr` - A raw pointer to the prepared statement.
+ * * `out_rows_affected` - A pointer where the number of rows affected by the statement is written. May be null.
+ *
+ * # Returns
+ *
+ * Returns `0` on success. If an error occurs, returns the error code and records the error for `libsql_php_last_error_message`.
+ * The statement is reset before it runs, so it can be executed again right away.
+ */
+int libsql_php_stmt_execute(void *stmt_ptr,
+                            uint64_t *out_rows_affected);
+
+/**
+ * Runs a prepared statement with its bound values, returning the query result in JSON format.
+ *
+ * # Safety
+ *
+ * This function is marked as unsafe because it dereferences raw pointers.
+ *
+ * # Arguments
+ *
+ * * `stmt_ptr` - A raw pointer to the prepared statement.
+ * * `out_json` - A pointer where the null-terminated C string holding the JSON-formatted query result is written.
+ *   Free it with `libsql_php_free_string`.
+ *
+ * # Returns
+ *
+ * Returns `0` on success. If an error occurs, returns the error code and records the error for `libsql_php_last_error_message`.
+ * The statement is reset before it runs, so it can be queried again right away.
+ */
+int libsql_php_stmt_query(void *stmt_ptr,
+                          const char **out_json);
+
+/**
+ * Resets a prepared statement to its initial state, keeping the bound values.
+ *
+ * # Safety
+ *
+ * This function is marked as unsafe because it dereferences raw pointers.
+ *
+ * # Arguments
+ *
+ * * `stmt_ptr` - A raw pointer to the prepared statement.
+ *
+ * # Returns
+ *
+ * Returns `0` if the statement was reset. If the statement pointer is null, returns the error code
+ * and records the error for `libsql_php_last_error_message`.
+ */
+int libsql_php_stmt_reset(void *stmt_ptr);
+
+/**
+ * Forgets every value bound to a prepared statement.
+ *
+ * # Safety
+ *
+ * This function is marked as unsafe because it dereferences raw pointers.
+ *
+ * # Arguments
+ *
+ * * `stmt_ptr` - A raw pointer to the prepared statement.
+ *
+ * # Returns
+ *
+ * Returns `0` if the bindings were cleared. If the statement pointer is null, returns the error code
+ * and records the error for `libsql_php_last_error_message`.
+ */
+int libsql_php_stmt_clear_bindings(void *stmt_ptr);
+
+/**
+ * Retrieves the number of parameters of a prepared statement.
+ *
+ * # Safety
+ *
+ * This function is marked as unsafe because it dereferences raw pointers.
+ *
+ * # Arguments
+ *
+ * * `stmt_ptr` - A raw pointer to the prepared statement.
+ * * `out_count` - A pointer where the number of parameters is written.
+ *
+ * # Returns
+ *
+ * Returns `0` on success. If a pointer is null, returns the error code and records the error
+ * for `libsql_php_last_error_message`.
+ */
+int libsql_php_stmt_parameter_count(void *stmt_ptr, int *out_count);
+
+/**
+ * Retrieves the name of a parameter of a prepared statement, including its `:`, `@` or `$` prefix.
+ *
+ * # Safety
+ *
+ * This function is marked as unsafe because it dereferences raw pointers.
+ *
+ * # Arguments
+ *
+ * * `stmt_ptr` - A raw pointer to the prepared statement.
+ * * `index` - The 1-based position of the parameter.
+ * * `out_name` - A pointer where the parameter name is written, or a null pointer when the parameter is positional.
+ *
+ * # Returns
+ *
+ * Returns `0` on success. If a pointer is null, returns the error code and records the error
+ * for `libsql_php_last_error_message`. The name is owned by the statement and stays valid until it is finalized.
+ */
+int libsql_php_stmt_parameter_name(void *stmt_ptr,
+                                   int index,
+                                   const char **out_name);
+
+/**
+ * Describes the result columns of a prepared statement in JSON format.
+ *
+ * # Safety
+ *
+ * This function is marked as unsafe because it dereferences raw pointers.
+ *
+ * # Arguments
+ *
+ * * `stmt_ptr` - A raw pointer to the prepared statement.
+ * * `out_json` - A pointer where the null-terminated C string holding the JSON array of columns is written.
+ *   Free it with `libsql_php_free_string`.
+ *   Each column is an object with its `name`, declared type `decltype`, origin `table` and `origin` column name,
+ *   where everything but the name may be `null`.
+ *
+ * # Returns
+ *
+ * Returns `0` on success. If an error occurs, returns the error code and records the error for `libsql_php_last_error_message`.
+ */
+int libsql_php_stmt_columns(void *stmt_ptr,
+                            const char **out_json);
+
+/**
+ * Finalizes a prepared statement, releasing it and its bound values.
+ *
+ * # Safety
+ *
+ * This function is marked as unsafe because it takes ownership of a raw pointer.
+ * The statement must not be used after this call.
+ *
+ * # Arguments
+ *
+ * * `stmt_ptr` - A raw pointer to the prepared statement returned by `libsql_php_prepare`.
+ *
+ * # Returns
+ *
+ * Returns `0` if the statement was finalized. If the statement pointer is null, returns the error code
+ * and records the error for `libsql_php_last_error_message`.
+ */
+int libsql_php_stmt_finalize(void *stmt_ptr);
+
+/**
+ * Establishes a connection to a local LibSQL database with optional encryption,
+ * using the provided path, flags, and encryption key.
+ *
+ * This function is marked with #[no_mangle] to ensure its symbol is preserved for
+ * use in external C code.
+ *
+ * # Safety
+ *
+ * This function manipulates raw pointers and interfaces with asynchronous code,
+ * requiring careful handling to ensure memory safety and avoid undefined behavior.
+ *
+ * # Arguments
+ *
+ * * `path` - A pointer to a null-terminated C string representing the path to the database.
+ * * `flags` - A bitmask of `LIBSQL_PHP_OPEN_*` flags for database opening, or `0` for
+ *   `LIBSQL_PHP_OPEN_READWRITE | LIBSQL_PHP_OPEN_CREATE`.
+ * * `encryption` - A pointer to the `LibsqlPhpEncryption` settings of an encrypted database,
+ *   or a null pointer for an unencrypted one.
+ * * `out_conn` - A pointer where the raw pointer to the `LibsqlHandle` is written.
+ *
+ * # Returns
+ *
+ * Returns `0` if the connection is successfully established. Otherwise returns the error code
+ * and records the error for `libsql_php_last_error_message`.
+ *
+ * # Examples
+ *
+ * ```no_run
+ * use std::ptr;
+ * use sql_php_client::libsqlphp::{connection_close::libsql_php_close, local::libsql_php_connect_local};
+ * use sql_php_client::utils::open_flags::{LIBSQL_PHP_OPEN_CREATE, LIBSQL_PHP_OPEN_READWRITE};
+ *
+ * let path = "example.db\0".as_ptr().cast();
+ * let flags = LIBSQL_PHP_OPEN_READWRITE | LIBSQL_PHP_OPEN_CREATE;
+ * let mut conn_ptr = ptr::null_mut();
+ * let status = unsafe { libsql_php_connect_local(path, flags, ptr::null(), &mut conn_ptr) };
+ * assert_eq!(status, 0);
+ * unsafe { libsql_php_close(conn_ptr.cast()) };
+ * ```
+ */
+int libsql_php_connect_local(const char *path,
+                             int flags,
+                             const struct LibsqlPhpEncryption *encryption,
+                             struct LibsqlHandle **out_conn);
+
+/**
+ * Rotates the encryption key of the database file of a local connection.
+ *
+ * Replica connections are rejected with `ErrorCode::UnsupportedMode`: their connections do not
+ * run key pragmas, and re-encrypting the replica file would break the frames the replicator
+ * injects into it. The file is re-encrypted in place, so it must not be in WAL journal mode. Connections opened
+ * afterwards from the same database object still use the key it was opened with, so the database
+ * should be reopened with the new key.
+ *
+ * # Safety
+ *
+ * This function is marked as unsafe because it dereferences raw pointers.
+ *
+ * # Arguments
+ *
+ * * `client_ptr` - A raw pointer to the `LibsqlHandle` of the connection.
+ * * `encryption` - A pointer to the new `LibsqlPhpEncryption` settings, or a null pointer to
+ *   decrypt the file.
+ *
+ * # Returns
+ *
+ * Returns `0` on success. If an error occurs, returns the error code and records the error for
+ * `libsql_php_last_error_message`.
+ */
+int libsql_php_rekey(void *client_ptr,
+                     const struct LibsqlPhpEncryption *encryption);
+
+/**
+ * Opens a local LibSQL database without connecting to it, so several connections
+ * can be made to the same database object.
+ *
+ * # Safety
+ *
+ * This function manipulates raw pointers and interfaces with asynchronous code,
+ * requiring careful handling to ensure memory safety and avoid undefined behavior.
+ *
+ * # Arguments
+ *
+ * * `path` - A pointer to a null-terminated C string representing the path to the database.
+ * * `flags` - A bitmask of `LIBSQL_PHP_OPEN_*` flags for database opening, or `0` for
+ *   `LIBSQL_PHP_OPEN_READWRITE | LIBSQL_PHP_OPEN_CREATE`.
+ * * `encryption` - A pointer to the `LibsqlPhpEncryption` settings of an encrypted database,
+ *   or a null pointer for an unencrypted one.
+ * * `out_db` - A pointer where the raw pointer to the `LibsqlDatabase` is written.
+ *
+ * # Returns
+ *
+ * Returns `0` if the database is opened. Otherwise returns the error code
+ * and records the error for `libsql_php_last_error_message`.
+ * The database must be released with `libsql_php_db_close`.
+ *
+ * # Examples
+ *
+ * ```no_run
+ * use std::ptr;
+ * use sql_php_client::libsqlphp::database::{libsql_php_db_close, libsql_php_db_open};
+ * use sql_php_client::utils::open_flags::{LIBSQL_PHP_OPEN_CREATE, LIBSQL_PHP_OPEN_READWRITE};
+ *
+ * let path = "example.db\0".as_ptr().cast();
+ * let flags = LIBSQL_PHP_OPEN_READWRITE | LIBSQL_PHP_OPEN_CREATE;
+ * let mut db_ptr = ptr::null_mut();
+ * let status = unsafe { libsql_php_db_open(path, flags, ptr::null(), &mut db_ptr) };
+ * assert_eq!(status, 0);
+ * unsafe { libsql_php_db_close(db_ptr.cast()) };
+ * ```
+ */
+int libsql_php_db_open(const char *path,
+                       int flags,
+                       const struct LibsqlPhpEncryption *encryption,
+                       struct LibsqlDatabase **out_db);
+
+/**
+ * Opens a new connection to a database opened with `libsql_php_db_open`.
+ *
+ * Each connection to a `:memory:` database sees its own, separate database.
+ *
+ * # Safety
+ *
+ * This function is marked as unsafe because it dereferences raw pointers.
+ *
+ * # Arguments
+ *
+ * * `db_ptr` - A raw pointer to the `LibsqlDatabase`.
+ * * `out_conn` - A pointer where the raw pointer to the `LibsqlHandle` is written.
+ *
+ * # Returns
+ *
+ * Returns `0` if the connection is established. Otherwise returns the error code
+ * and records the error for `libsql_php_last_error_message`.
+ * The connection is released with `libsql_php_close` and keeps the database open until then.
+ */
+int libsql_php_db_connect(void *db_ptr, struct LibsqlHandle **out_conn);
+
+/**
+ * Releases a database opened with `libsql_php_db_open`.
+ *
+ * Connections and pools made from the database stay usable; the database is closed
+ * once the last of them is released.
+ *
+ * # Safety
+ *
+ * This function takes ownership of the raw pointer, which must not be used afterwards.
+ *
+ * # Arguments
+ *
+ * * `db_ptr` - A raw pointer to the `LibsqlDatabase`.
+ *
+ * # Returns
+ *
+ * Returns `0` when the database is released, or the error code if the pointer is null.
+ */
+int libsql_php_db_close(void *db_ptr);
+
+/**
+ * Creates a bounded pool of connections to a database opened with `libsql_php_db_open`.
+ *
+ * # Safety
+ *
+ * This function is marked as unsafe because it dereferences raw pointers.
+ *
+ * # Arguments
+ *
+ * * `db_ptr` - A raw pointer to the `LibsqlDatabase`.
+ * * `max_size` - The maximum number of connections the pool opens.
+ * * `idle_timeout_secs` - How long, in seconds, a checked in connection is kept before it is closed.
+ *   `0` keeps idle connections until the pool is freed.
+ * * `out_pool` - A pointer where the raw pointer to the `ConnectionPool` is written.
+ *
+ * # Returns
+ *
+ * Returns `0` on success. Otherwise returns the error code and records the error for
+ * `libsql_php_last_error_message`. In-memory databases cannot be pooled, because every
+ * connection to them sees a separate database.
+ * The pool must be released with `libsql_php_pool_free`.
+ */
+int libsql_php_pool_new(void *db_ptr,
+                        unsigned int max_size,
+                        unsigned int idle_timeout_secs,
+                        struct ConnectionPool **out_pool);
+
+/**
+ * Checks a connection out of a pool, waiting for one to be checked in if the pool is at its max size.
+ *
+ * # Safety
+ *
+ * This function is marked as unsafe because it dereferences raw pointers.
+ *
+ * # Arguments
+ *
+ * * `pool_ptr` - A raw pointer to the `ConnectionPool`.
+ * * `timeout_ms` - How long, in milliseconds, to wait for a connection. `0` fails at once when none is available.
+ * * `out_conn` - A pointer where the raw pointer to the `LibsqlHandle` is written.
+ *
+ * # Returns
+ *
+ * Returns `0` on success. Otherwise returns the error code and records the error for
+ * `libsql_php_last_error_message`.
+ * The connection is used like any other `LibsqlHandle` and is given back with
+ * `libsql_php_pool_checkin`. Closing it with `libsql_php_close` instead frees its slot in the pool
+ * without reusing the connection.
+ * A reused connection gets the default retry policy and busy timeout back on checkin, but any other
+ * state an earlier caller set, such as pragmas run with `libsql_php_exec`, carries over.
+ */
+int libsql_php_pool_checkout(void *pool_ptr,
+                             unsigned int timeout_ms,
+                             struct LibsqlHandle **out_conn);
+
+/**
+ * Gives a connection checked out with `libsql_php_pool_checkout` back to its pool.
+ *
+ * A connection left inside a transaction is closed instead of being reused. The retry policy and
+ * busy timeout of a reused connection are reset to their defaults.
+ *
+ * # Safety
+ *
+ * This function takes ownership of the connection pointer, which must not be used afterwards.
+ *
+ * # Arguments
+ *
+ * * `pool_ptr` - A raw pointer to the `ConnectionPool`.
+ * * `client_ptr` - A raw pointer to the `LibsqlHandle` of the connection.
+ *
+ * # Returns
+ *
+ * Returns `0` on success. Otherwise returns the error code and records the error for
+ * `libsql_php_last_error_message`; the connection then still belongs to the caller.
+ */
+int libsql_php_pool_checkin(void *pool_ptr, void *client_ptr);
+
+/**
+ * Retrieves how many connections of a pool are idle and how many are checked out.
+ *
+ * # Safety
+ *
+ * This function is marked as unsafe because it dereferences raw pointers.
+ *
+ * # Arguments
+ *
+ * * `pool_ptr` - A raw pointer to the `ConnectionPool`.
+ * * `out_idle` - A pointer where the number of idle connections is written.
+ * * `out_in_use` - A pointer where the number of checked out connections is written.
+ *
+ * # Returns
+ *
+ * Returns `0` on success. If a pointer is null, returns the error code and records the error
+ * for `libsql_php_last_error_message`.
+ */
+int libsql_php_pool_stats(void *pool_ptr, unsigned int *out_idle, unsigned int *out_in_use);
+
+/**
+ * Closes the idle connections of a pool and releases it.
+ *
+ * Connections still checked out stay usable and are released with `libsql_php_close`.
+ *
+ * # Safety
+ *
+ * This function takes ownership of the raw pointer, which must not be used afterwards.
+ *
+ * # Arguments
+ *
+ * * `pool_ptr` - A raw pointer to the `ConnectionPool`.
+ *
+ * # Returns
+ *
+ * Returns `0` when the pool is released, or the error code if the pointer is null.
+ */
+int libsql_php_pool_free(void *pool_ptr);
+
+/**
+ * Synchronizes the remote replica database associated with the provided `LibsqlHandle`.
+ *
+ * A connection opened read-only by `libsql_php_connect_offline_replica` is switched to the replica
+ * once the sync succeeds.
+ *
+ * This function is marked with #[no_mangle] to ensure its symbol is preserved for
+ * use in external C code.
+ *
+ * # Safety
+ *
+ * This function manipulates raw pointers and interfaces with asynchronous code,
+ * requiring careful handling to ensure memory safety and avoid undefined behavior.
+ *
+ * # Arguments
+ *
+ * * `client_ptr` - A raw pointer to the `LibsqlHandle` of a remote replica connection.
+ * * `out_frame_no` - A pointer where the frame number the replica reached is written, or `-1`
+ *   if it has not replicated any frame yet. May be null.
+ * * `out_frames_applied` - A pointer where the number of frames applied by this sync is written.
+ *   May be null.
+ *
+ * # Returns
+ *
+ * * `0` - If synchronization is successful.
+ * * Otherwise the error code, with the error recorded for `libsql_php_last_error_message`.
+ *
+ * # Examples
+ *
+ * ```no_run
+ * use std::ptr;
+ * use sql_php_client::libsqlphp::sync::libsql_php_sync;
+ *
+ * let client_ptr: *mut std::ffi::c_void = ptr::null_mut();
+ * let result = unsafe { libsql_php_sync(client_ptr, ptr::null_mut(), ptr::null_mut()) };
+ * assert_ne!(result, 0);
+ * ```
+ */
+int libsql_php_sync(void *client_ptr, int64_t *out_frame_no, uint64_t *out_frames_applied);
+
+/**
+ * Synchronizes a remote replica until it has reached a frame number, such as the one
+ * returned for a write made through the primary.
+ *
+ * Like `libsql_php_sync`, a connection opened read-only by `libsql_php_connect_offline_replica` is
+ * switched to the replica once the frame has been reached.
+ *
+ * # Safety
+ *
+ * This function manipulates raw pointers and interfaces with asynchronous code,
+ * requiring careful handling to ensure memory safety and avoid undefined behavior.
+ *
+ * # Arguments
+ *
+ * * `client_ptr` - A raw pointer to the `LibsqlHandle` of a remote replica connection.
+ * * `frame_no` - The frame number to wait for.
+ * * `timeout_ms` - How long, in milliseconds, to keep syncing before giving up. A replica that has
+ *   already reached the frame returns without syncing, even with a timeout of `0`.
+ * * `out_frame_no` - A pointer where the frame number the replica reached is written, or `-1`
+ *   if it has not replicated any frame yet. May be null.
+ *
+ * # Returns
+ *
+ * Returns `0` once the frame has been reached. Otherwise returns the error code, after the
+ * timeout when the frame was not reached, and records the error for `libsql_php_last_error_message`.
+ */
+int libsql_php_sync_until(void *client_ptr,
+                          uint64_t frame_no,
+                          unsigned int timeout_ms,
+                          int64_t *out_frame_no);
+
+/**
+ * Retrieves the frame number a remote replica has committed locally, without contacting the primary.
+ *
+ * # Safety
+ *
+ * This function is marked as unsafe because it dereferences raw pointers.
+ *
+ * # Arguments
+ *
+ * * `client_ptr` - A raw pointer to the `LibsqlHandle` of a remote replica connection.
+ * * `out_frame_no` - A pointer where the frame number is written, or `-1` if the replica has not
+ *   replicated any frame yet.
+ *
+ * # Returns
+ *
+ * Returns `0` on success. If an error occurs, returns the error code and records the error for
+ * `libsql_php_last_error_message`.
+ */
+int libsql_php_replication_index(void *client_ptr,
+                                 int64_t *out_frame_no);
+
+/**
+ * Reports whether a remote replica is stale and how far behind its primary it is, without
+ * contacting the primary.
+ *
+ * The status reflects the syncs made through this extension: `libsql_php_sync`,
+ * `libsql_php_sync_until` and the background sync.
+ *
+ * # Safety
+ *
+ * This function is marked as unsafe because it dereferences raw pointers.
+ *
+ * # Arguments
+ *
+ * * `client_ptr` - A raw pointer to the `LibsqlHandle` of a remote replica connection.
+ * * `out_status` - A pointer to the `LibsqlPhpReplicaStatus` to fill in.
+ *
+ * # Returns
+ *
+ * Returns `0` on success. If an error occurs, returns the error code and records the error for
+ * `libsql_php_last_error_message`.
+ */
+int libsql_php_replica_status(void *client_ptr, struct LibsqlPhpReplicaStatus *out_status);
+
+/**
+ * Starts syncing a remote replica periodically on the shared runtime, replacing the background
+ * sync it already had.
+ *
+ * # Safety
+ *
+ * This function is marked as unsafe because it dereferences raw pointers. `callback` runs on a
+ * runtime thread, so it and `user_data` must be safe to use from another thread until the
+ * background sync is stopped or the connection is closed.
+ *
+ * # Arguments
  *
- * This struct is marked with #[repr(C)] to ensure its memory layout is compatible
- * with C code.
+ * * `client_ptr` - A raw pointer to the `LibsqlHandle` of a remote replica connection.
+ * * `interval_ms` - How long, in milliseconds, to wait between two syncs.
+ * * `timeout_ms` - How long, in milliseconds, each sync may take, or `0` for `interval_ms`.
+ * * `callback` - A function called after each sync, or a null pointer.
+ * * `user_data` - A pointer passed to `callback` as is.
+ *
+ * # Returns
+ *
+ * Returns `0` on success. If an error occurs, returns the error code and records the error for
+ * `libsql_php_last_error_message`.
  */
-typedef struct DbConnPair {
-  /**
-   * A raw pointer to the database object.
-   */
-  Database *db;
-  /**
-   * A raw pointer to the connection object.
-   */
-  Connection *conn;
-} DbConnPair;
+int libsql_php_background_sync_start(void *client_ptr,
+                                     unsigned int interval_ms,
+                                     unsigned int timeout_ms,
+                                     LibsqlPhpSyncCallback callback,
+                                     void *user_data);
 
 /**
- * Rolls back a transaction in a PHP extension.
+ * Stops the background sync of a remote replica. Stopping one that is not running does nothing.
+ *
+ * A callback that is being called is waited for, and none is called once this function returns,
+ * so its `user_data` can be freed. It must not be called from the callback itself.
  *
  * # Safety
  *
@@ -26,16 +1561,21 @@ typedef struct DbConnPair {
  *
  * # Arguments
  *
- * * `trx_ptr` - A raw pointer to the transaction object.
+ * * `client_ptr` - A raw pointer to the `LibsqlHandle` of a remote replica connection.
  *
  * # Returns
  *
- * Returns `1` if the transaction is successfully rolled back. If an error occurs or a null pointer is provided, returns `0`.
+ * Returns `0` on success. If an error occurs, returns the error code and records the error for
+ * `libsql_php_last_error_message`.
  */
-int64_t libsql_php_transaction_rollback(void *trx_ptr);
+int libsql_php_background_sync_stop(void *client_ptr);
 
 /**
- * Commits a transaction in a PHP extension.
+ * Reports whether the background sync of a remote replica is running, when the replica last
+ * synced, and how its latest syncs failed.
+ *
+ * The last sync time, error and failure count cover every sync of the replica, including the
+ * ones made with `libsql_php_sync` and `libsql_php_sync_until`.
  *
  * # Safety
  *
@@ -43,16 +1583,29 @@ int64_t libsql_php_transaction_rollback(void *trx_ptr);
  *
  * # Arguments
  *
- * * `trx_ptr` - A raw pointer to the transaction object.
+ * * `client_ptr` - A raw pointer to the `LibsqlHandle` of a remote replica connection.
+ * * `out_status` - A pointer to the `LibsqlPhpBackgroundSyncStatus` to fill in.
  *
  * # Returns
  *
- * Returns `1` if the transaction is successfully committed. If an error occurs or a null pointer is provided, returns `0`.
+ * Returns `0` on success. If an error occurs, returns the error code and records the error for
+ * `libsql_php_last_error_message`.
  */
-int64_t libsql_php_transaction_commit(void *trx_ptr);
+int libsql_php_background_sync_status(void *client_ptr,
+                                      struct LibsqlPhpBackgroundSyncStatus *out_status);
 
 /**
- * Executes a LibSQL query within a transaction in a PHP extension.
+ * Seeds a new remote replica from a local file, so the first sync only fetches the frames
+ * written after it instead of the whole database.
+ *
+ * The replica is opened afterwards with `libsql_php_connect_new_remote_replica` or
+ * `libsql_php_connect_offline_replica` on the same path and the primary the source came from,
+ * and continues syncing from the frame number stored in the source.
+ *
+ * A replica source is copied together with its WAL and replication index while holding its write
+ * lock, so syncs of the source wait for the copy and the copy fails if the source is syncing.
+ * Encrypted replicas cannot be read without their key, so they are rejected as a source.
+ * A snapshot source is applied to a new database; snapshots of encrypted databases are not supported.
  *
  * # Safety
  *
@@ -60,41 +1613,65 @@ int64_t libsql_php_transaction_commit(void *trx_ptr);
  *
  * # Arguments
  *
- * * `trx_ptr` - A raw pointer to the transaction object.
- * * `query` - A raw pointer to a C-style string representing the LibSQL query to execute.
- * * `query_params` - A pointer to an array of raw pointers to C-style strings representing query parameters.
- * * `query_params_len` - The length of the `query_params` array.
+ * * `path` - A pointer to a C-style string with the path of the replica to create.
+ * * `source` - A pointer to a C-style string with the path of the file to seed it from.
+ * * `source_kind` - `LIBSQL_PHP_SEED_REPLICA` or `LIBSQL_PHP_SEED_SNAPSHOT`.
+ * * `out_frame_no` - A pointer where the frame number the replica starts from is written, or `-1`
+ *   if the source has not replicated any frame. May be null.
  *
  * # Returns
  *
- * Returns a pointer to the transaction object if successful. If an error occurs or a null pointer is provided, returns a null pointer.
+ * Returns `0` on success. If an error occurs, including when a database already exists at `path`,
+ * returns the error code and records the error for `libsql_php_last_error_message`.
  */
-Transaction *libsql_php_transaction_exec(void *trx_ptr,
-                                         const char *query,
-                                         const char *const *query_params,
-                                         uintptr_t query_params_len);
+int libsql_php_bootstrap_replica(const char *path,
+                                 const char *source,
+                                 int source_kind,
+                                 int64_t *out_frame_no);
 
 /**
- * Initiates a LibSQL transaction in a PHP extension with the specified behavior.
+ * Establishes a connection to a remote LibSQL database over HTTP (Hrana), without a local replica.
  *
  * # Safety
  *
- * This function is marked as unsafe because it dereferences raw pointers.
+ * This function manipulates raw pointers and interfaces with asynchronous code,
+ * requiring careful handling to ensure memory safety and avoid undefined behavior.
  *
  * # Arguments
  *
- * * `client_ptr` - A raw pointer to the LibSQL connection client.
- * * `behavior` - A raw pointer to a C-style string representing the transaction behavior. Can be "DEFERRED", "WRITE", "READ", or null.
+ * * `url` - A pointer to a C-style string representing the URL of the database (`libsql://`, `https://` or `http://`).
+ * * `token` - A pointer to a C-style string representing the authentication token. May be empty.
+ * * `out_conn` - A pointer where the raw pointer to the `LibsqlHandle` is written.
  *
  * # Returns
  *
- * Returns a pointer to a memory location containing the transaction object if successful. If an error occurs or the client pointer is null, returns a null pointer.
+ * Returns `0` if the connection is successfully established. Otherwise returns the error code
+ * and records the error for `libsql_php_last_error_message`. The server is not contacted until
+ * the first statement runs, so an unreachable server is reported by that statement.
+ *
+ * # Examples
+ *
+ * ```no_run
+ * use std::ptr;
+ * use sql_php_client::libsqlphp::{connection_close::libsql_php_close, remote::libsql_php_connect_remote};
+ *
+ * let url = "http://127.0.0.1:8080\0".as_ptr().cast();
+ * let token = "\0".as_ptr().cast();
+ * let mut conn_ptr = ptr::null_mut();
+ * let status = unsafe { libsql_php_connect_remote(url, token, &mut conn_ptr) };
+ * assert_eq!(status, 0);
+ * unsafe { libsql_php_close(conn_ptr.cast()) };
+ * ```
  */
-Transaction *libsql_php_transaction(void *client_ptr,
-                                    const char *behavior);
+int libsql_php_connect_remote(const char *url,
+                              const char *token,
+                              struct LibsqlHandle **out_conn);
 
 /**
- * Retrieves the last inserted row ID from a SQL connection in a PHP extension.
+ * Connects to a new remote replica in a PHP extension.
+ *
+ * The replica syncs in the background every `sync_duration` seconds, which
+ * `libsql_php_background_sync_status` reports on.
  *
  * # Safety
  *
@@ -102,16 +1679,39 @@ Transaction *libsql_php_transaction(void *client_ptr,
  *
  * # Arguments
  *
- * * `client_ptr` - A raw pointer to the LibSQL connection client.
+ * * `path` - A pointer to a C-style string representing the path.
+ * * `url` - A pointer to a C-style string representing the URL.
+ * * `token` - A pointer to a C-style string representing the token.
+ * * `sync_duration` - The synchronization duration in seconds, or `0` for 5 seconds.
+ * * `read_your_writes` - An integer representing whether "read your writes" mode is enabled (1) or not (0).
+ * * `encryption` - A pointer to the `LibsqlPhpEncryption` settings of the local copy,
+ *   or a null pointer to keep it unencrypted.
+ * * `out_conn` - A pointer where the raw pointer to the `LibsqlHandle` is written.
  *
  * # Returns
  *
- * Returns the last inserted row ID as an integer. If an error occurs or the client pointer is null, returns `0`.
+ * Returns `0` on success. If an error occurs or null pointers are provided, returns the error code
+ * and records the error for `libsql_php_last_error_message`.
  */
-int64_t libsql_php_last_insert_rowid(void *client_ptr);
+int libsql_php_connect_new_remote_replica(const char *path,
+                                          const char *url,
+                                          const char *token,
+                                          uintptr_t sync_duration,
+                                          int read_your_writes,
+                                          const struct LibsqlPhpEncryption *encryption,
+                                          struct LibsqlHandle **out_conn);
 
 /**
- * Checks if autocommit is enabled for a LibSQL connection in a PHP extension.
+ * Connects to a remote replica that keeps working while its primary can't be reached.
+ *
+ * The primary is contacted once when the replica is opened. If it does not answer before the
+ * timeout, the connection reads the local replica file read-only instead, as long as the replica
+ * has synced before. Either way the replica keeps syncing in the background, and
+ * `libsql_php_replica_status` reports whether it is stale and how far behind it is.
+ *
+ * A connection that fell back to the local replica file stays read-only until `libsql_php_sync`
+ * or `libsql_php_sync_until` succeeds on it, which switches it to the replica so writes work again.
+ * Background syncs keep the file up to date but do not switch the connection.
  *
  * # Safety
  *
@@ -119,79 +1719,156 @@ int64_t libsql_php_last_insert_rowid(void *client_ptr);
  *
  * # Arguments
  *
- * * `client_ptr` - A raw pointer to the LibSQL connection client.
+ * * `path` - A pointer to a C-style string representing the path.
+ * * `url` - A pointer to a C-style string representing the URL.
+ * * `token` - A pointer to a C-style string representing the token.
+ * * `sync_duration` - The synchronization duration in seconds, or `0` for 5 seconds.
+ * * `read_your_writes` - An integer representing whether "read your writes" mode is enabled (1) or not (0).
+ * * `encryption` - A pointer to the `LibsqlPhpEncryption` settings of the local copy,
+ *   or a null pointer to keep it unencrypted.
+ * * `timeout_ms` - How long, in milliseconds, each attempt to reach the primary may take.
+ * * `out_conn` - A pointer where the raw pointer to the `LibsqlHandle` is written.
  *
  * # Returns
  *
- * Returns an integer representing whether autocommit is enabled (`1`) or not (`0`). If an error occurs or the client pointer is null, returns `0`.
+ * Returns `0` on success, including when the connection fell back to the local replica file.
+ * If the primary cannot be reached and the replica has never synced, returns
+ * `ErrorCode::ConnectionFailed` with a message starting with "Primary could not be reached".
+ * If another error occurs or null pointers are provided, returns the error code and records the error
+ * for `libsql_php_last_error_message`.
  */
-int64_t libsql_php_is_autocommit(void *client_ptr);
+int libsql_php_connect_offline_replica(const char *path,
+                                       const char *url,
+                                       const char *token,
+                                       uintptr_t sync_duration,
+                                       int read_your_writes,
+                                       const struct LibsqlPhpEncryption *encryption,
+                                       unsigned int timeout_ms,
+                                       struct LibsqlHandle **out_conn);
 
 /**
- * Resets a LibSQL connection in a PHP extension.
+ * Calls the `libsql_php_error` function if the provided client pointer is null,
+ * otherwise closes the LibSQL connection and its database, and deallocates the handle.
+ *
+ * A connection checked out of a pool is closed instead of being reused, and its slot is given back to the pool.
+ * Statements, cursors and transactions opened on the connection stay usable until they are freed with
+ * their own functions.
  *
  * # Safety
  *
- * This function is marked as unsafe because it dereferences raw pointers.
+ * This function dereferences raw pointers and requires proper handling to ensure
+ * memory safety and avoid undefined behavior.
  *
  * # Arguments
  *
- * * `client_ptr` - A raw pointer to the LibSQL connection client.
+ * * `client_ptr` - A raw pointer to the `LibsqlHandle` of the connection.
  *
  * # Returns
  *
- * Returns a pointer to a memory location indicating the success of the reset operation. If an error occurs or the client pointer is null, returns a null pointer.
+ * Returns `0` when the connection is closed, or the error code if the client pointer is null.
+ *
+ * # Examples
+ *
+ * ```no_run
+ * use std::ptr;
+ * use sql_php_client::libsqlphp::connection_close::libsql_php_close;
+ *
+ * let client_ptr: *mut std::ffi::c_void = ptr::null_mut();
+ * assert_ne!(unsafe { libsql_php_close(client_ptr) }, 0);
+ * ```
  */
-const int *libsql_php_reset(void *client_ptr);
+int libsql_php_close(void *client_ptr);
 
 /**
- * Executes a batch SQL query in a PHP extension, interfacing with a provided LibSQL connection.
+ * Retrieves how the database of a LibSQL connection was opened.
  *
  * # Safety
  *
- * This function is marked as unsafe because it dereferences raw pointers and performs FFI operations.
+ * This function is marked as unsafe because it dereferences raw pointers.
  *
  * # Arguments
  *
- * * `client_ptr` - A raw pointer to the LibSQL connection client.
- * * `query` - A raw pointer to a C-style string representing the batch SQL query to execute.
+ * * `client_ptr` - A raw pointer to the `LibsqlHandle` of the connection.
+ * * `out_mode` - A pointer where the `ConnectionMode` value is written:
+ *   `1` (local), `2` (memory), `3` (remote) or `4` (remote replica).
  *
  * # Returns
  *
- * Returns a pointer to a memory location indicating the success of the batch execution. If an error occurs, returns a null pointer.
+ * Returns `0` on success. If a pointer is null, returns the error code and records the error
+ * for `libsql_php_last_error_message`.
  */
-const int *libsql_php_execute_batch(void *client_ptr,
-                                    const char *query);
+int libsql_php_connection_mode(void *client_ptr, int *out_mode);
 
 /**
- * Executes a SQL query in a PHP extension, interfacing with a provided LibSQL connection.
+ * Retrieves the number of affected rows after executing a LibSQL query in a PHP extension.
  *
  * # Safety
  *
- * This function is marked as unsafe because it dereferences raw pointers and performs FFI operations.
+ * This function is marked as unsafe because it dereferences raw pointers.
  *
  * # Arguments
  *
- * * `client_ptr` - A raw pointer to the LibSQL connection client.
- * * `query` - A raw pointer to a C-style string representing the SQL query to execute.
- * * `query_params` - A pointer to an array of raw pointers to C-style strings representing query parameters.
- * * `query_params_len` - The length of the `query_params` array.
+ * * `client_ptr` - A raw pointer to the `LibsqlHandle` of the connection.
+ * * `out_rows` - A pointer where the number of affected rows is written.
  *
  * # Returns
  *
- * Returns a pointer to a memory location containing the number of rows affected by the query execution. If an error occurs, returns a null pointer.
+ * Returns `0` on success. If the client pointer or the output pointer is null, returns the error code
+ * and records the error for `libsql_php_last_error_message`.
  */
-const int64_t *libsql_php_exec(void *client_ptr,
-                               const char *query,
-                               const char *const *query_params,
-                               uintptr_t query_params_len);
+int libsql_php_affected_rows(void *client_ptr,
+                             uint64_t *out_rows);
 
 /**
- * Executes an LibSQL query using the provided client pointer, query string, and query parameters,
- * returning the query result in JSON format.
+ * Retrieves the version of the LibSQL library in use.
  *
- * This function is marked with #[no_mangle] to ensure its symbol is preserved for
- * use in external C code.
+ * # Returns
+ *
+ * Returns a pointer to a C-style string containing the version information of the LibSQL library.
+ * The string is owned by the library for its whole lifetime and must not be freed.
+ */
+const char *libsql_version(void);
+
+/**
+ * Frees a string returned by the library, such as the JSON written by `libsql_php_query`.
+ *
+ * Strings owned by a handle, like the rows of a `RowCursor` or the last error message, are
+ * freed with their owner and must not be passed here.
+ *
+ * # Safety
+ *
+ * This function is marked as unsafe because it takes ownership of a raw pointer.
+ * The string must not be used after this call.
+ *
+ * # Arguments
+ *
+ * * `ptr` - A pointer to the string, or a null pointer, which is ignored.
+ */
+void libsql_php_free_string(const char *ptr);
+
+/**
+ * Releases a transaction handle, rolling the transaction back if it was neither committed nor
+ * rolled back.
+ *
+ * # Safety
+ *
+ * This function is marked as unsafe because it takes ownership of a raw pointer.
+ * The handle must not be used after this call.
+ *
+ * # Arguments
+ *
+ * * `trx_ptr` - A raw pointer to the transaction handle returned by `libsql_php_transaction`.
+ *
+ * # Returns
+ *
+ * Returns `0` if the handle was released. If the transaction pointer is null, returns the error code
+ * and records the error for `libsql_php_last_error_message`.
+ */
+int libsql_php_transaction_free(void *trx_ptr);
+
+/**
+ * Executes a LibSQL query within a transaction, returning the query result in the JSON format of
+ * `libsql_php_query`.
  *
  * # Safety
  *
@@ -200,40 +1877,56 @@ const int64_t *libsql_php_exec(void *client_ptr,
  *
  * # Arguments
  *
- * * `client_ptr` - A raw pointer to the SQL connection client.
+ * * `trx_ptr` - A raw pointer to the transaction handle returned by `libsql_php_transaction`.
  * * `query` - A pointer to a null-terminated C string representing the SQL query.
- * * `query_params` - A pointer to an array of null-terminated C strings representing query parameters.
+ * * `query_params` - A pointer to an array of null-terminated C strings representing query parameters,
+ *   bound as text. A null or non-UTF-8 parameter is rejected with `InvalidParameter`.
  * * `query_params_len` - The number of query parameters in the array.
+ * * `out_json` - A pointer where the null-terminated C string holding the JSON-formatted query result is written.
+ *   Free it with `libsql_php_free_string`.
  *
  * # Returns
  *
- * A pointer to a null-terminated C string representing the JSON-formatted query result,
- * or a null pointer if an error occurs.
+ * Returns `0` on success. If an error occurs, returns the error code and records the error
+ * for `libsql_php_last_error_message`.
+ */
+int libsql_php_transaction_query(void *trx_ptr,
+                                 const char *query,
+                                 const char *const *query_params,
+                                 uintptr_t query_params_len,
+                                 const char **out_json);
+
+/**
+ * Executes a LibSQL query with typed positional parameters within a transaction, returning the query result in JSON format.
  *
- * # Examples
+ * # Safety
  *
- * ```
- * use std::ptr;
+ * This function manipulates raw pointers and interfaces with asynchronous code,
+ * requiring careful handling to ensure memory safety and avoid undefined behavior.
  *
- * let client_ptr: *mut std::ffi::c_void = ptr::null_mut();
- * let query = "SELECT * FROM table\0".as_ptr();
- * let query_params: *const *const std::os::raw::c_char = ptr::null();
- * let query_params_len = 0;
- * let result = libsql_php_query(client_ptr, query, query_params, query_params_len);
- * assert!(!result.is_null());
- * ```
+ * # Arguments
+ *
+ * * `trx_ptr` - A raw pointer to the transaction handle returned by `libsql_php_transaction`.
+ * * `query` - A pointer to a null-terminated C string representing the SQL query.
+ * * `values` - A pointer to an array of `LibsqlPhpValue` representing query parameters.
+ * * `values_len` - The number of query parameters in the array.
+ * * `out_json` - A pointer where the null-terminated C string holding the JSON-formatted query result is written.
+ *   Free it with `libsql_php_free_string`.
+ *
+ * # Returns
+ *
+ * Returns `0` on success. If an error occurs, returns the error code and records the error
+ * for `libsql_php_last_error_message`.
  */
-const char *libsql_php_query(void *client_ptr,
-                             const char *query,
-                             const char *const *query_params,
-                             uintptr_t query_params_len);
+int libsql_php_transaction_query_values(void *trx_ptr,
+                                        const char *query,
+                                        const struct LibsqlPhpValue *values,
+                                        uintptr_t values_len,
+                                        const char **out_json);
 
 /**
- * Establishes a connection to a local LibSQL database with optional encryption,
- * using the provided path, flags, and encryption key.
- *
- * This function is marked with #[no_mangle] to ensure its symbol is preserved for
- * use in external C code.
+ * Executes a LibSQL query with named parameters (`:name`, `@name` or `$name`) within a transaction, returning the query result
+ * in JSON format.
  *
  * # Safety
  *
@@ -242,111 +1935,159 @@ const char *libsql_php_query(void *client_ptr,
  *
  * # Arguments
  *
- * * `path` - A pointer to a null-terminated C string representing the path to the database.
- * * `flags` - A pointer to a null-terminated C string representing the flags for database opening.
- * * `encryption_key` - A pointer to a null-terminated C string representing the encryption key.
+ * * `trx_ptr` - A raw pointer to the transaction handle returned by `libsql_php_transaction`.
+ * * `query` - A pointer to a null-terminated C string representing the SQL query.
+ * * `names` - A pointer to an array of C-style strings holding the parameter names, with or without their prefix.
+ * * `values` - A pointer to an array of `LibsqlPhpValue` holding the value of each name.
+ * * `values_len` - The length of the `names` and `values` arrays.
+ * * `out_json` - A pointer where the null-terminated C string holding the JSON-formatted query result is written.
+ *   Free it with `libsql_php_free_string`.
  *
  * # Returns
  *
- * A raw pointer to a `Connection` object if the connection is successfully established,
- * otherwise a null pointer.
+ * Returns `0` on success. If an error occurs, returns the error code and records the error
+ * for `libsql_php_last_error_message`.
+ */
+int libsql_php_transaction_query_named(void *trx_ptr,
+                                       const char *query,
+                                       const char *const *names,
+                                       const struct LibsqlPhpValue *values,
+                                       uintptr_t values_len,
+                                       const char **out_json);
+
+/**
+ * Executes a batch of SQL statements within a transaction in a PHP extension.
  *
- * # Examples
+ * # Safety
  *
- * ```
- * use std::ptr;
+ * This function is marked as unsafe because it dereferences raw pointers and performs FFI operations.
  *
- * let path = "example_path\0".as_ptr();
- * let flags = "LIBSQLPHP_OPEN_READWRITE\0".as_ptr();
- * let encryption_key = "example_key\0".as_ptr();
- * let conn_ptr = libsql_php_connect_local(path, flags, encryption_key);
- * assert!(!conn_ptr.is_null());
- * ```
+ * # Arguments
+ *
+ * * `trx_ptr` - A raw pointer to the transaction handle returned by `libsql_php_transaction`.
+ * * `query` - A raw pointer to a C-style string representing the batch SQL query to execute.
+ *
+ * # Returns
+ *
+ * Returns `0` if the batch was executed. If an error occurs, returns the error code and records the error for `libsql_php_last_error_message`.
  */
-Connection *libsql_php_connect_local(const char *path,
-                                     const char *flags,
-                                     const char *encryption_key);
+int libsql_php_transaction_execute_batch(void *trx_ptr,
+                                         const char *query);
 
 /**
- * Synchronizes the remote replica database associated with the provided `DbConnPair`.
+ * Prepares a SQL statement within a transaction so it can be executed many times without re-parsing.
  *
- * This function is marked with #[no_mangle] to ensure its symbol is preserved for
- * use in external C code.
+ * The statement runs on the connection of the transaction, so its executions are part of the transaction
+ * until it is committed or rolled back.
  *
  * # Safety
  *
- * This function manipulates raw pointers and interfaces with asynchronous code,
- * requiring careful handling to ensure memory safety and avoid undefined behavior.
+ * This function is marked as unsafe because it dereferences raw pointers.
  *
  * # Arguments
  *
- * * `pair` - A raw pointer to a `DbConnPair` struct containing database and connection pointers.
+ * * `trx_ptr` - A raw pointer to the transaction handle returned by `libsql_php_transaction`.
+ * * `query` - A raw pointer to a C-style string representing the SQL statement to prepare.
+ * * `out_stmt` - A pointer where the prepared statement handle is written.
  *
  * # Returns
  *
- * * `0` - If synchronization is successful.
- * * `-1` - If the provided database pair pointer is null.
- * * `-2` - If there is an error during synchronization.
+ * Returns `0` on success. If an error occurs, returns the error code and records the error for `libsql_php_last_error_message`.
+ * The statement must be released with `libsql_php_stmt_finalize`.
+ */
+int libsql_php_transaction_prepare(void *trx_ptr,
+                                   const char *query,
+                                   struct PreparedStatement **out_stmt);
+
+/**
+ * Retrieves the number of rows affected by the last statement executed within a transaction.
  *
- * # Examples
+ * # Safety
  *
- * ```
- * use std::ptr;
+ * This function is marked as unsafe because it dereferences raw pointers.
  *
- * let pair: *mut DbConnPair = ptr::null_mut();
- * let result = libsql_php_sync(pair);
- * assert_eq!(result, -1);
- * ```
+ * # Arguments
+ *
+ * * `trx_ptr` - A raw pointer to the transaction handle returned by `libsql_php_transaction`.
+ * * `out_rows` - A pointer where the number of affected rows is written.
+ *
+ * # Returns
+ *
+ * Returns `0` on success. If the transaction pointer or the output pointer is null, or the transaction was already
+ * committed or rolled back, returns the error code and records the error for `libsql_php_last_error_message`.
  */
-int32_t libsql_php_sync(struct DbConnPair *pair);
+int libsql_php_transaction_changes(void *trx_ptr,
+                                   uint64_t *out_rows);
 
 /**
- * Connects to a new remote replica in a PHP extension.
+ * Retrieves the row ID of the last row inserted within a transaction.
+ *
+ * # Safety
+ *
+ * This function is marked as unsafe because it dereferences raw pointers.
  *
  * # Arguments
  *
- * * `path` - A pointer to a C-style string representing the path.
- * * `url` - A pointer to a C-style string representing the URL.
- * * `token` - A pointer to a C-style string representing the token.
- * * `sync_duration` - The synchronization duration in seconds.
- * * `read_your_writes` - An integer representing whether "read your writes" mode is enabled (1) or not (0).
+ * * `trx_ptr` - A raw pointer to the transaction handle returned by `libsql_php_transaction`.
+ * * `out_rowid` - A pointer where the last inserted row ID is written.
  *
  * # Returns
  *
- * Returns a pointer to a memory location containing a pair of database connection objects (`DbConnPair`). If an error occurs or null pointers are provided, returns a null pointer.
+ * Returns `0` on success. If the transaction pointer or the output pointer is null, or the transaction was already
+ * committed or rolled back, returns the error code and records the error for `libsql_php_last_error_message`.
  */
-struct DbConnPair *libsql_php_connect_new_remote_replica(const char *path,
-                                                         const char *url,
-                                                         const char *token,
-                                                         uintptr_t sync_duration,
-                                                         int read_your_writes);
+int libsql_php_transaction_last_insert_rowid(void *trx_ptr,
+                                             int64_t *out_rowid);
 
 /**
- * Calls the `libsql_php_error` function if the provided client pointer is null,
- * otherwise closes the LibSQL connection and deallocates memory.
+ * Opens a savepoint inside a transaction, nested in the innermost savepoint already open.
  *
  * # Safety
  *
- * This function dereferences raw pointers and requires proper handling to ensure
- * memory safety and avoid undefined behavior.
+ * This function is marked as unsafe because it dereferences raw pointers.
  *
  * # Arguments
  *
- * * `client_ptr` - A raw pointer to the LibSQL connection client.
+ * * `trx_ptr` - A raw pointer to the transaction handle returned by `libsql_php_transaction`.
+ * * `name` - A raw pointer to a C-style string holding the name of the savepoint. If it is null or empty,
+ *   a unique name is generated.
+ * * `out_name` - A pointer where the name of the savepoint is written, or null. Free it with
+ *   `libsql_php_free_string`.
  *
- * # Examples
+ * # Returns
  *
- * ```
- * use std::ptr;
+ * Returns `0` if the savepoint was opened. If an error occurs, a null pointer is provided or the transaction
+ * was already committed or rolled back, returns the error code and records the error for
+ * `libsql_php_last_error_message`.
+ */
+int libsql_php_transaction_savepoint(void *trx_ptr,
+                                     const char *name,
+                                     const char **out_name);
+
+/**
+ * Releases a savepoint, keeping its changes in the transaction and closing every savepoint opened inside it.
  *
- * let client_ptr: *mut std::ffi::c_void = ptr::null_mut();
- * libsql_php_close(client_ptr);
- * ```
+ * # Safety
+ *
+ * This function is marked as unsafe because it dereferences raw pointers.
+ *
+ * # Arguments
+ *
+ * * `trx_ptr` - A raw pointer to the transaction handle returned by `libsql_php_transaction`.
+ * * `name` - A raw pointer to a C-style string holding the name of the savepoint, or null for the innermost one.
+ *
+ * # Returns
+ *
+ * Returns `0` if the savepoint was released. If no open savepoint has the name, an error occurs, a null pointer
+ * is provided or the transaction was already committed or rolled back, returns the error code and records the
+ * error for `libsql_php_last_error_message`.
  */
-void libsql_php_close(void *client_ptr);
+int libsql_php_transaction_release(void *trx_ptr,
+                                   const char *name);
 
 /**
- * Retrieves the number of affected rows after executing a LibSQL query in a PHP extension.
+ * Rolls a transaction back to a savepoint, undoing its changes and closing every savepoint opened inside it.
+ * The savepoint itself stays open.
  *
  * # Safety
  *
@@ -354,19 +2095,142 @@ void libsql_php_close(void *client_ptr);
  *
  * # Arguments
  *
- * * `client_ptr` - A raw pointer to the SQL connection client.
+ * * `trx_ptr` - A raw pointer to the transaction handle returned by `libsql_php_transaction`.
+ * * `name` - A raw pointer to a C-style string holding the name of the savepoint, or null for the innermost one.
  *
  * # Returns
  *
- * Returns a pointer to a memory location containing the number of affected rows. If an error occurs or the client pointer is null, returns a null pointer.
+ * Returns `0` if the transaction was rolled back to the savepoint. If no open savepoint has the name, an error
+ * occurs, a null pointer is provided or the transaction was already committed or rolled back, returns the error
+ * code and records the error for `libsql_php_last_error_message`.
  */
-const uint64_t *libsql_php_affected_rows(void *client_ptr);
+int libsql_php_transaction_rollback_to(void *trx_ptr,
+                                       const char *name);
 
 /**
- * Retrieves the version of the LibSQL library in use.
+ * Retrieves how many savepoints are open inside a transaction.
+ *
+ * # Safety
+ *
+ * This function is marked as unsafe because it dereferences raw pointers.
+ *
+ * # Arguments
+ *
+ * * `trx_ptr` - A raw pointer to the transaction handle returned by `libsql_php_transaction`.
+ * * `out_depth` - A pointer where the number of open savepoints is written.
  *
  * # Returns
  *
- * Returns a pointer to a C-style string containing the version information of the LibSQL library.
+ * Returns `0` on success. If the transaction pointer or the output pointer is null, returns the error code
+ * and records the error for `libsql_php_last_error_message`.
  */
-const char *libsql_version(void);
+int libsql_php_transaction_savepoint_depth(void *trx_ptr,
+                                           uint32_t *out_depth);
+
+/**
+ * Sets how a connection retries statements that failed with a transient error.
+ *
+ * The policy applies to statements executed and queried on the connection and to starting
+ * transactions. Batches and statements run inside a transaction are not retried, because running
+ * them again could repeat work that already succeeded. For the same reason, remote requests are only
+ * retried when their connection could not be opened.
+ *
+ * # Safety
+ *
+ * This function is marked as unsafe because it dereferences raw pointers.
+ *
+ * # Arguments
+ *
+ * * `client_ptr` - A raw pointer to the `LibsqlHandle` of the connection.
+ * * `policy` - A pointer to the `LibsqlPhpRetryPolicy` to use, or a null pointer to stop retrying.
+ *
+ * # Returns
+ *
+ * Returns `0` on success. If the client pointer is null or the policy is invalid, returns the error
+ * code and records the error for `libsql_php_last_error_message`.
+ */
+int libsql_php_set_retry_policy(void *client_ptr,
+                                const struct LibsqlPhpRetryPolicy *policy);
+
+/**
+ * Retrieves how a connection retries statements that failed with a transient error.
+ *
+ * # Safety
+ *
+ * This function is marked as unsafe because it dereferences raw pointers.
+ *
+ * # Arguments
+ *
+ * * `client_ptr` - A raw pointer to the `LibsqlHandle` of the connection.
+ * * `out_policy` - A pointer where the `LibsqlPhpRetryPolicy` of the connection is written.
+ *
+ * # Returns
+ *
+ * Returns `0` on success. If the client pointer or the output pointer is null, returns the error code
+ * and records the error for `libsql_php_last_error_message`.
+ */
+int libsql_php_retry_policy(void *client_ptr,
+                            struct LibsqlPhpRetryPolicy *out_policy);
+
+/**
+ * Sets how long SQLite waits for a lock held by another connection before a statement fails with
+ * `SQLITE_BUSY`, using `PRAGMA busy_timeout`.
+ *
+ * Remote connections are not supported, because the server manages its own locks. Remote replica
+ * connections are not supported either, because they do not run pragmas that set a value.
+ *
+ * # Safety
+ *
+ * This function is marked as unsafe because it dereferences raw pointers.
+ *
+ * # Arguments
+ *
+ * * `client_ptr` - A raw pointer to the `LibsqlHandle` of the connection.
+ * * `timeout_ms` - How long to wait, in milliseconds, or `0` to fail at once.
+ *
+ * # Returns
+ *
+ * Returns `0` on success. If an error occurs, returns the error code and records the error for
+ * `libsql_php_last_error_message`.
+ */
+int libsql_php_busy_timeout(void *client_ptr, unsigned int timeout_ms);
+
+/**
+ * Executes a batch of statements, each with its own typed parameters, returning the result of every
+ * statement in JSON format.
+ *
+ * The statements run in order and the batch stops at the first one that fails. An atomic batch runs
+ * inside a transaction that is rolled back when a statement fails, so either every statement is applied
+ * or none is. A batch that is not atomic keeps the changes of the statements that ran before the failure,
+ * and still reports their results. On a remote connection, each statement of a batch that is not atomic
+ * runs in its own transaction.
+ *
+ * # Safety
+ *
+ * This function manipulates raw pointers and interfaces with asynchronous code,
+ * requiring careful handling to ensure memory safety and avoid undefined behavior.
+ *
+ * # Arguments
+ *
+ * * `client_ptr` - A raw pointer to the `LibsqlHandle` of the connection.
+ * * `statements` - A pointer to an array of `LibsqlPhpBatchStatement` holding the statements to run.
+ * * `statements_len` - The number of statements in the array.
+ * * `atomic` - Whether the statements run inside a transaction.
+ * * `out_json` - A pointer where the null-terminated C string holding the JSON-formatted results is written:
+ *   an array with the `rows_affected`, `last_insert_rowid`, `columns` and `rows` of every statement that
+ *   ran and was kept. It is written even when a statement of a batch that is not atomic fails, and is set
+ *   to null otherwise. Free it with `libsql_php_free_string`.
+ * * `out_failed` - A pointer where the 1-based number of the statement that failed is written, or `0` when
+ *   none did. May be null.
+ *
+ * # Returns
+ *
+ * Returns `0` if every statement ran. If an error occurs, returns the error code and records the error
+ * for `libsql_php_last_error_message`, prefixed with the 1-based number of the statement that failed.
+ */
+int libsql_php_batch(void *client_ptr,
+                     const struct LibsqlPhpBatchStatement *statements,
+                     uintptr_t statements_len,
+                     bool atomic,
+                     const char **out_json,
+                     uintptr_t *out_failed);
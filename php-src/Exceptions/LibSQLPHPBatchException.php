@@ -0,0 +1,53 @@
+<?php
+
+namespace Darkterminal\LibSQLPHPExtension\Exceptions;
+
+use Darkterminal\LibSQLPHPExtension\Responses\LibSQLPHPClientResult;
+
+/**
+ * Represents a batch that stopped at a failing statement.
+ */
+class LibSQLPHPBatchException extends LibSQLPHPException
+{
+    /**
+     * Constructor.
+     *
+     * @param string $message The error message reported by the native library.
+     * @param int $code The LibSQLPHP error code.
+     * @param int $extendedCode The SQLite extended result code, or 0 if the error did not come from SQLite.
+     * @param LibSQLPHPClientResult[] $results The results of the statements that ran and were kept.
+     * @param int $failedStatement The 1-based number of the statement that failed, or 0 if the batch failed
+     *                             outside of a statement.
+     */
+    public function __construct(
+        string $message,
+        int $code = 0,
+        int $extendedCode = 0,
+        protected array $results = [],
+        protected int $failedStatement = 0
+    ) {
+        parent::__construct($message, $code, $extendedCode);
+    }
+
+    /**
+     * Get the results of the statements that ran before the failure.
+     *
+     * An atomic batch is rolled back when a statement fails, so it never has results.
+     *
+     * @return LibSQLPHPClientResult[] The results, in order.
+     */
+    public function getResults(): array
+    {
+        return $this->results;
+    }
+
+    /**
+     * Get the number of the statement that failed.
+     *
+     * @return int The 1-based number of the statement, or 0 if the batch failed outside of a statement.
+     */
+    public function getFailedStatement(): int
+    {
+        return $this->failedStatement;
+    }
+}
@@ -0,0 +1,59 @@
+<?php
+
+namespace Darkterminal\LibSQLPHPExtension\Exceptions;
+
+use FFI;
+
+/**
+ * Represents an error reported by the LibSQLPHP native library.
+ */
+class LibSQLPHPException extends \Exception
+{
+    /**
+     * Constructor.
+     *
+     * @param string $message The error message reported by the native library.
+     * @param int $code The LibSQLPHP error code.
+     * @param int $extendedCode The SQLite extended result code, or 0 if the error did not come from SQLite.
+     */
+    public function __construct(
+        string $message,
+        int $code = 0,
+        protected int $extendedCode = 0
+    ) {
+        parent::__construct($message, $code);
+    }
+
+    /**
+     * Get the SQLite extended result code of the error.
+     *
+     * @return int The SQLite extended result code, or 0 if the error did not come from SQLite.
+     */
+    public function getExtendedCode(): int
+    {
+        return $this->extendedCode;
+    }
+
+    /**
+     * Throw the last error recorded by the native library when a call did not succeed.
+     *
+     * @param FFI $ffi The FFI instance.
+     * @param int $status The status returned by the native function.
+     *
+     * @return void
+     *
+     * @throws LibSQLPHPException If the status is not 0.
+     */
+    public static function check(FFI $ffi, int $status): void
+    {
+        if ($status === 0) {
+            return;
+        }
+
+        throw new self(
+            $ffi->libsql_php_last_error_message() ?? "Unknown LibSQLPHP error",
+            $status,
+            $ffi->libsql_php_last_error_extended_code()
+        );
+    }
+}
@@ -10,12 +10,15 @@
 
 namespace Darkterminal\LibSQLPHPExtension;
 
+use Darkterminal\LibSQLPHPExtension\Exceptions\LibSQLPHPBatchException;
+use Darkterminal\LibSQLPHPExtension\Exceptions\LibSQLPHPException;
 use Darkterminal\LibSQLPHPExtension\Responses\LibSQLPHPClientResult;
 use Darkterminal\LibSQLPHPExtension\Responses\LibSQLPHPResult;
 use Darkterminal\LibSQLPHPExtension\Responses\LibSQLPHPStmt;
 use Darkterminal\LibSQLPHPExtension\Responses\Transaction;
-use Darkterminal\LibSQLPHPExtension\Utils\QueryParams;
+use Darkterminal\LibSQLPHPExtension\Utils\Encryption;
 use Darkterminal\LibSQLPHPExtension\Utils\TransactionBehavior;
+use Darkterminal\LibSQLPHPExtension\Utils\TypedParams;
 
 /**
  * LibSQLPHP class.
@@ -50,8 +53,6 @@ class LibSQLPHP
      */
     public string $connection_mode;
 
-    protected $dbPair;
-
     /**
      * Constructor.
      * 
@@ -85,16 +86,28 @@ class LibSQLPHP
      * // Full option
      * $db = new LibSQLPHP(path: "file:database.db", url: $url, token: $token, sync_interval: 10, read_your_writes: true);
      * ```
+     * 
+     * ## Example Remote Connection
+     * 
+     * ```
+     * $db = new LibSQLPHP(url: "libsql://database.turso.io", token: $token);
+     * ```
      *
      * @param string $path **(Local/Remote Replica)** Path to the database file.
      * @param int $flags **(Local)** Flags to control database opening mode. Default: LIBSQLPHP_OPEN_READWRITE | LIBSQLPHP_OPEN_CREATE
      * @param string $encryptionKey **(Local/Remote Replica)** Encryption key for database (if applicable).
-     * @param string $url **(Remote Replica)** Base URL for HTTP connection (if applicable).
-     * @param string $token **(Remote Replica)** Authentication token for HTTP connection (if applicable).
+     * @param string $url **(Remote/Remote Replica)** Base URL for HTTP connection (if applicable).
+     * @param string $token **(Remote/Remote Replica)** Authentication token for HTTP connection (if applicable).
      * @param int $sync_interval **(Remote Replica)** Database sync duration in seconds (if applicable).
      * @param bool $read_your_writes **(Remote Replica)** Enable read-your-writes consistency (if applicable).
+     * @param int $cipher **(Local/Remote Replica)** The `LIBSQLPHP_CIPHER_*` cipher of an encrypted database. Default: LIBSQLPHP_CIPHER_AES256CBC
+     * @param int $key_format **(Local/Remote Replica)** The `LIBSQLPHP_KEY_*` format of the encryption key. Default: LIBSQLPHP_KEY_RAW
+     * @param int $offline_timeout_ms **(Remote Replica)** When above 0, how long in milliseconds to wait for the server before reading the local replica file read-only instead. Default: 0, always connect to the server
+     * @param string $seed **(Remote Replica)** A file to seed the replica from when `$path` does not exist yet, so the first sync does not download the whole database. It must not be encrypted.
+     * @param int $seed_kind **(Remote Replica)** `LIBSQLPHP_SEED_REPLICA` if `$seed` is the file of another replica, or `LIBSQLPHP_SEED_SNAPSHOT` if it is a snapshot. Default: LIBSQLPHP_SEED_REPLICA
      *
-     * @throws \Exception If invalid flags are provided or if LibSQLPHP definition and extension files do not exist.
+     * @throws \Exception If LibSQLPHP definition and extension files do not exist.
+     * @throws LibSQLPHPException If the flags are an invalid combination or the connection cannot be established.
      */
     public function __construct(
         string $path = "",
@@ -103,13 +116,13 @@ class LibSQLPHP
         string $url = "",
         string $token = "",
         int $sync_interval = 5,
-        bool $read_your_writes = true
+        bool $read_your_writes = true,
+        int $cipher = LIBSQLPHP_CIPHER_AES256CBC,
+        int $key_format = LIBSQLPHP_KEY_RAW,
+        int $offline_timeout_ms = 0,
+        string $seed = "",
+        int $seed_kind = LIBSQLPHP_SEED_REPLICA
     ) {
-        // Check if provided flags are allowed
-        if ($this->checkFlags($flags) === false) {
-            throw new \Exception("LibSQLPHP Flags are not allowed to be used");
-        }
-
         if (!file_exists(__DIR__ . '/libsql_php.def') && !file_exists(__DIR__ . '/../libs/libsql_php_client.so')) {
             throw new \Exception("LibSQLPHP definition and extension is not exits!");
         }
@@ -121,7 +134,7 @@ class LibSQLPHP
         );
 
         // Open the database
-        $this->open($path, $flags, $encryptionKey, $url, $token, $sync_interval, $read_your_writes);
+        $this->open($path, $flags, $encryptionKey, $url, $token, $sync_interval, $read_your_writes, $cipher, $key_format, $offline_timeout_ms, $seed, $seed_kind);
     }
 
     /**
@@ -152,16 +165,28 @@ class LibSQLPHP
      * // Full option
      * $db = new LibSQLPHP(path: "file:database.db", url: $url, token: $token, sync_interval: 10, read_your_writes: true);
      * ```
+     * 
+     * ## Example Remote Connection
+     * 
+     * ```
+     * $db = new LibSQLPHP(url: "libsql://database.turso.io", token: $token);
+     * ```
      *
      * @param string $path **(Local/Remote Replica)** Path to the database file.
      * @param int $flags **(Local)** Flags to control database opening mode. Default: LIBSQLPHP_OPEN_READWRITE | LIBSQLPHP_OPEN_CREATE
      * @param string $encryptionKey **(Local/Remote Replica)** Encryption key for database (if applicable).
-     * @param string $url **(Remote Replica)** Base URL for HTTP connection (if applicable).
-     * @param string $token **(Remote Replica)** Authentication token for HTTP connection (if applicable).
+     * @param string $url **(Remote/Remote Replica)** Base URL for HTTP connection (if applicable).
+     * @param string $token **(Remote/Remote Replica)** Authentication token for HTTP connection (if applicable).
      * @param int $sync_interval **(Remote Replica)** Database sync duration in seconds (if applicable).
      * @param bool $read_your_writes **(Remote Replica)** Enable read-your-writes consistency (if applicable).
+     * @param int $cipher **(Local/Remote Replica)** The `LIBSQLPHP_CIPHER_*` cipher of an encrypted database. Default: LIBSQLPHP_CIPHER_AES256CBC
+     * @param int $key_format **(Local/Remote Replica)** The `LIBSQLPHP_KEY_*` format of the encryption key. Default: LIBSQLPHP_KEY_RAW
+     * @param int $offline_timeout_ms **(Remote Replica)** When above 0, how long in milliseconds to wait for the server before reading the local replica file read-only instead. Default: 0, always connect to the server
+     * @param string $seed **(Remote Replica)** A file to seed the replica from when `$path` does not exist yet, so the first sync does not download the whole database. It must not be encrypted.
+     * @param int $seed_kind **(Remote Replica)** `LIBSQLPHP_SEED_REPLICA` if `$seed` is the file of another replica, or `LIBSQLPHP_SEED_SNAPSHOT` if it is a snapshot. Default: LIBSQLPHP_SEED_REPLICA
      *
-     * @throws \Exception If invalid flags are provided or if LibSQLPHP definition and extension files do not exist.
+     * @throws \Exception If LibSQLPHP definition and extension files do not exist.
+     * @throws LibSQLPHPException If the connection cannot be established.
      */
     public function open(
         string $path,
@@ -170,34 +195,93 @@ class LibSQLPHP
         string $url = "",
         string $token = "",
         int $sync_interval = 0,
-        bool $read_your_writes = true
+        bool $read_your_writes = true,
+        int $cipher = LIBSQLPHP_CIPHER_AES256CBC,
+        int $key_format = LIBSQLPHP_KEY_RAW,
+        int $offline_timeout_ms = 0,
+        string $seed = "",
+        int $seed_kind = LIBSQLPHP_SEED_REPLICA
     ): void {
-        // Check if provided flags are allowed
-        if ($this->checkFlags($flags) === false) {
-            throw new \Exception("LibSQLPHP Flags are not allowed to be used");
+        $conn = $this->checkConnectionMode($path, $url, $token);
+        if ($conn === false || !in_array($conn['mode'], ['local', 'memory', 'remote', 'remote_replica'])) {
+            throw new \Exception("Error: Connection failed available mode: Local, in-memory, remote or remote replica");
         }
 
-        $conn = $this->checkConnectionMode($path, $url, $token);
-        if ($conn === false || !in_array($conn['mode'], ['local', 'memory', 'remote_replica'])) {
-            throw new \Exception("Error: Connection failed available mode: Local or in-momory");
+        if ($conn['mode'] === "remote_replica" && $seed !== "" && !file_exists($path)) {
+            LibSQLPHPException::check($this->ffi, $this->ffi->libsql_php_bootstrap_replica($path, $seed, $seed_kind, null));
         }
 
-        if ($conn['mode'] !== "remote_replica") {
-            $this->db = $this->ffi->libsql_php_connect_local($conn['uri'], $this->checkFlags($flags), $encryptionKey);
-            $this->is_connected = ($this->db) ? true : false;
-        } else {
-            $this->dbPair = $this->ffi->libsql_php_connect_new_remote_replica(
-                $path,
-                $url,
-                $token,
-                (int) $sync_interval,
-                (int) $read_your_writes
-            );
-            $this->db = $this->dbPair->conn;
-            $this->is_connected = ($this->dbPair->conn) ? true : false;
+        $encryption = new Encryption($this->ffi, $encryptionKey === "" ? null : $encryptionKey, $cipher, $key_format);
+
+        try {
+            if ($conn['mode'] === "remote") {
+                $this->db = $this->ffi->new("void*");
+                LibSQLPHPException::check($this->ffi, $this->ffi->libsql_php_connect_remote(
+                    $conn['url'],
+                    $conn['token'],
+                    \FFI::addr($this->db)
+                ));
+                $this->is_connected = true;
+            } else if ($conn['mode'] !== "remote_replica") {
+                $this->db = $this->ffi->new("void*");
+                LibSQLPHPException::check($this->ffi, $this->ffi->libsql_php_connect_local(
+                    $conn['uri'],
+                    $flags,
+                    $encryption->getData(),
+                    \FFI::addr($this->db)
+                ));
+                $this->is_connected = true;
+            } else if ($offline_timeout_ms > 0) {
+                $this->db = $this->ffi->new("void*");
+                LibSQLPHPException::check($this->ffi, $this->ffi->libsql_php_connect_offline_replica(
+                    $path,
+                    $url,
+                    $token,
+                    (int) $sync_interval,
+                    (int) $read_your_writes,
+                    $encryption->getData(),
+                    $offline_timeout_ms,
+                    \FFI::addr($this->db)
+                ));
+                $this->is_connected = true;
+            } else {
+                $this->db = $this->ffi->new("void*");
+                LibSQLPHPException::check($this->ffi, $this->ffi->libsql_php_connect_new_remote_replica(
+                    $path,
+                    $url,
+                    $token,
+                    (int) $sync_interval,
+                    (int) $read_your_writes,
+                    $encryption->getData(),
+                    \FFI::addr($this->db)
+                ));
+                $this->is_connected = true;
+            }
+        } finally {
+            $encryption->free();
         }
     }
 
+    /**
+     * Wrap a connection handle opened elsewhere, such as one checked out of a `LibSQLPHPPool`.
+     *
+     * @param \FFI $ffi The FFI instance the handle was opened with.
+     * @param mixed $db The connection handle.
+     * @param string $connection_mode The connection mode of the handle.
+     *
+     * @return static The connection.
+     */
+    public static function fromHandle(\FFI $ffi, $db, string $connection_mode = 'local'): static
+    {
+        $instance = (new \ReflectionClass(static::class))->newInstanceWithoutConstructor();
+        $instance->ffi = $ffi;
+        $instance->db = $db;
+        $instance->connection_mode = $connection_mode;
+        $instance->is_connected = true;
+
+        return $instance;
+    }
+
     /**
      * Checks whether the database connection is in autocommit mode.
      * 
@@ -212,7 +296,9 @@ class LibSQLPHP
      */
     public function is_autocommit(): bool
     {
-        return $this->ffi->libsql_php_is_autocommit($this->db);
+        $autocommit = $this->ffi->new("int");
+        LibSQLPHPException::check($this->ffi, $this->ffi->libsql_php_is_autocommit($this->db, \FFI::addr($autocommit)));
+        return $autocommit->cdata === 1;
     }
 
     /**
@@ -250,7 +336,9 @@ class LibSQLPHP
     public function close(): void
     {
         if ($this->db) {
-            $this->ffi->libsql_php_close($this->db);
+            LibSQLPHPException::check($this->ffi, $this->ffi->libsql_php_close($this->db));
+            $this->db = null;
+            $this->is_connected = false;
         }
     }
 
@@ -267,24 +355,71 @@ class LibSQLPHP
      * @param array $params The SQL statement to parameters.
      *
      * @return LibSQLPHPResult The result of the query.
+     *
+     * @throws LibSQLPHPException If the query fails.
      */
     public function query(string $stmt, array $params = []): LibSQLPHPResult
     {
-        if (is_array_assoc($params)) {
-            $queryParams = new QueryParams([]);
-            $intoParams = intoParams($stmt, $params);
-            var_dump($intoParams);
-            $data = $this->ffi->libsql_php_query($this->db, $intoParams, $queryParams->getData(), $queryParams->getLength());
-            $queryParams->freeParams();
-        } else {
-            $queryParams = new QueryParams($params);
-            $data = $this->ffi->libsql_php_query($this->db, $stmt, $queryParams->getData(), $queryParams->getLength());
-            $queryParams->freeParams();
-        }
-        $object = json_decode($data, true);
+        $queryParams = new TypedParams($this->ffi, $params);
+        $data = $this->ffi->new("const char*");
+        $status = $queryParams->isNamed()
+            ? $this->ffi->libsql_php_query_named($this->db, $stmt, $queryParams->getNames(), $queryParams->getData(), $queryParams->getLength(), \FFI::addr($data))
+            : $this->ffi->libsql_php_query_values($this->db, $stmt, $queryParams->getData(), $queryParams->getLength(), \FFI::addr($data));
+        $queryParams->freeParams();
+        LibSQLPHPException::check($this->ffi, $status);
+
+        $object = json_decode(\FFI::string($data), true);
+        $this->ffi->libsql_php_free_string($data);
         return new LibSQLPHPResult($this->ffi, $this->db, $object);
     }
 
+    /**
+     * Execute a query and read its rows one at a time.
+     *
+     * Rows are fetched from the database as the generator advances, so large results
+     * can be iterated in constant memory.
+     *
+     * **Example**
+     *
+     * ```
+     * foreach ($db->cursor("SELECT * FROM users") as $row) {
+     *     echo $row['name'] . PHP_EOL;
+     * }
+     * ```
+     *
+     * @param string $stmt The SQL statement to execute.
+     * @param array $params The SQL statement to parameters.
+     *
+     * @return \Generator<array> The rows, keyed by column name.
+     *
+     * @throws LibSQLPHPException If the query fails.
+     */
+    public function cursor(string $stmt, array $params = []): \Generator
+    {
+        $queryParams = new TypedParams($this->ffi, $params);
+        $rows = $this->ffi->new("void*");
+        $status = $this->ffi->libsql_php_query_open($this->db, $stmt, $queryParams->getNames(), $queryParams->getData(), $queryParams->getLength(), \FFI::addr($rows));
+        $queryParams->freeParams();
+        LibSQLPHPException::check($this->ffi, $status);
+
+        try {
+            $data = $this->ffi->new("const char*");
+            LibSQLPHPException::check($this->ffi, $this->ffi->libsql_php_rows_columns($rows, \FFI::addr($data)));
+            $columns = array_column(json_decode(\FFI::string($data), true), 'name');
+
+            while (true) {
+                LibSQLPHPException::check($this->ffi, $this->ffi->libsql_php_rows_next($rows, \FFI::addr($data)));
+                if (\FFI::isNull($data)) {
+                    break;
+                }
+
+                yield array_combine($columns, array_map([LibSQLPHPResult::class, 'decodeValue'], json_decode(\FFI::string($data), true)));
+            }
+        } finally {
+            $this->ffi->libsql_php_rows_free($rows);
+        }
+    }
+
     /**
      * Execute a query and retrieve a single result from the database.
      * 
@@ -307,12 +442,7 @@ class LibSQLPHP
     {
         $end = substr($stmt, -5);
         if (strpos($stmt, 'WHERE') !== false && $end !== 'WHERE') {
-            $queryParams = new QueryParams($params);
-            $data = $this->ffi->libsql_php_query($this->db, $stmt, $queryParams->getData(), $queryParams->getLength());
-            $queryParams->freeParams();
-
-            $object = json_decode($data, true);
-            $result = new LibSQLPHPResult($this->ffi, $this->db, $object);
+            $result = $this->query($stmt, $params);
             $handle = current($result->fetchArray(LIBSQLPHP_ASSOC));
             $arr = array_map(fn ($value) => $value, array_values($handle));
 
@@ -351,10 +481,14 @@ class LibSQLPHP
      * @param string $query The SQL query to prepare.
      *
      * @return LibSQLPHPStmt A prepared statement object.
+     *
+     * @throws LibSQLPHPException If the statement cannot be prepared.
      */
     public function prepare(string $query): LibSQLPHPStmt
     {
-        return new LibSQLPHPStmt($this->ffi, $this->db, $query);
+        $stmt = $this->ffi->new("void*");
+        LibSQLPHPException::check($this->ffi, $this->ffi->libsql_php_prepare($this->db, $query, \FFI::addr($stmt)));
+        return new LibSQLPHPStmt($this->ffi, $this->db, $query, $stmt);
     }
 
     /**
@@ -373,8 +507,9 @@ class LibSQLPHP
      */
     public function changes(): int
     {
-        $row_affected = $this->ffi->libsql_php_affected_rows($this->db);
-        return $row_affected[0];
+        $row_affected = $this->ffi->new("uint64_t");
+        LibSQLPHPException::check($this->ffi, $this->ffi->libsql_php_affected_rows($this->db, \FFI::addr($row_affected)));
+        return $row_affected->cdata;
     }
 
     /**
@@ -384,7 +519,9 @@ class LibSQLPHP
      */
     public function last_insert_rowid(): int
     {
-        return $this->ffi->libsql_php_last_insert_rowid($this->db);
+        $rowid = $this->ffi->new("int64_t");
+        LibSQLPHPException::check($this->ffi, $this->ffi->libsql_php_last_insert_rowid($this->db, \FFI::addr($rowid)));
+        return $rowid->cdata;
     }
 
     /**
@@ -406,11 +543,13 @@ class LibSQLPHP
      *     echo "Rollback the changes" . PHP_EOL;
      * }
      * ```
-     * NOTE: After `commit` or `rollback` the `$tx` will be free from memory
+     * NOTE: A transaction is committed or rolled back only once, and is rolled back when `$tx` is freed unfinished.
      *
-     * @param string $behavior The behavior of the transaction (optional, defaults to TransactionBehavior::Deferred).
+     * @param string $behavior The behavior of the transaction, one of the `TransactionBehavior` constants (optional, defaults to TransactionBehavior::Deferred).
      *
      * @return Transaction A Transaction instance representing the initiated transaction.
+     *
+     * @throws LibSQLPHPException If the behavior is unknown or the transaction cannot be started.
      */
     public function transaction(string $behavior = TransactionBehavior::Deferred): Transaction
     {
@@ -431,14 +570,19 @@ class LibSQLPHP
      * @param string $query The SQL statement to execute.
      * @param array $params The SQL parameters to execute. Optional
      *
-     * @return bool True if the execution was successful, false otherwise.
+     * @return bool True if the execution was successful.
+     *
+     * @throws LibSQLPHPException If the execution fails.
      */
     public function exec(string $query, array $params = []): bool
     {
-        $queryParams = new QueryParams($params);
-        $exec = $this->ffi->libsql_php_exec($this->db, $query, $queryParams->getData(), $queryParams->getLength());
+        $queryParams = new TypedParams($this->ffi, $params);
+        $status = $queryParams->isNamed()
+            ? $this->ffi->libsql_php_exec_named($this->db, $query, $queryParams->getNames(), $queryParams->getData(), $queryParams->getLength(), null)
+            : $this->ffi->libsql_php_exec_values($this->db, $query, $queryParams->getData(), $queryParams->getLength(), null);
         $queryParams->freeParams();
-        return $exec[0] > 0;
+        LibSQLPHPException::check($this->ffi, $status);
+        return true;
     }
 
     /**
@@ -470,27 +614,406 @@ class LibSQLPHP
      * @param string $query The batch of SQL queries to execute.
      *
      * @return void
+     *
+     * @throws LibSQLPHPException If one of the queries fails.
      */
     public function execute_batch(string $query): void
     {
-        $this->ffi->libsql_php_execute_batch($this->db, $query);
+        LibSQLPHPException::check($this->ffi, $this->ffi->libsql_php_execute_batch($this->db, $query));
+    }
+
+    /**
+     * Executes a list of statements, each with its own parameters, and returns the result of every statement.
+     *
+     * The statements run in order and the batch stops at the first one that fails. An atomic batch runs
+     * inside a transaction that is rolled back when a statement fails, so either every statement is applied
+     * or none is. A batch that is not atomic keeps the changes of the statements that ran before the failure.
+     *
+     * **Example**
+     *
+     * ```
+     * $results = $db->batch([
+     *     "CREATE TABLE IF NOT EXISTS users (id INTEGER PRIMARY KEY, name TEXT)",
+     *     ['sql' => "INSERT INTO users (name) VALUES (?)", 'params' => ["Alice"]],
+     *     ['sql' => "SELECT * FROM users WHERE name = :name", 'params' => ['name' => "Alice"]],
+     * ]);
+     * echo $results[1]->lastInsertRowid;
+     * ```
+     *
+     * @param array $statements The statements, each either an SQL string or an array with its `sql`, its `params`
+     *                          and optionally the `LIBSQLPHP_*` `types` of its parameters.
+     * @param bool $atomic Whether the statements run inside a transaction.
+     *
+     * @return LibSQLPHPClientResult[] The result of every statement, in order.
+     *
+     * @throws LibSQLPHPBatchException If a statement fails. The message starts with the number of the statement,
+     *                                 and a batch that is not atomic keeps the results of the statements that ran.
+     */
+    public function batch(array $statements, bool $atomic = true): array
+    {
+        $count = count($statements);
+        $ffi_statements = $count === 0 ? null : $this->ffi->new("LibsqlPhpBatchStatement[{$count}]");
+        $queryParams = [];
+        $buffers = [];
+
+        $i = 0;
+        foreach ($statements as $statement) {
+            if (is_string($statement)) {
+                $statement = ['sql' => $statement];
+            }
+
+            $params = new TypedParams($this->ffi, $statement['params'] ?? [], $statement['types'] ?? []);
+            $queryParams[] = $params;
+
+            $sql = (string) $statement['sql'];
+            $length = strlen($sql);
+            $buffer = $this->ffi->new("char[" . ($length + 1) . "]");
+            \FFI::memcpy($buffer, $sql, $length);
+            $buffers[] = $buffer;
+
+            $ffi_statements[$i]->sql = \FFI::cast("char*", \FFI::addr($buffer[0]));
+            $ffi_statements[$i]->names = $params->getNames();
+            $ffi_statements[$i]->values = $params->getData();
+            $ffi_statements[$i]->values_len = $params->getLength();
+            $i++;
+        }
+
+        $data = $this->ffi->new("const char*");
+        $failed = $this->ffi->new("uintptr_t");
+        $status = $this->ffi->libsql_php_batch(
+            $this->db,
+            $ffi_statements === null ? null : \FFI::addr($ffi_statements[0]),
+            $count,
+            $atomic,
+            \FFI::addr($data),
+            \FFI::addr($failed)
+        );
+
+        foreach ($queryParams as $params) {
+            $params->freeParams();
+        }
+
+        $results = [];
+        if (!\FFI::isNull($data)) {
+            $steps = json_decode(\FFI::string($data), true);
+            $this->ffi->libsql_php_free_string($data);
+
+            $results = array_map(function (array $step) {
+                $result = new LibSQLPHPResult($this->ffi, $this->db, $step);
+                return new LibSQLPHPClientResult(
+                    $result->columName(),
+                    $result->columnType(),
+                    $result->fetchArray(LIBSQLPHP_ASSOC),
+                    $step['rows_affected'],
+                    $step['last_insert_rowid']
+                );
+            }, $steps);
+        }
+
+        if ($status !== 0) {
+            throw new LibSQLPHPBatchException(
+                $this->ffi->libsql_php_last_error_message() ?? "Unknown LibSQLPHP error",
+                $status,
+                $this->ffi->libsql_php_last_error_extended_code(),
+                $results,
+                $failed->cdata
+            );
+        }
+
+        return $results;
     }
 
     /**
      * Synchronize changes with the database server.
      *
-     * @return int The result of the synchronization operation.
+     * **Example**
+     *
+     * ```
+     * $result = $db->sync();
+     * echo "Reached frame {$result['frame_no']}, applied {$result['frames_applied']} frames";
+     * ```
+     *
+     * @return array The `frame_no` the replica reached, or null if it has not replicated any frame yet,
+     *               and the number of `frames_applied` by this sync.
+     *
+     * @throws \Exception If attempting to sync with a local file connection.
+     * @throws LibSQLPHPException If the synchronization fails.
+     */
+    public function sync(): array
+    {
+        if ($this->connection_mode !== 'remote_replica') {
+            throw new \Exception("Error: Sync only works for remote replica connection.");
+        }
+
+        $frame_no = $this->ffi->new("int64_t");
+        $frames_applied = $this->ffi->new("uint64_t");
+        LibSQLPHPException::check($this->ffi, $this->ffi->libsql_php_sync($this->db, \FFI::addr($frame_no), \FFI::addr($frames_applied)));
+
+        return [
+            'frame_no' => $frame_no->cdata < 0 ? null : $frame_no->cdata,
+            'frames_applied' => $frames_applied->cdata,
+        ];
+    }
+
+    /**
+     * Synchronize until the replica has reached a frame number, so writes made through the primary are visible locally.
+     *
+     * @param int $frame_no The frame number to wait for.
+     * @param int $timeout_ms How long, in milliseconds, to keep syncing before giving up.
+     *
+     * @return int The frame number the replica reached.
      *
      * @throws \Exception If attempting to sync with a local file connection.
+     * @throws LibSQLPHPException If the synchronization fails or the frame is not reached before the timeout.
      */
-    public function sync(): int
+    public function sync_until(int $frame_no, int $timeout_ms = 5000): int
     {
-        if ($this->connection_mode === 'local') {
-            throw new \Exception("Error: Sync not work for local file connection.");
+        if ($this->connection_mode !== 'remote_replica') {
+            throw new \Exception("Error: Sync only works for remote replica connection.");
         }
 
-        $exec = $this->ffi->libsql_php_sync($this->dbPair);
-        return $exec === 0;
+        $reached = $this->ffi->new("int64_t");
+        LibSQLPHPException::check($this->ffi, $this->ffi->libsql_php_sync_until($this->db, $frame_no, $timeout_ms, \FFI::addr($reached)));
+
+        return $reached->cdata;
+    }
+
+    /**
+     * Get the frame number the replica has committed locally, without contacting the server.
+     *
+     * @return int|null The frame number, or null if the replica has not replicated any frame yet.
+     *
+     * @throws \Exception If the connection is not a remote replica.
+     */
+    public function replication_index(): ?int
+    {
+        if ($this->connection_mode !== 'remote_replica') {
+            throw new \Exception("Error: Replication index only exists for remote replica connection.");
+        }
+
+        $frame_no = $this->ffi->new("int64_t");
+        LibSQLPHPException::check($this->ffi, $this->ffi->libsql_php_replication_index($this->db, \FFI::addr($frame_no)));
+
+        return $frame_no->cdata < 0 ? null : $frame_no->cdata;
+    }
+
+    /**
+     * Report whether the replica is stale and how far behind the server it is, without contacting the server.
+     *
+     * **Example**
+     *
+     * ```
+     * $status = $db->replica_status();
+     * if ($status['stale']) {
+     *     echo "Replica is {$status['frames_behind']} frames behind";
+     * }
+     * ```
+     *
+     * @return array Whether the last sync reached the server (`online`), whether the connection reads the local
+     *               file `read_only`, whether the replica is `stale`, its `frame_no`, the `frames_behind` the last
+     *               frame known for the server and the `seconds_since_sync`. Unknown values are null.
+     *
+     * @throws \Exception If the connection is not a remote replica.
+     */
+    public function replica_status(): array
+    {
+        if ($this->connection_mode !== 'remote_replica') {
+            throw new \Exception("Error: Replica status only exists for remote replica connection.");
+        }
+
+        $status = $this->ffi->new("LibsqlPhpReplicaStatus");
+        LibSQLPHPException::check($this->ffi, $this->ffi->libsql_php_replica_status($this->db, \FFI::addr($status)));
+
+        return [
+            'online' => $status->online === 1,
+            'read_only' => $status->read_only === 1,
+            'stale' => $status->stale === 1,
+            'frame_no' => $status->frame_no < 0 ? null : $status->frame_no,
+            'frames_behind' => $status->frames_behind < 0 ? null : $status->frames_behind,
+            'seconds_since_sync' => $status->seconds_since_sync < 0 ? null : $status->seconds_since_sync,
+        ];
+    }
+
+    /**
+     * Start syncing the replica in the background, replacing the background sync it already had.
+     *
+     * Remote replica connections start one every `sync_interval` seconds when they are opened.
+     *
+     * @param int $interval_ms How long, in milliseconds, to wait between two syncs.
+     * @param int $timeout_ms How long, in milliseconds, each sync may take. Default: 0, the interval
+     *
+     * @return void
+     *
+     * @throws \Exception If the connection is not a remote replica.
+     * @throws LibSQLPHPException If the interval is 0.
+     */
+    public function background_sync_start(int $interval_ms, int $timeout_ms = 0): void
+    {
+        if ($this->connection_mode !== 'remote_replica') {
+            throw new \Exception("Error: Background sync only works for remote replica connection.");
+        }
+
+        LibSQLPHPException::check($this->ffi, $this->ffi->libsql_php_background_sync_start($this->db, $interval_ms, $timeout_ms, null, null));
+    }
+
+    /**
+     * Stop syncing the replica in the background.
+     *
+     * @return void
+     *
+     * @throws \Exception If the connection is not a remote replica.
+     */
+    public function background_sync_stop(): void
+    {
+        if ($this->connection_mode !== 'remote_replica') {
+            throw new \Exception("Error: Background sync only works for remote replica connection.");
+        }
+
+        LibSQLPHPException::check($this->ffi, $this->ffi->libsql_php_background_sync_stop($this->db));
+    }
+
+    /**
+     * Report whether the background sync is running and how the latest syncs went.
+     *
+     * **Example**
+     *
+     * ```
+     * $status = $db->background_sync_status();
+     * if ($status['consecutive_failures'] > 3) {
+     *     error_log("Replica cannot sync: {$status['last_error']}");
+     * }
+     * ```
+     *
+     * @return array Whether the background sync is `running`, the `last_sync` time in milliseconds since the Unix epoch
+     *               or null, the `last_error` of a failed sync or null, and the number of `consecutive_failures`.
+     *
+     * @throws \Exception If the connection is not a remote replica.
+     */
+    public function background_sync_status(): array
+    {
+        if ($this->connection_mode !== 'remote_replica') {
+            throw new \Exception("Error: Background sync only works for remote replica connection.");
+        }
+
+        $status = $this->ffi->new("LibsqlPhpBackgroundSyncStatus");
+        LibSQLPHPException::check($this->ffi, $this->ffi->libsql_php_background_sync_status($this->db, \FFI::addr($status)));
+
+        return [
+            'running' => $status->running === 1,
+            'last_sync' => $status->last_sync_unix_ms < 0 ? null : $status->last_sync_unix_ms,
+            'last_error' => $status->last_error === null ? null : \FFI::string($status->last_error),
+            'consecutive_failures' => $status->consecutive_failures,
+        ];
+    }
+
+    /**
+     * Rotate the encryption key of the database file of a local connection.
+     *
+     * Remote replicas cannot be rekeyed. The file is re-encrypted in place, so it must not be in WAL journal mode.
+     * Reopen the database with the new key afterwards.
+     *
+     * **Example**
+     *
+     * ```
+     * $db = new LibSQLPHP("file:database.db", encryptionKey: "old secret", key_format: LIBSQLPHP_KEY_PASSPHRASE);
+     * $db->rekey("new secret", key_format: LIBSQLPHP_KEY_PASSPHRASE);
+     * ```
+     *
+     * @param string|null $key The new encryption key, or null to decrypt the file.
+     * @param int $cipher The `LIBSQLPHP_CIPHER_*` cipher.
+     * @param int $key_format The `LIBSQLPHP_KEY_*` format of the key.
+     *
+     * @return void
+     *
+     * @throws LibSQLPHPException If the key cannot be changed.
+     */
+    public function rekey(?string $key, int $cipher = LIBSQLPHP_CIPHER_AES256CBC, int $key_format = LIBSQLPHP_KEY_RAW): void
+    {
+        $encryption = new Encryption($this->ffi, $key, $cipher, $key_format);
+        $status = $this->ffi->libsql_php_rekey($this->db, $encryption->getData());
+        $encryption->free();
+        LibSQLPHPException::check($this->ffi, $status);
+    }
+
+    /**
+     * Retry statements that fail with a transient error, waiting longer after each attempt.
+     *
+     * Statements executed and queried on the connection and starting a transaction are retried.
+     * Batches and statements inside a transaction are not, because running them again could repeat
+     * work that already succeeded. For the same reason, `LIBSQLPHP_RETRY_REMOTE` only retries requests
+     * whose connection to the remote database could not be opened.
+     *
+     * **Example**
+     *
+     * ```
+     * $db->set_retry_policy(max_attempts: 5, initial_backoff_ms: 50, max_backoff_ms: 1000);
+     * $db->exec("INSERT INTO users (name) VALUES ('Handoko')"); // retried while the database is locked
+     * ```
+     *
+     * @param int $max_attempts How many times a statement is run at most, counting the first attempt. `1` disables retries.
+     * @param int $initial_backoff_ms The delay before the first retry, doubled for every retry after it.
+     * @param int $max_backoff_ms The longest delay between two attempts.
+     * @param int $jitter_percent How much of each delay, from 0 to 100 percent, is randomly skipped.
+     * @param int $retry_on The `LIBSQLPHP_RETRY_*` error classes to retry.
+     *
+     * @return void
+     *
+     * @throws LibSQLPHPException If the policy is invalid.
+     */
+    public function set_retry_policy(
+        int $max_attempts,
+        int $initial_backoff_ms = 50,
+        int $max_backoff_ms = 2000,
+        int $jitter_percent = 20,
+        int $retry_on = LIBSQLPHP_RETRY_BUSY | LIBSQLPHP_RETRY_LOCKED
+    ): void {
+        $policy = $this->ffi->new("LibsqlPhpRetryPolicy");
+        $policy->max_attempts = $max_attempts;
+        $policy->initial_backoff_ms = $initial_backoff_ms;
+        $policy->max_backoff_ms = $max_backoff_ms;
+        $policy->jitter_percent = $jitter_percent;
+        $policy->retry_on = $retry_on;
+
+        LibSQLPHPException::check($this->ffi, $this->ffi->libsql_php_set_retry_policy($this->db, \FFI::addr($policy)));
+    }
+
+    /**
+     * Get how statements that fail with a transient error are retried.
+     *
+     * @return array The `max_attempts`, `initial_backoff_ms`, `max_backoff_ms`, `jitter_percent` and `retry_on` of the policy.
+     */
+    public function retry_policy(): array
+    {
+        $policy = $this->ffi->new("LibsqlPhpRetryPolicy");
+        LibSQLPHPException::check($this->ffi, $this->ffi->libsql_php_retry_policy($this->db, \FFI::addr($policy)));
+
+        return [
+            'max_attempts' => $policy->max_attempts,
+            'initial_backoff_ms' => $policy->initial_backoff_ms,
+            'max_backoff_ms' => $policy->max_backoff_ms,
+            'jitter_percent' => $policy->jitter_percent,
+            'retry_on' => $policy->retry_on,
+        ];
+    }
+
+    /**
+     * Set how long to wait for a lock held by another connection before a statement fails as busy.
+     *
+     * **Example**
+     *
+     * ```
+     * $db->busy_timeout(5000);
+     * ```
+     *
+     * @param int $timeout_ms How long to wait, in milliseconds, or 0 to fail at once.
+     *
+     * @return void
+     *
+     * @throws LibSQLPHPException If the connection is remote, is a remote replica, or the timeout cannot be set.
+     */
+    public function busy_timeout(int $timeout_ms): void
+    {
+        LibSQLPHPException::check($this->ffi, $this->ffi->libsql_php_busy_timeout($this->db, $timeout_ms));
     }
 
     /**
@@ -522,43 +1045,12 @@ class LibSQLPHP
         return $escaped_value;
     }
 
-    /**
-     * Check the flags provided for database opening mode.
-     *
-     * @param int $flags The flags to check.
-     *
-     * @return string|false The string representation of the flags, or false if invalid.
-     */
-    private function checkFlags(int $flags): string|false
-    {
-        $flag_string = 'LIBSQLPHP_OPEN_READWRITE_LIBSQLPHP_OPEN_CREATE';
-        switch ($flags) {
-            case LIBSQLPHP_OPEN_READONLY:
-                $flag_string = 'LIBSQLPHP_OPEN_READONLY';
-                break;
-            case LIBSQLPHP_OPEN_READWRITE:
-                $flag_string = 'LIBSQLPHP_OPEN_READWRITE';
-                break;
-            case LIBSQLPHP_OPEN_CREATE:
-                $flag_string = 'LIBSQLPHP_OPEN_CREATE';
-                break;
-            case (LIBSQLPHP_OPEN_READWRITE | LIBSQLPHP_OPEN_CREATE):
-                $flag_string = 'LIBSQLPHP_OPEN_READWRITE_LIBSQLPHP_OPEN_CREATE';
-                break;
-            case (LIBSQLPHP_OPEN_READONLY | LIBSQLPHP_OPEN_CREATE):
-                $flag_string = 'LIBSQLPHP_OPEN_READONLY_LIBSQLPHP_OPEN_CREATE';
-                break;
-            default:
-                $flag_string = false;
-                break;
-        }
-        return $flag_string;
-    }
-
     /**
      * Check the connection mode based on the provided path.
      *
-     * @param string $path The database connection path.
+     * @param string $path The database connection path, or the URL of a remote database.
+     * @param string $url The URL of the remote database (optional).
+     * @param string $token The authentication token of the remote database (optional).
      *
      * @return array|false The connection mode details, or false if not applicable.
      */
@@ -578,6 +1070,20 @@ class LibSQLPHP
                 'mode' => $this->connection_mode,
                 'uri' => str_replace("file:", "", $path)
             ];
+        } else if (empty($path) && preg_match('/^(libsql|https?):\/\//', $url)) {
+            $this->connection_mode = 'remote';
+            $path = [
+                'mode' => $this->connection_mode,
+                'url' => $url,
+                'token' => $token
+            ];
+        } else if (preg_match('/^(libsql|https?):\/\//', $path)) {
+            $this->connection_mode = 'remote';
+            $path = [
+                'mode' => $this->connection_mode,
+                'url' => $path,
+                'token' => $token
+            ];
         } else if ($path === ":memory:") {
             $this->connection_mode = 'memory';
             $path = [
@@ -0,0 +1,191 @@
+<?php
+
+namespace Darkterminal\LibSQLPHPExtension;
+
+use Darkterminal\LibSQLPHPExtension\Exceptions\LibSQLPHPException;
+use Darkterminal\LibSQLPHPExtension\Utils\Encryption;
+
+/**
+ * A bounded pool of connections to one local database, kept inside the extension process.
+ *
+ * Long-running PHP servers (Swoole, RoadRunner, FrankenPHP workers) keep the pool for the
+ * lifetime of the worker and check a connection out for every request.
+ *
+ * @package Darkterminal\LibSQLPHPExtension
+ */
+class LibSQLPHPPool
+{
+    /**
+     * FFI instance for interacting with the C library.
+     *
+     * @var \FFI
+     */
+    public \FFI $ffi;
+
+    /**
+     * Database handle shared by every connection of the pool.
+     *
+     * @var mixed
+     */
+    protected $database;
+
+    /**
+     * Pool handle.
+     *
+     * @var mixed
+     */
+    protected $pool;
+
+    /**
+     * Constructor.
+     *
+     * **Example**
+     *
+     * ```
+     * $pool = new LibSQLPHPPool("file:database.db", max_size: 8, idle_timeout: 300);
+     *
+     * $db = $pool->checkout();
+     * $db->query("SELECT * FROM users");
+     * $pool->checkin($db);
+     * ```
+     *
+     * @param string $path Path to the database file, prefixed with `file:`.
+     * @param int $flags Flags to control database opening mode. Default: LIBSQLPHP_OPEN_READWRITE | LIBSQLPHP_OPEN_CREATE
+     * @param string $encryptionKey Encryption key for database (if applicable).
+     * @param int $max_size The maximum number of connections the pool opens.
+     * @param int $idle_timeout How long, in seconds, an unused connection is kept. `0` keeps it until the pool is closed.
+     * @param int $cipher The `LIBSQLPHP_CIPHER_*` cipher of an encrypted database. Default: LIBSQLPHP_CIPHER_AES256CBC
+     * @param int $key_format The `LIBSQLPHP_KEY_*` format of the encryption key. Default: LIBSQLPHP_KEY_RAW
+     *
+     * @throws \Exception If LibSQLPHP definition and extension files do not exist.
+     * @throws LibSQLPHPException If the database cannot be opened.
+     */
+    public function __construct(
+        string $path,
+        int $flags = LIBSQLPHP_OPEN_READWRITE | LIBSQLPHP_OPEN_CREATE,
+        string $encryptionKey = "",
+        int $max_size = 8,
+        int $idle_timeout = 300,
+        int $cipher = LIBSQLPHP_CIPHER_AES256CBC,
+        int $key_format = LIBSQLPHP_KEY_RAW
+    ) {
+        if (strpos($path, "file:") === false) {
+            throw new \Exception("Error: Only local file databases can be pooled");
+        }
+
+        if (!file_exists(__DIR__ . '/libsql_php.def') && !file_exists(__DIR__ . '/../libs/libsql_php_client.so')) {
+            throw new \Exception("LibSQLPHP definition and extension is not exits!");
+        }
+
+        $this->ffi = \FFI::cdef(
+            file_get_contents(__DIR__ . '/libsql_php.def'),
+            __DIR__ . '/../libs/libsql_php_client.so'
+        );
+
+        $encryption = new Encryption($this->ffi, $encryptionKey === "" ? null : $encryptionKey, $cipher, $key_format);
+        $this->database = $this->ffi->new("void*");
+        $status = $this->ffi->libsql_php_db_open(
+            str_replace("file:", "", $path),
+            $flags,
+            $encryption->getData(),
+            \FFI::addr($this->database)
+        );
+        $encryption->free();
+        LibSQLPHPException::check($this->ffi, $status);
+
+        $this->pool = $this->ffi->new("void*");
+        LibSQLPHPException::check($this->ffi, $this->ffi->libsql_php_pool_new(
+            $this->database,
+            $max_size,
+            $idle_timeout,
+            \FFI::addr($this->pool)
+        ));
+    }
+
+    /**
+     * Check a connection out of the pool.
+     *
+     * @param int $timeout_ms How long, in milliseconds, to wait when every connection is in use.
+     *
+     * @return LibSQLPHP The connection, to be given back with `checkin`. Closing it instead frees its slot
+     *                   without reusing the connection. It may have been used before, and keeps any
+     *                   pragma an earlier caller ran other than `busy_timeout`.
+     *
+     * @throws LibSQLPHPException If no connection became available before the timeout.
+     */
+    public function checkout(int $timeout_ms = 0): LibSQLPHP
+    {
+        $conn = $this->ffi->new("void*");
+        LibSQLPHPException::check($this->ffi, $this->ffi->libsql_php_pool_checkout($this->pool, $timeout_ms, \FFI::addr($conn)));
+
+        return LibSQLPHP::fromHandle($this->ffi, $conn);
+    }
+
+    /**
+     * Give a connection checked out with `checkout` back to the pool.
+     *
+     * A connection left inside a transaction is closed instead of being reused. The retry policy and
+     * busy timeout of a reused connection are reset; any other state, such as pragmas run with `exec`,
+     * carries over to the next caller.
+     *
+     * @param LibSQLPHP $conn The connection.
+     *
+     * @return void
+     *
+     * @throws LibSQLPHPException If the connection was not checked out of this pool.
+     */
+    public function checkin(LibSQLPHP $conn): void
+    {
+        LibSQLPHPException::check($this->ffi, $this->ffi->libsql_php_pool_checkin($this->pool, $conn->db));
+        $conn->db = null;
+        $conn->is_connected = false;
+    }
+
+    /**
+     * Open a connection to the pooled database that is not managed by the pool.
+     *
+     * @return LibSQLPHP The connection, to be closed with `close`.
+     */
+    public function connect(): LibSQLPHP
+    {
+        $conn = $this->ffi->new("void*");
+        LibSQLPHPException::check($this->ffi, $this->ffi->libsql_php_db_connect($this->database, \FFI::addr($conn)));
+
+        return LibSQLPHP::fromHandle($this->ffi, $conn);
+    }
+
+    /**
+     * Get how many connections are idle and how many are checked out.
+     *
+     * @return array An array with the `idle` and `in_use` counts.
+     */
+    public function stats(): array
+    {
+        $idle = $this->ffi->new("unsigned int");
+        $in_use = $this->ffi->new("unsigned int");
+        LibSQLPHPException::check($this->ffi, $this->ffi->libsql_php_pool_stats($this->pool, \FFI::addr($idle), \FFI::addr($in_use)));
+
+        return [
+            'idle' => $idle->cdata,
+            'in_use' => $in_use->cdata,
+        ];
+    }
+
+    /**
+     * Close the idle connections and release the pool.
+     *
+     * @return void
+     */
+    public function close(): void
+    {
+        if ($this->pool) {
+            LibSQLPHPException::check($this->ffi, $this->ffi->libsql_php_pool_free($this->pool));
+            $this->pool = null;
+        }
+
+        if ($this->database) {
+            LibSQLPHPException::check($this->ffi, $this->ffi->libsql_php_db_close($this->database));
+            $this->database = null;
+        }
+    }
+}
@@ -2,6 +2,7 @@
 
 namespace Darkterminal\LibSQLPHPExtension\Responses;
 
+use Darkterminal\LibSQLPHPExtension\Exceptions\LibSQLPHPException;
 use FFI;
 
 /**
@@ -65,7 +66,7 @@ class LibSQLPHPResult
      */
     public function columName(int|null $index = null): array|string|false
     {
-        $columns = array_keys($this->data['columns']);
+        $columns = $this->data['columns'];
 
         if (is_null($index)) {
             return $columns;
@@ -79,26 +80,62 @@ class LibSQLPHPResult
     }
 
     /**
-     * Get the type of a column by name or index.
+     * Get the declared type of a column by name or index.
      *
      * @param int|string|null $column The name or index of the column (optional).
      *
-     * @return array|string|false The type of the column, or an array of all column types, or false if the column does not exist.
+     * @return array|string|null|false The declared type of the column, or an array of all declared types, or false if the column does not exist.
+     *                                 The type is null for expressions and for columns without a declared type.
      */
-    public function columnType(int|string|null $column = null): array|string|false
+    public function columnType(int|string|null $column = null): array|string|null|false
     {
+        if (is_null($column)) {
+            return array_column($this->data['meta'], 'decltype');
+        }
+
+        $meta = $this->columnMeta($column);
+
+        return $meta === false ? false : $meta['decltype'];
+    }
 
+    /**
+     * Get the metadata of a column by name or index.
+     *
+     * @param int|string|null $column The name or index of the column (optional).
+     *
+     * @return array|false The `name`, `decltype`, origin `table` and `origin` column of the column,
+     *                     or an array of the metadata of all columns, or false if the column does not exist.
+     */
+    public function columnMeta(int|string|null $column = null): array|false
+    {
         if (is_null($column)) {
-            return array_values($this->data['columns']);
+            return $this->data['meta'];
         }
 
-        if (isset($this->data['columns'][$column])) {
-            return $this->data['columns'][$column];
+        if (is_string($column)) {
+            $column = array_search($column, $this->data['columns'], true);
+        }
+
+        if ($column !== false && isset($this->data['meta'][$column])) {
+            return $this->data['meta'][$column];
         }
 
         return false;
     }
 
+    /**
+     * Get the storage class of every value in the result set.
+     *
+     * The types are `LIBSQLPHP_INTEGER`, `LIBSQLPHP_FLOAT`, `LIBSQLPHP_TEXT`, `LIBSQLPHP_BLOB` or `LIBSQLPHP_NULL`,
+     * in the same layout as the rows returned by `fetchArray(LIBSQLPHP_NUM)`.
+     *
+     * @return array The storage classes, one array per row.
+     */
+    public function fetchTypes(): array
+    {
+        return $this->data['types'];
+    }
+
     /**
      * Get the raw result data.
      *
@@ -128,12 +165,31 @@ class LibSQLPHPResult
     public function reset(): bool
     {
         if (!empty($this->db)) {
-            $this->ffi->libsql_php_reset($this->db);
+            LibSQLPHPException::check($this->ffi, $this->ffi->libsql_php_reset($this->db));
             return true;
         }
         return false;
     }
 
+    /**
+     * Decode one cell of a query result into its PHP value.
+     *
+     * Blobs arrive base64 encoded and integers outside ±(2^53 - 1) arrive as strings,
+     * so both are restored exactly.
+     *
+     * @param array $cell The cell, with its `type` and `value`.
+     *
+     * @return mixed The PHP value.
+     */
+    public static function decodeValue(array $cell): mixed
+    {
+        return match ($cell['type']) {
+            LIBSQLPHP_INTEGER => is_string($cell['value']) ? intval($cell['value']) : $cell['value'],
+            LIBSQLPHP_BLOB => base64_decode($cell['value']),
+            default => $cell['value'],
+        };
+    }
+
     /**
      * Convert raw result data into a structured format.
      *
@@ -144,21 +200,15 @@ class LibSQLPHPResult
     private function _raw_converter(array $data): array
     {
         $result = [
-            "columns" => [],
+            "columns" => array_column($data['columns'], 'name'),
+            "meta" => $data['columns'],
+            "types" => [],
             "rows" => [],
         ];
 
-        foreach ($data as $item) {
-            foreach ($item as $key => $value) {
-                if (!isset($result['columns'][$key])) {
-                    $result['columns'][$key] = key($value);
-                }
-            }
-            $row = [];
-            foreach ($result['columns'] as $column => $type) {
-                $row[] = current($item[$column]);
-            }
-            $result['rows'][] = $row;
+        foreach ($data['rows'] as $item) {
+            $result['types'][] = array_column($item, 'type');
+            $result['rows'][] = array_map([self::class, 'decodeValue'], $item);
         }
         return $result;
     }
@@ -170,7 +220,7 @@ class LibSQLPHPResult
      */
     private function _results(): array
     {
-        $columns = array_keys($this->data['columns']);
+        $columns = $this->data['columns'];
         $result = array_map(function ($row) use ($columns) {
             return array_combine($columns, $row);
         }, $this->data['rows']);
@@ -203,7 +253,7 @@ class LibSQLPHPResult
      */
     private function _libsqlphp_fetch_both(array $data): array
     {
-        $columns = array_keys($data['columns']);
+        $columns = $data['columns'];
         $result = [];
 
         foreach ($data['rows'] as $rowIndex => $row) {
@@ -2,8 +2,9 @@
 
 namespace Darkterminal\LibSQLPHPExtension\Responses;
 
+use Darkterminal\LibSQLPHPExtension\Exceptions\LibSQLPHPException;
 use Darkterminal\LibSQLPHPExtension\LibSQLPHP;
-use Darkterminal\LibSQLPHPExtension\Utils\QueryParams;
+use Darkterminal\LibSQLPHPExtension\Utils\TypedParams;
 use FFI;
 
 /**
@@ -20,15 +21,18 @@ class LibSQLPHPStmt
      * @param FFI $ffi The FFI instance.
      * @param $db The database connection handle.
      * @param string $query The SQL query string.
+     * @param $stmt The prepared statement handle returned by `libsql_php_prepare`.
      */
     public function __construct(
         protected FFI $ffi,
         protected $db,
-        protected string $query
+        protected string $query,
+        protected $stmt
     ) {
         $this->ffi = $ffi;
         $this->db = $db;
         $this->query = $query;
+        $this->stmt = $stmt;
     }
 
     /**
@@ -82,39 +86,35 @@ class LibSQLPHPStmt
     /**
      * Execute the prepared statement with bound parameters.
      *
-     * @return bool True if the execution was successful, false otherwise.
+     * The statement is parsed once by `prepare`, so it can be executed again with new bindings.
+     *
+     * @return bool True if the execution was successful.
+     *
+     * @throws LibSQLPHPException If the execution fails.
      */
     public function execute()
     {
-        $query = $this->query;
-
-        if (!empty($this->named_parameters)) {
-            foreach ($this->named_parameters as $param => $paramData) {
-                $value = $this->typed_value($paramData['value'], $paramData['type']);
-                if ($paramData['type'] === LIBSQLPHP_TEXT || $paramData['type'] === LIBSQLPHP_BLOB) {
-                    $value = "'" . LibSQLPHP::escapeString($value) . "'";
-                }
-                $query = str_replace($param, $value, $query);
-            }
-        }
-
-        if (!empty($this->positonal_parameters)) {
-            foreach ($this->positonal_parameters as $param => $paramData) {
-                $value = $this->typed_value($paramData['value'], $paramData['type']);
-                if ($paramData['type'] === LIBSQLPHP_TEXT || $paramData['type'] === LIBSQLPHP_BLOB) {
-                    $value = "'" . LibSQLPHP::escapeString($value) . "'";
-                }
-                $query = preg_replace('/\?/', $value, $query, 1);
-            }
-        }
+        $this->bindAll();
+        LibSQLPHPException::check($this->ffi, $this->ffi->libsql_php_stmt_execute($this->stmt, null));
+        return true;
+    }
 
-        $this->reset();
+    /**
+     * Run the prepared statement with bound parameters and return its rows.
+     *
+     * @return LibSQLPHPResult The result of the query.
+     *
+     * @throws LibSQLPHPException If the query fails.
+     */
+    public function query(): LibSQLPHPResult
+    {
+        $this->bindAll();
+        $data = $this->ffi->new("const char*");
+        LibSQLPHPException::check($this->ffi, $this->ffi->libsql_php_stmt_query($this->stmt, \FFI::addr($data)));
 
-        $params = [];
-        $queryParams = new QueryParams($params);
-        $exec = $this->ffi->libsql_php_exec($this->db, $query, $queryParams->getData(), $queryParams->getLength());
-        $queryParams->freeParams();
-        return $exec[0] === 0;
+        $object = json_decode(\FFI::string($data), true);
+        $this->ffi->libsql_php_free_string($data);
+        return new LibSQLPHPResult($this->ffi, $this->db, $object);
     }
 
     /**
@@ -156,10 +156,41 @@ class LibSQLPHPStmt
      */
     public function paramCount(): int
     {
-        $namedParamCount = count($this->named_parameters);
-        $positionalParamCount = count($this->positonal_parameters);
+        $count = $this->ffi->new("int");
+        LibSQLPHPException::check($this->ffi, $this->ffi->libsql_php_stmt_parameter_count($this->stmt, \FFI::addr($count)));
+
+        return $count->cdata;
+    }
+
+    /**
+     * Get the name of a parameter in the prepared statement.
+     *
+     * @param int $position The 1-based position of the parameter.
+     *
+     * @return string|null The parameter name including its prefix, or null for positional parameters.
+     */
+    public function paramName(int $position): ?string
+    {
+        $name = $this->ffi->new("const char*");
+        LibSQLPHPException::check($this->ffi, $this->ffi->libsql_php_stmt_parameter_name($this->stmt, $position, \FFI::addr($name)));
 
-        return $namedParamCount + $positionalParamCount;
+        return \FFI::isNull($name) ? null : \FFI::string($name);
+    }
+
+    /**
+     * Get the result columns of the prepared statement.
+     *
+     * @return array A list of columns, each with its `name` and declared type `decltype`.
+     */
+    public function columns(): array
+    {
+        $data = $this->ffi->new("const char*");
+        LibSQLPHPException::check($this->ffi, $this->ffi->libsql_php_stmt_columns($this->stmt, \FFI::addr($data)));
+
+        $columns = json_decode(\FFI::string($data), true);
+        $this->ffi->libsql_php_free_string($data);
+
+        return $columns;
     }
 
     /**
@@ -202,6 +233,7 @@ class LibSQLPHPStmt
     {
         $this->named_parameters = [];
         $this->positonal_parameters = [];
+        LibSQLPHPException::check($this->ffi, $this->ffi->libsql_php_stmt_reset($this->stmt));
     }
 
     /**
@@ -226,8 +258,39 @@ class LibSQLPHPStmt
      */
     public function close(): void
     {
-        unset($this->named_parameters);
-        unset($this->positonal_parameters);
+        $this->named_parameters = [];
+        $this->positonal_parameters = [];
+
+        if ($this->stmt !== null) {
+            LibSQLPHPException::check($this->ffi, $this->ffi->libsql_php_stmt_finalize($this->stmt));
+            $this->stmt = null;
+        }
+    }
+
+    /**
+     * Bind every parameter to the prepared statement handle, replacing earlier bindings.
+     *
+     * @return void
+     *
+     * @throws LibSQLPHPException If a value cannot be bound.
+     */
+    private function bindAll(): void
+    {
+        LibSQLPHPException::check($this->ffi, $this->ffi->libsql_php_stmt_clear_bindings($this->stmt));
+
+        foreach ($this->named_parameters as $param => $paramData) {
+            $value = new TypedParams($this->ffi, [$this->typed_value($paramData['value'], $paramData['type'])], [$paramData['type']]);
+            $status = $this->ffi->libsql_php_stmt_bind_named($this->stmt, $param, $value->getData());
+            $value->freeParams();
+            LibSQLPHPException::check($this->ffi, $status);
+        }
+
+        foreach ($this->positonal_parameters as $position => $paramData) {
+            $value = new TypedParams($this->ffi, [$this->typed_value($paramData['value'], $paramData['type'])], [$paramData['type']]);
+            $status = $this->ffi->libsql_php_stmt_bind($this->stmt, (int) $position, $value->getData());
+            $value->freeParams();
+            LibSQLPHPException::check($this->ffi, $status);
+        }
     }
 
     /**
@@ -2,6 +2,8 @@
 
 namespace Darkterminal\LibSQLPHPExtension\Responses;
 
+use Darkterminal\LibSQLPHPExtension\Exceptions\LibSQLPHPException;
+use Darkterminal\LibSQLPHPExtension\Utils\TypedParams;
 use FFI;
 
 /**
@@ -19,7 +21,7 @@ class Transaction
      *
      * @param FFI $ffi The FFI instance.
      * @param mixed $db The database handle.
-     * @param string $behavior The behavior of the transaction.
+     * @param string $behavior The behavior of the transaction, one of the `TransactionBehavior` constants.
      */
     public function __construct(
         protected FFI $ffi,
@@ -39,9 +41,12 @@ class Transaction
      * @param string $behavior The behavior of the transaction.
      *
      * @return Transaction The Transaction instance.
+     *
+     * @throws LibSQLPHPException If the transaction cannot be started.
      */
     private function begin(string $behavior): Transaction {
-        $this->transaction = $this->ffi->libsql_php_transaction($this->db, $behavior);
+        $this->transaction = $this->ffi->new("void*");
+        LibSQLPHPException::check($this->ffi, $this->ffi->libsql_php_transaction($this->db, $behavior, FFI::addr($this->transaction)));
         return $this;
     }
 
@@ -52,34 +57,150 @@ class Transaction
      * @param array $params Optional parameters for the query.
      *
      * @return Transaction The Transaction instance.
+     *
+     * @throws LibSQLPHPException If the query fails.
      */
     public function exec(string $query, array $params = []): Transaction {
-        $query_params_len = count($params);
-        $ffi_query_params = $this->ffi->new("const char*[{$query_params_len}]", false);
-        foreach ($params as $i => $param) {
-            $ffi_query_params[$i] = FFI::new("char[" . (strlen($param) + 1) . "]", false);
-            FFI::memcpy($ffi_query_params[$i], $param, strlen($param));
-        }
-        $this->ffi->libsql_php_transaction_exec($this->transaction, $query, FFI::addr($ffi_query_params[0]), $query_params_len);
+        $queryParams = new TypedParams($this->ffi, $params);
+        $status = $queryParams->isNamed()
+            ? $this->ffi->libsql_php_transaction_exec_named($this->transaction, $query, $queryParams->getNames(), $queryParams->getData(), $queryParams->getLength())
+            : $this->ffi->libsql_php_transaction_exec_values($this->transaction, $query, $queryParams->getData(), $queryParams->getLength());
+        $queryParams->freeParams();
+        LibSQLPHPException::check($this->ffi, $status);
         return $this;
     }
 
+    /**
+     * Executes a SQL query within the transaction and returns its rows.
+     *
+     * @param string $query The SQL query to execute.
+     * @param array $params Optional parameters for the query.
+     *
+     * @return LibSQLPHPResult The result of the query.
+     *
+     * @throws LibSQLPHPException If the query fails.
+     */
+    public function query(string $query, array $params = []): LibSQLPHPResult {
+        $queryParams = new TypedParams($this->ffi, $params);
+        $data = $this->ffi->new("const char*");
+        $status = $queryParams->isNamed()
+            ? $this->ffi->libsql_php_transaction_query_named($this->transaction, $query, $queryParams->getNames(), $queryParams->getData(), $queryParams->getLength(), FFI::addr($data))
+            : $this->ffi->libsql_php_transaction_query_values($this->transaction, $query, $queryParams->getData(), $queryParams->getLength(), FFI::addr($data));
+        $queryParams->freeParams();
+        LibSQLPHPException::check($this->ffi, $status);
+
+        $object = json_decode(FFI::string($data), true);
+        $this->ffi->libsql_php_free_string($data);
+        return new LibSQLPHPResult($this->ffi, $this->db, $object);
+    }
+
+    /**
+     * Executes a batch of SQL statements within the transaction.
+     *
+     * @param string $query The SQL statements to execute.
+     *
+     * @return Transaction The Transaction instance.
+     *
+     * @throws LibSQLPHPException If a statement fails.
+     */
+    public function execute_batch(string $query): Transaction {
+        LibSQLPHPException::check($this->ffi, $this->ffi->libsql_php_transaction_execute_batch($this->transaction, $query));
+        return $this;
+    }
+
+    /**
+     * Prepares a SQL statement within the transaction.
+     *
+     * @param string $query The SQL statement to prepare.
+     *
+     * @return LibSQLPHPStmt The prepared statement.
+     *
+     * @throws LibSQLPHPException If the statement cannot be prepared.
+     */
+    public function prepare(string $query): LibSQLPHPStmt {
+        $stmt = $this->ffi->new("void*");
+        LibSQLPHPException::check($this->ffi, $this->ffi->libsql_php_transaction_prepare($this->transaction, $query, FFI::addr($stmt)));
+        return new LibSQLPHPStmt($this->ffi, $this->db, $query, $stmt);
+    }
+
+    /**
+     * Opens a savepoint nested in the innermost savepoint of the transaction.
+     *
+     * @param string|null $name The name of the savepoint, or null to generate one.
+     *
+     * @return string The name of the savepoint.
+     *
+     * @throws LibSQLPHPException If the savepoint cannot be opened.
+     */
+    public function savepoint(?string $name = null): string {
+        $savepoint = $this->ffi->new("const char*");
+        LibSQLPHPException::check($this->ffi, $this->ffi->libsql_php_transaction_savepoint($this->transaction, $name, FFI::addr($savepoint)));
+
+        $name = FFI::string($savepoint);
+        $this->ffi->libsql_php_free_string($savepoint);
+        return $name;
+    }
+
+    /**
+     * Releases a savepoint, keeping its changes and closing the savepoints opened inside it.
+     *
+     * @param string|null $name The name of the savepoint, or null for the innermost one.
+     *
+     * @return Transaction The Transaction instance.
+     *
+     * @throws LibSQLPHPException If no open savepoint has the name or it cannot be released.
+     */
+    public function release(?string $name = null): Transaction {
+        LibSQLPHPException::check($this->ffi, $this->ffi->libsql_php_transaction_release($this->transaction, $name));
+        return $this;
+    }
+
+    /**
+     * Rolls back the changes made since a savepoint was opened, closing the savepoints opened inside it.
+     * The savepoint itself stays open.
+     *
+     * @param string|null $name The name of the savepoint, or null for the innermost one.
+     *
+     * @return Transaction The Transaction instance.
+     *
+     * @throws LibSQLPHPException If no open savepoint has the name or the rollback fails.
+     */
+    public function rollback_to(?string $name = null): Transaction {
+        LibSQLPHPException::check($this->ffi, $this->ffi->libsql_php_transaction_rollback_to($this->transaction, $name));
+        return $this;
+    }
+
+    /**
+     * Retrieves how many savepoints are open inside the transaction.
+     *
+     * @return int The number of open savepoints.
+     */
+    public function savepoint_depth(): int {
+        $depth = $this->ffi->new("uint32_t");
+        LibSQLPHPException::check($this->ffi, $this->ffi->libsql_php_transaction_savepoint_depth($this->transaction, FFI::addr($depth)));
+        return $depth->cdata;
+    }
+
     /**
      * Checks whether the transaction is in autocommit mode.
      *
      * @return bool True if the transaction is in autocommit mode, false otherwise.
      */
     public function is_autocommit(): bool {
-        return $this->ffi->libsql_php_is_autocommit($this->db);
+        $autocommit = $this->ffi->new("int");
+        LibSQLPHPException::check($this->ffi, $this->ffi->libsql_php_is_autocommit($this->db, FFI::addr($autocommit)));
+        return $autocommit->cdata === 1;
     }
 
     /**
-     * Retrieves the number of rows affected by the transaction.
+     * Retrieves the number of rows affected by the last statement executed in the transaction.
      *
-     * @return int The number of rows affected by the transaction.
+     * @return int The number of rows affected by the last statement.
      */
     public function changes(): int {
-        return $this->ffi->libsql_php_affected_rows($this->db);
+        $changes = $this->ffi->new("uint64_t");
+        LibSQLPHPException::check($this->ffi, $this->ffi->libsql_php_transaction_changes($this->transaction, FFI::addr($changes)));
+        return $changes->cdata;
     }
 
     /**
@@ -88,7 +209,9 @@ class Transaction
      * @return int The rowid of the most recently inserted row.
      */
     public function last_insert_rowid(): int {
-        return $this->ffi->libsql_php_last_insert_rowid($this->db);
+        $rowid = $this->ffi->new("int64_t");
+        LibSQLPHPException::check($this->ffi, $this->ffi->libsql_php_transaction_last_insert_rowid($this->transaction, FFI::addr($rowid)));
+        return $rowid->cdata;
     }
 
     /**
@@ -97,28 +220,41 @@ class Transaction
      * @return void
      */
     public function reset(): void {
-        $this->ffi->libsql_php_reset($this->db);
+        LibSQLPHPException::check($this->ffi, $this->ffi->libsql_php_reset($this->db));
     }
 
     /**
      * Commits the transaction.
      *
-     * @return bool True if the transaction is committed successfully, false otherwise.
+     * @return bool True if the transaction is committed successfully.
+     *
+     * @throws LibSQLPHPException If the commit fails or the transaction is already finished.
      */
     public function commit(): bool {
-        $result = $this->ffi->libsql_php_transaction_commit($this->transaction);
-        $this->ffi->free($this->transaction);
-        return $result;
+        LibSQLPHPException::check($this->ffi, $this->ffi->libsql_php_transaction_commit($this->transaction));
+        return true;
     }
 
     /**
      * Rolls back the transaction.
      *
-     * @return bool True if the transaction is rolled back successfully, false otherwise.
+     * @return bool True if the transaction is rolled back successfully.
+     *
+     * @throws LibSQLPHPException If the rollback fails or the transaction is already finished.
      */
     public function rollback(): bool {
-        $result = $this->ffi->libsql_php_transaction_rollback($this->transaction);
-        $this->ffi->free($this->transaction);
-        return $result;
+        LibSQLPHPException::check($this->ffi, $this->ffi->libsql_php_transaction_rollback($this->transaction));
+        return true;
+    }
+
+    /**
+     * Releases the transaction handle, rolling the transaction back if it was neither committed nor rolled back.
+     */
+    public function __destruct()
+    {
+        if ($this->transaction !== null && !FFI::isNull($this->transaction)) {
+            $this->ffi->libsql_php_transaction_free($this->transaction);
+            $this->transaction = null;
+        }
     }
 }
@@ -0,0 +1,76 @@
+<?php
+
+namespace Darkterminal\LibSQLPHPExtension\Utils;
+
+/**
+ * Class Encryption
+ *
+ * Represents the encryption settings of a database passed as a `LibsqlPhpEncryption` struct.
+ */
+class Encryption
+{
+    /**
+     * The FFI `LibsqlPhpEncryption` struct, or null when the database is not encrypted.
+     */
+    protected $ffi_encryption = null;
+
+    /**
+     * The buffer holding the key bytes.
+     */
+    protected $buffer = null;
+
+    /**
+     * Encryption constructor.
+     *
+     * @param \FFI $ffi The FFI instance.
+     * @param string|null $key The encryption key, or null for no encryption.
+     * @param int $cipher The `LIBSQLPHP_CIPHER_*` cipher.
+     * @param int $key_format The `LIBSQLPHP_KEY_*` format the key is given in.
+     */
+    public function __construct(protected \FFI $ffi, ?string $key, int $cipher, int $key_format)
+    {
+        if ($key === null) {
+            return;
+        }
+
+        $this->ffi_encryption = $this->ffi->new("LibsqlPhpEncryption", false);
+        $this->ffi_encryption->cipher = $cipher;
+        $this->ffi_encryption->key_format = $key_format;
+
+        $length = strlen($key);
+        $this->ffi_encryption->key_len = $length;
+        if ($length > 0) {
+            $this->buffer = \FFI::new("uint8_t[{$length}]", false);
+            \FFI::memcpy($this->buffer, $key, $length);
+            $this->ffi_encryption->key = \FFI::addr($this->buffer[0]);
+        }
+    }
+
+    /**
+     * Gets the address of the FFI `LibsqlPhpEncryption` struct.
+     *
+     * @return mixed The address of the struct, or null when the database is not encrypted.
+     */
+    public function getData()
+    {
+        return $this->ffi_encryption === null ? null : \FFI::addr($this->ffi_encryption);
+    }
+
+    /**
+     * Frees the memory allocated for the encryption settings.
+     *
+     * @return void
+     */
+    public function free(): void
+    {
+        if ($this->buffer !== null) {
+            \FFI::free($this->buffer);
+            $this->buffer = null;
+        }
+
+        if ($this->ffi_encryption !== null) {
+            \FFI::free($this->ffi_encryption);
+            $this->ffi_encryption = null;
+        }
+    }
+}
@@ -15,12 +15,17 @@ class TransactionBehavior
     const Deferred = "DEFERRED";
 
     /**
-     * The WRITE transaction behavior.
+     * The IMMEDIATE transaction behavior.
      */
-    const Immediate = "WRITE";
+    const Immediate = "IMMEDIATE";
 
     /**
-     * The READ transaction behavior.
+     * The EXCLUSIVE transaction behavior.
      */
-    const ReadOnly = "READ";
+    const Exclusive = "EXCLUSIVE";
+
+    /**
+     * The READONLY transaction behavior.
+     */
+    const ReadOnly = "READONLY";
 }
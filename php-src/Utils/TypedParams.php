@@ -0,0 +1,201 @@
+<?php
+
+namespace Darkterminal\LibSQLPHPExtension\Utils;
+
+/**
+ * Class TypedParams
+ *
+ * Represents a collection of typed query parameters passed as a `LibsqlPhpValue` array.
+ * Associative arrays are passed as named parameters, with a parallel array of names.
+ */
+class TypedParams
+{
+    /**
+     * The number of query parameters.
+     */
+    protected int $query_params_len;
+
+    /**
+     * The FFI `LibsqlPhpValue` array.
+     */
+    protected $ffi_values = null;
+
+    /**
+     * The FFI `const char*` array holding parameter names, when the parameters are named.
+     */
+    protected $ffi_names = null;
+
+    /**
+     * The buffers holding text and blob bytes.
+     */
+    protected array $buffers = [];
+
+    /**
+     * TypedParams constructor.
+     *
+     * @param \FFI $ffi The FFI instance.
+     * @param array $data The query parameter values.
+     * @param array $types The `LIBSQLPHP_*` type of each value, keyed like `$data`. Missing types are detected from the PHP value.
+     */
+    public function __construct(protected \FFI $ffi, array $data, array $types = [])
+    {
+        $this->query_params_len = count($data);
+
+        if ($this->query_params_len === 0) {
+            return;
+        }
+
+        $this->ffi_values = $this->ffi->new("LibsqlPhpValue[{$this->query_params_len}]", false);
+
+        if (is_array_assoc($data)) {
+            $this->ffi_names = $this->ffi->new("const char*[{$this->query_params_len}]", false);
+        }
+
+        $i = 0;
+        foreach ($data as $key => $value) {
+            $type = $types[$key] ?? self::typeOf($value);
+            $this->fill($this->ffi_values[$i], $value, $value === null ? LIBSQLPHP_NULL : $type);
+            if ($this->ffi_names !== null) {
+                $this->ffi_names[$i] = $this->cstring((string) $key);
+            }
+            $i++;
+        }
+    }
+
+    /**
+     * Detects the `LIBSQLPHP_*` type of a PHP value.
+     *
+     * @param mixed $value The value to inspect.
+     *
+     * @return int The detected type.
+     */
+    public static function typeOf($value): int
+    {
+        return match (true) {
+            is_null($value) => LIBSQLPHP_NULL,
+            is_int($value), is_bool($value) => LIBSQLPHP_INTEGER,
+            is_float($value) => LIBSQLPHP_FLOAT,
+            default => LIBSQLPHP_TEXT,
+        };
+    }
+
+    /**
+     * Gets the address of the FFI `LibsqlPhpValue` array.
+     *
+     * @return mixed The address of the array, or null when there are no parameters.
+     */
+    public function getData()
+    {
+        return $this->ffi_values === null ? null : \FFI::addr($this->ffi_values[0]);
+    }
+
+    /**
+     * Checks whether the parameters are named.
+     *
+     * @return bool True if the parameters were given as an associative array.
+     */
+    public function isNamed(): bool
+    {
+        return $this->ffi_names !== null;
+    }
+
+    /**
+     * Gets the address of the FFI parameter names array.
+     *
+     * @return mixed The address of the array, or null when the parameters are not named.
+     */
+    public function getNames()
+    {
+        return $this->ffi_names === null ? null : \FFI::addr($this->ffi_names[0]);
+    }
+
+    /**
+     * Gets the length of the query parameters array.
+     *
+     * @return int The length of the query parameters array.
+     */
+    public function getLength(): int
+    {
+        return $this->query_params_len;
+    }
+
+    /**
+     * Frees the memory allocated for the query parameters.
+     *
+     * @return void
+     */
+    public function freeParams(): void
+    {
+        foreach ($this->buffers as $buffer) {
+            \FFI::free($buffer);
+        }
+        $this->buffers = [];
+
+        if ($this->ffi_names !== null) {
+            \FFI::free($this->ffi_names);
+            $this->ffi_names = null;
+        }
+
+        if ($this->ffi_values !== null) {
+            \FFI::free($this->ffi_values);
+            $this->ffi_values = null;
+        }
+    }
+
+    /**
+     * Copies a PHP string into a null-terminated C buffer owned by this instance.
+     *
+     * @param string $value The string to copy.
+     *
+     * @return mixed The `char*` to the buffer.
+     */
+    private function cstring(string $value)
+    {
+        $length = strlen($value);
+        $buffer = \FFI::new("char[" . ($length + 1) . "]", false);
+        \FFI::memcpy($buffer, $value, $length);
+        $buffer[$length] = "\0";
+        $this->buffers[] = $buffer;
+
+        return \FFI::cast("char*", \FFI::addr($buffer[0]));
+    }
+
+    /**
+     * Fills one `LibsqlPhpValue` with a PHP value.
+     *
+     * @param mixed $target The `LibsqlPhpValue` to fill.
+     * @param mixed $value The PHP value.
+     * @param int $type The `LIBSQLPHP_*` type to bind the value as.
+     *
+     * @return void
+     */
+    private function fill($target, $value, int $type): void
+    {
+        $target->tag = $type;
+
+        switch ($type) {
+            case LIBSQLPHP_INTEGER:
+                $target->int_value = intval($value);
+                break;
+            case LIBSQLPHP_FLOAT:
+                $target->real_value = floatval($value);
+                break;
+            case LIBSQLPHP_TEXT:
+            case LIBSQLPHP_BLOB:
+                $bytes = (string) $value;
+                $length = strlen($bytes);
+                $target->len = $length;
+                if ($length > 0) {
+                    $buffer = \FFI::new("uint8_t[{$length}]", false);
+                    \FFI::memcpy($buffer, $bytes, $length);
+                    $this->buffers[] = $buffer;
+                    $target->ptr = \FFI::addr($buffer[0]);
+                }
+                break;
+            case LIBSQLPHP_NULL:
+                break;
+            default:
+                throw new \Exception("Unknown parameter type: {$type}");
+        }
+    }
+}
@@ -55,15 +55,25 @@ define('LIBSQLPHP_OPEN_READWRITE', 2);
  */
 define('LIBSQLPHP_OPEN_CREATE', 4);
 
+/**
+ * Specifies interpreting the path as a URI filename.
+ */
+define('LIBSQLPHP_OPEN_URI', 0x00000040);
+
+/**
+ * Specifies opening the database in memory.
+ */
+define('LIBSQLPHP_OPEN_MEMORY', 0x00000080);
+
 /**
  * Specifies opening the database without any mutex.
  */
-define('LIBSQLPHP_OPEN_NOMUTEX', 8);
+define('LIBSQLPHP_OPEN_NOMUTEX', 0x00008000);
 
 /**
  * Specifies opening the database with full mutex.
  */
-define('LIBSQLPHP_OPEN_FULLMUTEX', 16);
+define('LIBSQLPHP_OPEN_FULLMUTEX', 0x00010000);
 
 /**
  * Specifies using a shared cache for the database.
@@ -75,6 +85,58 @@ define('LIBSQLPHP_OPEN_SHAREDCACHE', 0x00020000);
  */
 define('LIBSQLPHP_OPEN_PRIVATECACHE', 0x00040000);
 
+/**
+ * Specifies refusing to open a path that is a symbolic link.
+ */
+define('LIBSQLPHP_OPEN_NOFOLLOW', 0x01000000);
+
+/**
+ * Specifies encrypting the database with AES-256 in CBC mode.
+ */
+define('LIBSQLPHP_CIPHER_AES256CBC', 1);
+
+/**
+ * Specifies that the encryption key bytes are used as they are.
+ */
+define('LIBSQLPHP_KEY_RAW', 1);
+
+/**
+ * Specifies that the encryption key is given as hex digits.
+ */
+define('LIBSQLPHP_KEY_HEX', 2);
+
+/**
+ * Specifies that the encryption key is a passphrase, stretched with 100 000 rounds of
+ * PBKDF2-HMAC-SHA256 salted with `libsql-php-passphrase`.
+ */
+define('LIBSQLPHP_KEY_PASSPHRASE', 3);
+
+/**
+ * Specifies seeding a replica from the database file of another replica of the same server.
+ */
+define('LIBSQLPHP_SEED_REPLICA', 1);
+
+/**
+ * Specifies seeding a replica from a snapshot file written by the server.
+ */
+define('LIBSQLPHP_SEED_SNAPSHOT', 2);
+
+/**
+ * Retries statements that failed because the database is busy (`SQLITE_BUSY`).
+ */
+define('LIBSQLPHP_RETRY_BUSY', 1);
+
+/**
+ * Retries statements that failed because a table is locked (`SQLITE_LOCKED`).
+ */
+define('LIBSQLPHP_RETRY_LOCKED', 2);
+
+/**
+ * Retries statements whose connection to the remote database could not be opened. Requests that
+ * failed after they were sent are not retried, because they may already have been applied.
+ */
+define('LIBSQLPHP_RETRY_REMOTE', 4);
+
 /**
  * Specifies that the function is deterministic.
  */
@@ -8,7 +8,40 @@ const ERR_INVALID_PATH_CONVERT: &str = "Failed to convert path to string";
 const ERR_INVALID_ARGUMENTS: &str = "Client pointer or query is null";
 const ERR_INVALID_QUERY_CONVERT: &str = "Failed to convert query to string";
 const ERR_REMOTE_REPLICA_CONFIGURATION: &str = "Error remote replica configuration";
-const ERR_DATABASE_PAIR_NOT_FOUND: &str = "Database pair is not found";
+const ERR_REMOTE_CONFIGURATION: &str = "Error remote configuration";
+const ERR_UNSUPPORTED_MODE: &str = "Operation is not supported in this connection mode";
 const ERR_TRANSACTION_COMMIT: &str = "Transaction commit failed";
 const ERR_TRANSACTION_ROLLBACK: &str = "Transaction rollback failed";
+const ERR_TRANSACTION_FINISHED: &str = "Transaction is already finished";
+const ERR_TRANSACTION_BEHAVIOR: &str = "Transaction behavior must be DEFERRED, IMMEDIATE, EXCLUSIVE or READONLY";
+const ERR_SAVEPOINT_UNKNOWN: &str = "No open savepoint has this name";
+const ERR_SAVEPOINT_NONE: &str = "No savepoint is open in the transaction";
 const ERR_STRING_CONVERTION: &str = "Failed to convert query to string";
+const ERR_NULL_OUT_PTR: &str = "Output pointer is null";
+const ERR_NULL_STATEMENT_PTR: &str = "Statement pointer is null";
+const ERR_NULL_ROWS_PTR: &str = "Rows pointer is null";
+const ERR_NULL_DATABASE_PTR: &str = "Database pointer is null";
+const ERR_NULL_POOL_PTR: &str = "Pool pointer is null";
+const ERR_POOL_EXHAUSTED: &str = "No pooled connection became available before the timeout";
+const ERR_INVALID_POOL_SIZE: &str = "Pool size must be greater than zero";
+const ERR_FOREIGN_CONNECTION: &str = "Connection was not checked out of this pool";
+const ERR_OPEN_FLAGS_UNKNOWN: &str = "Open flags contain an unknown flag";
+const ERR_OPEN_FLAGS_ACCESS: &str = "Open flags must include READONLY or READWRITE";
+const ERR_OPEN_FLAGS_READONLY_READWRITE: &str = "Open flags cannot combine READONLY with READWRITE";
+const ERR_OPEN_FLAGS_CREATE: &str = "Open flag CREATE requires READWRITE";
+const ERR_OPEN_FLAGS_MUTEX: &str = "Open flags cannot combine NOMUTEX with FULLMUTEX";
+const ERR_OPEN_FLAGS_CACHE: &str = "Open flags cannot combine SHAREDCACHE with PRIVATECACHE";
+const ERR_SYNC_TIMEOUT: &str = "Replica did not reach the frame before the timeout";
+const ERR_BOOTSTRAP_KIND: &str = "Bootstrap source kind must be LIBSQL_PHP_SEED_REPLICA or LIBSQL_PHP_SEED_SNAPSHOT";
+const ERR_BOOTSTRAP_EXISTS: &str = "A database already exists at the replica path";
+const ERR_BOOTSTRAP_NO_INDEX: &str = "Source database is not a replica: it has no replication index";
+const ERR_BOOTSTRAP_SOURCE_BUSY: &str = "Source replica is syncing, retry once the sync is done";
+const ERR_BOOTSTRAP_SOURCE_ENCRYPTED: &str = "Source replica cannot be read: encrypted replicas cannot seed another replica";
+const ERR_PRIMARY_TIMEOUT: &str = "Primary did not answer before the timeout";
+const ERR_INVALID_SYNC_INTERVAL: &str = "Sync interval must be greater than 0";
+const ERR_REPLICA_UNREACHABLE: &str = "Primary could not be reached and the replica has never synced";
+const ERR_RETRY_ATTEMPTS: &str = "Retry policy needs at least one attempt";
+const ERR_RETRY_BACKOFF: &str = "Retry policy maximum backoff cannot be shorter than its initial backoff";
+const ERR_RETRY_JITTER: &str = "Retry policy jitter must be between 0 and 100 percent";
+const ERR_RETRY_CLASSES: &str = "Retry policy contains an unknown error class";
+const ERR_NULL_BATCH_STATEMENTS: &str = "Batch statements pointer is null but statements were expected";
@@ -1,4 +1,8 @@
-use crate::{utils::errors::libsql_php_error, ERR_NULL_CLIENT_PTR};
+use crate::{
+    types::handle::LibsqlHandle,
+    utils::errors::{catch_panic, libsql_php_error, ErrorCode, LIBSQL_PHP_OK},
+    ERR_NULL_CLIENT_PTR, ERR_NULL_OUT_PTR,
+};
 
 /// Retrieves the number of affected rows after executing a LibSQL query in a PHP extension.
 ///
@@ -8,20 +12,30 @@ use crate::{utils::errors::libsql_php_error, ERR_NULL_CLIENT_PTR};
 ///
 /// # Arguments
 ///
-/// * `client_ptr` - A raw pointer to the SQL connection client.
+/// * `client_ptr` - A raw pointer to the `LibsqlHandle` of the connection.
+/// * `out_rows` - A pointer where the number of affected rows is written.
 ///
 /// # Returns
 ///
-/// Returns a pointer to a memory location containing the number of affected rows. If an error occurs or the client pointer is null, returns a null pointer.
+/// Returns `0` on success. If the client pointer or the output pointer is null, returns the error code
+/// and records the error for `libsql_php_last_error_message`.
 #[no_mangle]
-pub extern "C" fn libsql_php_affected_rows(client_ptr: *mut libc::c_void) -> *const u64 {
-    if client_ptr.is_null() {
-        libsql_php_error(ERR_NULL_CLIENT_PTR, "ERR_NULL_CLIENT_PTR");
-        return std::ptr::null_mut();
-    }
+pub unsafe extern "C" fn libsql_php_affected_rows(
+    client_ptr: *mut libc::c_void,
+    out_rows: *mut u64,
+) -> libc::c_int {
+    catch_panic(|| {
+        if client_ptr.is_null() {
+            return libsql_php_error(ERR_NULL_CLIENT_PTR, ErrorCode::NullClientPtr);
+        }
 
-    let client = unsafe { &mut *(client_ptr as *mut libsql::Connection) };
+        if out_rows.is_null() {
+            return libsql_php_error(ERR_NULL_OUT_PTR, ErrorCode::InvalidArguments);
+        }
 
-    let affected_rows: u64 = client.changes();
-    Box::into_raw(Box::new(affected_rows))
+        let client = unsafe { &(*(client_ptr as *mut LibsqlHandle)).conn };
+
+        unsafe { *out_rows = client.changes() };
+        LIBSQL_PHP_OK
+    })
 }
@@ -0,0 +1,217 @@
+use std::time::Duration;
+
+use super::sync::replica;
+use crate::{
+    types::replica::{LibsqlPhpBackgroundSyncStatus, LibsqlPhpSyncCallback},
+    utils::errors::{catch_panic, libsql_php_error, ErrorCode, LIBSQL_PHP_OK},
+    ERR_INVALID_SYNC_INTERVAL, ERR_NULL_OUT_PTR,
+};
+
+/// Starts syncing a remote replica periodically on the shared runtime, replacing the background
+/// sync it already had.
+///
+/// # Safety
+///
+/// This function is marked as unsafe because it dereferences raw pointers. `callback` runs on a
+/// runtime thread, so it and `user_data` must be safe to use from another thread until the
+/// background sync is stopped or the connection is closed.
+///
+/// # Arguments
+///
+/// * `client_ptr` - A raw pointer to the `LibsqlHandle` of a remote replica connection.
+/// * `interval_ms` - How long, in milliseconds, to wait between two syncs.
+/// * `timeout_ms` - How long, in milliseconds, each sync may take, or `0` for `interval_ms`.
+/// * `callback` - A function called after each sync, or a null pointer.
+/// * `user_data` - A pointer passed to `callback` as is.
+///
+/// # Returns
+///
+/// Returns `0` on success. If an error occurs, returns the error code and records the error for
+/// `libsql_php_last_error_message`.
+#[no_mangle]
+pub unsafe extern "C" fn libsql_php_background_sync_start(
+    client_ptr: *mut libc::c_void,
+    interval_ms: libc::c_uint,
+    timeout_ms: libc::c_uint,
+    callback: LibsqlPhpSyncCallback,
+    user_data: *mut libc::c_void,
+) -> libc::c_int {
+    catch_panic(|| {
+        let (handle, state) = match unsafe { replica(client_ptr) } {
+            Ok(replica) => replica,
+            Err(status) => return status,
+        };
+
+        if interval_ms == 0 {
+            return libsql_php_error(ERR_INVALID_SYNC_INTERVAL, ErrorCode::InvalidArguments);
+        }
+
+        let interval = Duration::from_millis(interval_ms.into());
+        let timeout = match timeout_ms {
+            0 => interval,
+            timeout_ms => Duration::from_millis(timeout_ms.into()),
+        };
+
+        unsafe { state.start_background_sync(&handle.db, interval, timeout, callback, user_data) };
+
+        LIBSQL_PHP_OK
+    })
+}
+
+/// Stops the background sync of a remote replica. Stopping one that is not running does nothing.
+///
+/// A callback that is being called is waited for, and none is called once this function returns,
+/// so its `user_data` can be freed. It must not be called from the callback itself.
+///
+/// # Safety
+///
+/// This function is marked as unsafe because it dereferences raw pointers.
+///
+/// # Arguments
+///
+/// * `client_ptr` - A raw pointer to the `LibsqlHandle` of a remote replica connection.
+///
+/// # Returns
+///
+/// Returns `0` on success. If an error occurs, returns the error code and records the error for
+/// `libsql_php_last_error_message`.
+#[no_mangle]
+pub unsafe extern "C" fn libsql_php_background_sync_stop(
+    client_ptr: *mut libc::c_void,
+) -> libc::c_int {
+    catch_panic(|| {
+        let (_, state) = match unsafe { replica(client_ptr) } {
+            Ok(replica) => replica,
+            Err(status) => return status,
+        };
+
+        state.stop_background_sync();
+
+        LIBSQL_PHP_OK
+    })
+}
+
+/// Reports whether the background sync of a remote replica is running, when the replica last
+/// synced, and how its latest syncs failed.
+///
+/// The last sync time, error and failure count cover every sync of the replica, including the
+/// ones made with `libsql_php_sync` and `libsql_php_sync_until`.
+///
+/// # Safety
+///
+/// This function is marked as unsafe because it dereferences raw pointers.
+///
+/// # Arguments
+///
+/// * `client_ptr` - A raw pointer to the `LibsqlHandle` of a remote replica connection.
+/// * `out_status` - A pointer to the `LibsqlPhpBackgroundSyncStatus` to fill in.
+///
+/// # Returns
+///
+/// Returns `0` on success. If an error occurs, returns the error code and records the error for
+/// `libsql_php_last_error_message`.
+#[no_mangle]
+pub unsafe extern "C" fn libsql_php_background_sync_status(
+    client_ptr: *mut libc::c_void,
+    out_status: *mut LibsqlPhpBackgroundSyncStatus,
+) -> libc::c_int {
+    catch_panic(|| {
+        let (_, state) = match unsafe { replica(client_ptr) } {
+            Ok(replica) => replica,
+            Err(status) => return status,
+        };
+
+        if out_status.is_null() {
+            return libsql_php_error(ERR_NULL_OUT_PTR, ErrorCode::InvalidArguments);
+        }
+
+        unsafe { *out_status = state.background_status() };
+
+        LIBSQL_PHP_OK
+    })
+}
+
+#[cfg(test)]
+mod tests {
+    use std::{
+        ffi::CStr,
+        sync::atomic::{AtomicUsize, Ordering},
+        time::{Duration, Instant},
+    };
+
+    use super::*;
+    use crate::{
+        libsqlphp::bootstrap::remove_replica,
+        utils::testing::{close, connect_offline_replica, last_error, seed_replica, temp_path},
+    };
+
+    unsafe extern "C" fn count_syncs(user_data: *mut libc::c_void, status: libc::c_int, _: i64) {
+        assert_ne!(status, LIBSQL_PHP_OK);
+        unsafe { &*(user_data as *const AtomicUsize) }.fetch_add(1, Ordering::SeqCst);
+    }
+
+    fn status(handle: *mut libc::c_void) -> (libc::c_int, u32, Option<String>) {
+        let mut status = LibsqlPhpBackgroundSyncStatus {
+            running: -1,
+            last_sync_unix_ms: -1,
+            last_error: std::ptr::null(),
+            consecutive_failures: 0,
+        };
+        let result = unsafe { libsql_php_background_sync_status(handle, &mut status) };
+        assert_eq!(result, LIBSQL_PHP_OK, "{}", last_error());
+        let last_error = (!status.last_error.is_null()).then(|| {
+            unsafe { CStr::from_ptr(status.last_error) }
+                .to_string_lossy()
+                .into_owned()
+        });
+        (status.running, status.consecutive_failures, last_error)
+    }
+
+    #[test]
+    fn never_calls_back_after_stopping() {
+        let path = temp_path("background_sync");
+        seed_replica(&path);
+        let (result, handle) = connect_offline_replica(&path);
+        assert_eq!(result, LIBSQL_PHP_OK, "{}", last_error());
+
+        let syncs = AtomicUsize::new(0);
+        let user_data = &syncs as *const AtomicUsize as *mut libc::c_void;
+        let result = unsafe {
+            libsql_php_background_sync_start(handle, 20, 100, Some(count_syncs), user_data)
+        };
+        assert_eq!(result, LIBSQL_PHP_OK, "{}", last_error());
+
+        let started = Instant::now();
+        while syncs.load(Ordering::SeqCst) < 3 {
+            assert!(started.elapsed() < Duration::from_secs(10));
+            std::thread::sleep(Duration::from_millis(10));
+        }
+
+        let (running, failures, error) = status(handle);
+        assert_eq!(running, 1);
+        assert!(failures >= 3, "{failures}");
+        assert!(error.is_some());
+
+        assert_eq!(
+            unsafe { libsql_php_background_sync_stop(handle) },
+            LIBSQL_PHP_OK
+        );
+        let stopped_at = syncs.load(Ordering::SeqCst);
+        std::thread::sleep(Duration::from_millis(200));
+        assert_eq!(syncs.load(Ordering::SeqCst), stopped_at);
+        assert_eq!(status(handle).0, 0);
+
+        // Closing the connection while a sync may be in progress stops the background sync first.
+        let result = unsafe {
+            libsql_php_background_sync_start(handle, 1, 100, Some(count_syncs), user_data)
+        };
+        assert_eq!(result, LIBSQL_PHP_OK, "{}", last_error());
+        std::thread::sleep(Duration::from_millis(20));
+        close(handle);
+        let closed_at = syncs.load(Ordering::SeqCst);
+        std::thread::sleep(Duration::from_millis(100));
+        assert_eq!(syncs.load(Ordering::SeqCst), closed_at);
+
+        remove_replica(&path);
+    }
+}
@@ -0,0 +1,351 @@
+use crate::{
+    types::{
+        batch::{BatchStatement, BatchStatementError, BatchStepResult, LibsqlPhpBatchStatement},
+        handle::{ConnectionMode, LibsqlHandle},
+    },
+    utils::{
+        errors::{
+            catch_panic, libsql_php_error, libsql_php_libsql_error, libsql_php_libsql_error_in,
+            ErrorCode, LIBSQL_PHP_OK,
+        },
+        query_result::to_c_json,
+        runtime::runtime,
+    },
+    ERR_INVALID_QUERY_CONVERT, ERR_NULL_BATCH_STATEMENTS, ERR_NULL_CLIENT_PTR, ERR_NULL_OUT_PTR,
+};
+
+/// Why a batch stopped before running every statement.
+enum BatchError {
+    /// The transaction of an atomic batch could not be started.
+    Begin(libsql::Error),
+    /// The statement at the 0-based index failed.
+    Statement(usize, libsql::Error),
+    /// The transaction of an atomic batch could not be committed.
+    Commit(libsql::Error),
+}
+
+/// Executes a batch of statements, each with its own typed parameters, returning the result of every
+/// statement in JSON format.
+///
+/// The statements run in order and the batch stops at the first one that fails. An atomic batch runs
+/// inside a transaction that is rolled back when a statement fails, so either every statement is applied
+/// or none is. A batch that is not atomic keeps the changes of the statements that ran before the failure,
+/// and still reports their results. On a remote connection, each statement of a batch that is not atomic
+/// runs in its own transaction.
+///
+/// # Safety
+///
+/// This function manipulates raw pointers and interfaces with asynchronous code,
+/// requiring careful handling to ensure memory safety and avoid undefined behavior.
+///
+/// # Arguments
+///
+/// * `client_ptr` - A raw pointer to the `LibsqlHandle` of the connection.
+/// * `statements` - A pointer to an array of `LibsqlPhpBatchStatement` holding the statements to run.
+/// * `statements_len` - The number of statements in the array.
+/// * `atomic` - Whether the statements run inside a transaction.
+/// * `out_json` - A pointer where the null-terminated C string holding the JSON-formatted results is written:
+///   an array with the `rows_affected`, `last_insert_rowid`, `columns` and `rows` of every statement that
+///   ran and was kept. It is written even when a statement of a batch that is not atomic fails, and is set
+///   to null otherwise. Free it with `libsql_php_free_string`.
+/// * `out_failed` - A pointer where the 1-based number of the statement that failed is written, or `0` when
+///   none did. May be null.
+///
+/// # Returns
+///
+/// Returns `0` if every statement ran. If an error occurs, returns the error code and records the error
+/// for `libsql_php_last_error_message`, prefixed with the 1-based number of the statement that failed.
+#[no_mangle]
+pub unsafe extern "C" fn libsql_php_batch(
+    client_ptr: *mut libc::c_void,
+    statements: *const LibsqlPhpBatchStatement,
+    statements_len: usize,
+    atomic: bool,
+    out_json: *mut *const libc::c_char,
+    out_failed: *mut usize,
+) -> libc::c_int {
+    catch_panic(|| {
+        if client_ptr.is_null() {
+            return libsql_php_error(ERR_NULL_CLIENT_PTR, ErrorCode::NullClientPtr);
+        }
+
+        if statements.is_null() && statements_len > 0 {
+            return libsql_php_error(ERR_NULL_BATCH_STATEMENTS, ErrorCode::InvalidArguments);
+        }
+
+        if out_json.is_null() {
+            return libsql_php_error(ERR_NULL_OUT_PTR, ErrorCode::InvalidArguments);
+        }
+
+        unsafe { *out_json = std::ptr::null() };
+        let set_failed = |number: usize| {
+            if !out_failed.is_null() {
+                unsafe { *out_failed = number };
+            }
+        };
+        set_failed(0);
+
+        let handle = unsafe { &*(client_ptr as *mut LibsqlHandle) };
+
+        let raw_statements = if statements_len == 0 {
+            &[][..]
+        } else {
+            unsafe { std::slice::from_raw_parts(statements, statements_len) }
+        };
+
+        let mut batch = Vec::with_capacity(raw_statements.len());
+        for (idx, raw) in raw_statements.iter().enumerate() {
+            let (msg, code) = match unsafe { raw.to_statement() } {
+                Ok(statement) => {
+                    batch.push(statement);
+                    continue;
+                }
+                Err(BatchStatementError::Sql) => (
+                    ERR_INVALID_QUERY_CONVERT.to_string(),
+                    ErrorCode::InvalidQueryConvert,
+                ),
+                Err(BatchStatementError::Parameter(e)) => (e, ErrorCode::InvalidParameter),
+            };
+            set_failed(idx + 1);
+            return libsql_php_error(&format!("Statement {}: {msg}", idx + 1), code);
+        }
+
+        let mut results = Vec::with_capacity(batch.len());
+        let batch_result = runtime().block_on(async {
+            if atomic {
+                run_atomic(&handle.conn, handle.mode, batch, &mut results).await
+            } else {
+                run_all(&handle.conn, handle.mode, batch, &mut results).await
+            }
+        });
+
+        let status = match batch_result {
+            Ok(()) => LIBSQL_PHP_OK,
+            Err(BatchError::Begin(e)) => {
+                libsql_php_libsql_error(&e, ErrorCode::InitiateTransaction)
+            }
+            Err(BatchError::Statement(idx, e)) => {
+                set_failed(idx + 1);
+                libsql_php_libsql_error_in(
+                    &format!("Statement {}", idx + 1),
+                    &e,
+                    ErrorCode::QueryExecution,
+                )
+            }
+            Err(BatchError::Commit(e)) => libsql_php_libsql_error(&e, ErrorCode::TransactionCommit),
+        };
+
+        // An atomic batch that failed was rolled back, so none of its results hold.
+        if status != LIBSQL_PHP_OK && atomic {
+            return status;
+        }
+
+        match to_c_json(&results) {
+            Ok(c_json) => {
+                unsafe { *out_json = c_json.into_raw() };
+                status
+            }
+            Err(e) if status == LIBSQL_PHP_OK => libsql_php_error(&e, ErrorCode::Serialization),
+            Err(_) => status,
+        }
+    })
+}
+
+/// Runs every statement on `conn`, stopping at the first one that fails, and adds their results to
+/// `results`.
+///
+/// On a remote connection outside a transaction, each statement runs in its own transaction so that
+/// its counters can be measured.
+async fn run_all(
+    conn: &libsql::Connection,
+    mode: ConnectionMode,
+    batch: Vec<BatchStatement>,
+    results: &mut Vec<BatchStepResult>,
+) -> Result<(), BatchError> {
+    let own_transaction = mode == ConnectionMode::Remote && conn.is_autocommit();
+
+    for (idx, statement) in batch.into_iter().enumerate() {
+        let result = if own_transaction {
+            run_in_transaction(conn, mode, statement).await
+        } else {
+            statement.run(conn, mode).await
+        };
+        results.push(result.map_err(|e| BatchError::Statement(idx, e))?);
+    }
+    Ok(())
+}
+
+/// Runs one statement inside a transaction of its own.
+async fn run_in_transaction(
+    conn: &libsql::Connection,
+    mode: ConnectionMode,
+    statement: BatchStatement,
+) -> libsql::Result<BatchStepResult> {
+    let trx = conn.transaction().await?;
+    match statement.run(&trx, mode).await {
+        Ok(result) => {
+            trx.commit().await?;
+            Ok(result)
+        }
+        Err(e) => {
+            let _ = trx.rollback().await;
+            Err(e)
+        }
+    }
+}
+
+/// Runs every statement inside a transaction on `conn`, rolling it back if one of them fails.
+async fn run_atomic(
+    conn: &libsql::Connection,
+    mode: ConnectionMode,
+    batch: Vec<BatchStatement>,
+    results: &mut Vec<BatchStepResult>,
+) -> Result<(), BatchError> {
+    let trx = conn.transaction().await.map_err(BatchError::Begin)?;
+
+    match run_all(&trx, mode, batch, results).await {
+        Ok(()) => trx.commit().await.map_err(BatchError::Commit),
+        Err(e) => {
+            let _ = trx.rollback().await;
+            Err(e)
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use std::ffi::CString;
+
+    use super::*;
+    use crate::utils::testing::{close, connect_memory, exec, last_error, query, take_json};
+
+    fn statements(sql: &[&CString]) -> Vec<LibsqlPhpBatchStatement> {
+        sql.iter()
+            .map(|sql| LibsqlPhpBatchStatement {
+                sql: sql.as_ptr(),
+                names: std::ptr::null(),
+                values: std::ptr::null(),
+                values_len: 0,
+            })
+            .collect()
+    }
+
+    fn batch(
+        conn: *mut libc::c_void,
+        sql: &[&str],
+        atomic: bool,
+    ) -> (libc::c_int, usize, Option<serde_json::Value>) {
+        let sql: Vec<CString> = sql.iter().map(|sql| CString::new(*sql).unwrap()).collect();
+        let statements = statements(&sql.iter().collect::<Vec<_>>());
+        let mut json = std::ptr::null();
+        let mut failed = usize::MAX;
+        let status = unsafe {
+            libsql_php_batch(
+                conn,
+                statements.as_ptr(),
+                statements.len(),
+                atomic,
+                &mut json,
+                &mut failed,
+            )
+        };
+        let results = (!json.is_null()).then(|| take_json(json));
+        (status, failed, results)
+    }
+
+    #[test]
+    fn reports_counters_of_each_statement() {
+        let conn = connect_memory();
+        exec(conn, "CREATE TABLE t (id INTEGER PRIMARY KEY, name TEXT)");
+
+        let (status, failed, results) = batch(
+            conn,
+            &[
+                "INSERT INTO t VALUES (1, 'a'), (2, 'b')",
+                "SELECT * FROM t",
+                "UPDATE t SET name = 'c' RETURNING id",
+                "CREATE TABLE u (x)",
+            ],
+            true,
+        );
+        assert_eq!(status, LIBSQL_PHP_OK, "{}", last_error());
+        assert_eq!(failed, 0);
+
+        let results = results.unwrap();
+        let counters: Vec<_> = results
+            .as_array()
+            .unwrap()
+            .iter()
+            .map(|step| {
+                (
+                    step["rows_affected"].as_u64(),
+                    step["last_insert_rowid"].as_i64(),
+                )
+            })
+            .collect();
+        assert_eq!(
+            counters,
+            [
+                (Some(2), Some(2)),
+                (Some(0), Some(2)),
+                (Some(2), Some(2)),
+                (Some(0), Some(2))
+            ]
+        );
+        assert_eq!(results[1]["rows"].as_array().unwrap().len(), 2);
+
+        close(conn);
+    }
+
+    #[test]
+    fn keeps_results_of_a_failed_batch_that_is_not_atomic() {
+        let conn = connect_memory();
+        exec(conn, "CREATE TABLE t (id INTEGER PRIMARY KEY)");
+
+        let sql = [
+            "INSERT INTO t VALUES (1)",
+            "INSERT INTO t VALUES (1)",
+            "INSERT INTO t VALUES (2)",
+        ];
+
+        let (status, failed, results) = batch(conn, &sql, true);
+        assert_eq!(status, ErrorCode::QueryExecution as libc::c_int);
+        assert_eq!(failed, 2);
+        assert!(results.is_none());
+        assert!(last_error().starts_with("Statement 2: "));
+        assert_eq!(
+            query(conn, "SELECT * FROM t")["rows"],
+            serde_json::json!([])
+        );
+
+        let (status, failed, results) = batch(conn, &sql, false);
+        assert_eq!(status, ErrorCode::QueryExecution as libc::c_int);
+        assert_eq!(failed, 2);
+        assert_eq!(results.unwrap().as_array().unwrap().len(), 1);
+        assert_eq!(
+            query(conn, "SELECT * FROM t")["rows"],
+            serde_json::json!([[{"type": 1, "value": 1}]])
+        );
+
+        close(conn);
+    }
+
+    #[test]
+    fn rejects_null_statements() {
+        let conn = connect_memory();
+        let mut json = std::ptr::null();
+        let status = unsafe {
+            libsql_php_batch(
+                conn,
+                std::ptr::null(),
+                1,
+                true,
+                &mut json,
+                std::ptr::null_mut(),
+            )
+        };
+        assert_eq!(status, ErrorCode::InvalidArguments as libc::c_int);
+        assert_eq!(last_error(), ERR_NULL_BATCH_STATEMENTS);
+        close(conn);
+    }
+}
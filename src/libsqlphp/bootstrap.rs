@@ -0,0 +1,445 @@
+use std::path::{Path, PathBuf};
+
+use libsql::{
+    replication::{Frames, SnapshotFile},
+    OpenFlags,
+};
+
+use crate::{
+    utils::{
+        errors::{
+            catch_panic, libsql_php_error, libsql_php_libsql_error, ErrorCode, LIBSQL_PHP_OK,
+        },
+        runtime::runtime,
+    },
+    ERR_BOOTSTRAP_EXISTS, ERR_BOOTSTRAP_KIND, ERR_BOOTSTRAP_NO_INDEX, ERR_BOOTSTRAP_SOURCE_BUSY,
+    ERR_BOOTSTRAP_SOURCE_ENCRYPTED, ERR_INVALID_PATH_CONVERT, ERR_PATH_IS_EMPTY,
+};
+
+/// The source is the database file of another remote replica of the same primary, matching
+/// `LIBSQLPHP_SEED_REPLICA` on the PHP side.
+pub const LIBSQL_PHP_SEED_REPLICA: libc::c_int = 1;
+/// The source is a snapshot file written by the primary, matching `LIBSQLPHP_SEED_SNAPSHOT` on the PHP side.
+pub const LIBSQL_PHP_SEED_SNAPSHOT: libc::c_int = 2;
+
+/// The suffix libsql gives the file where a replica stores the log id and frame number it reached.
+///
+/// The file holds the `WalIndexMetaData` of `libsql_replication` 0.3.1, which libsql 0.3.5 uses: a
+/// 16 bytes log id, then the committed frame number as a `u64`, both little endian, then 8 bytes of
+/// padding. `set_log_id` and `committed_frame_no` rely on that layout, so check it again when
+/// upgrading libsql.
+const WAL_INDEX_SUFFIX: &str = "-client_wal_index";
+
+/// Seeds a new remote replica from a local file, so the first sync only fetches the frames
+/// written after it instead of the whole database.
+///
+/// The replica is opened afterwards with `libsql_php_connect_new_remote_replica` or
+/// `libsql_php_connect_offline_replica` on the same path and the primary the source came from,
+/// and continues syncing from the frame number stored in the source.
+///
+/// A replica source is copied together with its WAL and replication index while holding its write
+/// lock, so syncs of the source wait for the copy and the copy fails if the source is syncing.
+/// Encrypted replicas cannot be read without their key, so they are rejected as a source.
+/// A snapshot source is applied to a new database; snapshots of encrypted databases are not supported.
+///
+/// # Safety
+///
+/// This function is marked as unsafe because it dereferences raw pointers.
+///
+/// # Arguments
+///
+/// * `path` - A pointer to a C-style string with the path of the replica to create.
+/// * `source` - A pointer to a C-style string with the path of the file to seed it from.
+/// * `source_kind` - `LIBSQL_PHP_SEED_REPLICA` or `LIBSQL_PHP_SEED_SNAPSHOT`.
+/// * `out_frame_no` - A pointer where the frame number the replica starts from is written, or `-1`
+///   if the source has not replicated any frame. May be null.
+///
+/// # Returns
+///
+/// Returns `0` on success. If an error occurs, including when a database already exists at `path`,
+/// returns the error code and records the error for `libsql_php_last_error_message`.
+#[no_mangle]
+pub unsafe extern "C" fn libsql_php_bootstrap_replica(
+    path: *const libc::c_char,
+    source: *const libc::c_char,
+    source_kind: libc::c_int,
+    out_frame_no: *mut i64,
+) -> libc::c_int {
+    catch_panic(|| {
+        if path.is_null() || source.is_null() {
+            return libsql_php_error(ERR_PATH_IS_EMPTY, ErrorCode::PathIsEmpty);
+        }
+
+        let (path, source) = match unsafe { (path_from_c(path), path_from_c(source)) } {
+            (Some(path), Some(source)) => (path, source),
+            _ => return libsql_php_error(ERR_INVALID_PATH_CONVERT, ErrorCode::InvalidPathConvert),
+        };
+
+        if path.exists() || wal_index_path(&path).exists() {
+            return libsql_php_error(ERR_BOOTSTRAP_EXISTS, ErrorCode::BootstrapFailed);
+        }
+
+        let frame_no = match source_kind {
+            LIBSQL_PHP_SEED_REPLICA => copy_replica(&source, &path),
+            LIBSQL_PHP_SEED_SNAPSHOT => apply_snapshot(&source, &path),
+            _ => return libsql_php_error(ERR_BOOTSTRAP_KIND, ErrorCode::InvalidArguments),
+        };
+
+        match frame_no {
+            Ok(frame_no) => {
+                if !out_frame_no.is_null() {
+                    unsafe { *out_frame_no = frame_no.map_or(-1, |frame_no| frame_no as i64) };
+                }
+                LIBSQL_PHP_OK
+            }
+            Err(status) => status,
+        }
+    })
+}
+
+/// Copies the database file of a replica with its WAL and replication index, and returns the
+/// frame number stored in the index, or the status to return after the error has been recorded.
+///
+/// The source is locked for writing during the copy, so no sync applies frames to it meanwhile. A
+/// sync that committed its frames but has not stored their frame number in the index yet leaves
+/// frames in the copy past that frame number, which the next sync applies again. That is harmless
+/// since frames are whole pages.
+fn copy_replica(source: &Path, path: &Path) -> Result<Option<u64>, libc::c_int> {
+    if !wal_index_path(source).exists() {
+        return Err(libsql_php_error(
+            ERR_BOOTSTRAP_NO_INDEX,
+            ErrorCode::BootstrapFailed,
+        ));
+    }
+
+    let _lock = lock_source(source)?;
+
+    let index = std::fs::read(wal_index_path(source)).map_err(|e| io_error(&e))?;
+
+    let copy = || -> std::io::Result<()> {
+        std::fs::copy(source, path)?;
+
+        let source_wal = with_suffix(source, "-wal");
+        if source_wal.exists() {
+            std::fs::copy(source_wal, with_suffix(path, "-wal"))?;
+        }
+
+        std::fs::write(wal_index_path(path), &index)
+    };
+
+    if let Err(e) = copy() {
+        remove_replica(path);
+        return Err(io_error(&e));
+    }
+
+    Ok(committed_frame_no(&index))
+}
+
+/// Opens the source replica and starts a write transaction on it, which a sync of the replica needs
+/// to apply frames. The transaction is rolled back when the returned connection is dropped.
+///
+/// The schema is read inside the transaction, so a source that cannot be read without a key is
+/// rejected before anything is copied.
+fn lock_source(source: &Path) -> Result<libsql::Connection, libc::c_int> {
+    runtime().block_on(async {
+        let db = libsql::Builder::new_local(source)
+            .flags(OpenFlags::SQLITE_OPEN_READ_WRITE)
+            .build()
+            .await
+            .map_err(|e| libsql_php_libsql_error(&e, ErrorCode::BootstrapFailed))?;
+        let conn = db
+            .connect()
+            .map_err(|e| libsql_php_libsql_error(&e, ErrorCode::BootstrapFailed))?;
+
+        let locked = async {
+            conn.execute("BEGIN IMMEDIATE", ()).await?;
+            let mut rows = conn.query("SELECT count(*) FROM sqlite_schema", ()).await?;
+            rows.next().await.map(|_| ())
+        };
+
+        match locked.await {
+            Ok(()) => Ok(conn),
+            Err(libsql::Error::SqliteFailure(code, _))
+                if code & 0xff == libsql::ffi::SQLITE_BUSY =>
+            {
+                Err(libsql_php_error(
+                    ERR_BOOTSTRAP_SOURCE_BUSY,
+                    ErrorCode::BootstrapFailed,
+                ))
+            }
+            Err(libsql::Error::SqliteFailure(code, _))
+                if code & 0xff == libsql::ffi::SQLITE_NOTADB =>
+            {
+                Err(libsql_php_error(
+                    ERR_BOOTSTRAP_SOURCE_ENCRYPTED,
+                    ErrorCode::BootstrapFailed,
+                ))
+            }
+            Err(e) => Err(libsql_php_libsql_error(&e, ErrorCode::BootstrapFailed)),
+        }
+    })
+}
+
+/// Applies a snapshot to a new database at `path`, records the log id of the snapshot in its
+/// replication index, and returns the frame number it reached, or the status to return after the
+/// error has been recorded.
+fn apply_snapshot(source: &Path, path: &Path) -> Result<Option<u64>, libc::c_int> {
+    let result = runtime().block_on(async {
+        let snapshot = SnapshotFile::open(source, None)
+            .await
+            .map_err(|e| libsql_php_error(&e.to_string(), ErrorCode::BootstrapFailed))?;
+        let log_id = snapshot.header().log_id.get();
+
+        let db = libsql::Builder::new_local_replica(path)
+            .build()
+            .await
+            .map_err(|e| libsql_php_libsql_error(&e, ErrorCode::BootstrapFailed))?;
+        let frame_no = db
+            .sync_frames(Frames::Snapshot(snapshot))
+            .await
+            .map_err(|e| libsql_php_libsql_error(&e, ErrorCode::BootstrapFailed))?;
+        drop(db);
+
+        // A local replica records a log id of 0, which the primary would reject at the first
+        // handshake and replicate the whole database again.
+        set_log_id(path, log_id).map_err(|e| io_error(&e))?;
+
+        Ok(frame_no)
+    });
+
+    if result.is_err() {
+        remove_replica(path);
+    }
+
+    result
+}
+
+/// Overwrites the log id at the start of the replication index of the replica at `path`.
+fn set_log_id(path: &Path, log_id: u128) -> std::io::Result<()> {
+    let index_path = wal_index_path(path);
+    let mut index = std::fs::read(&index_path)?;
+
+    if index.len() < 16 {
+        return Err(std::io::Error::new(
+            std::io::ErrorKind::InvalidData,
+            "replication index is too short",
+        ));
+    }
+
+    index[..16].copy_from_slice(&log_id.to_le_bytes());
+    std::fs::write(index_path, index)
+}
+
+/// Reads the frame number stored in a replication index laid out as described on
+/// [`WAL_INDEX_SUFFIX`], with `u64::MAX` when no frame was replicated.
+fn committed_frame_no(index: &[u8]) -> Option<u64> {
+    let bytes = index.get(16..24)?.try_into().ok()?;
+    Some(u64::from_le_bytes(bytes)).filter(|frame_no| *frame_no != u64::MAX)
+}
+
+/// Removes the files of a replica that could not be seeded, so seeding can be retried.
+pub(crate) fn remove_replica(path: &Path) {
+    for file in [
+        path.to_path_buf(),
+        with_suffix(path, "-wal"),
+        with_suffix(path, "-shm"),
+        wal_index_path(path),
+    ] {
+        let _ = std::fs::remove_file(file);
+    }
+}
+
+fn wal_index_path(path: &Path) -> PathBuf {
+    with_suffix(path, WAL_INDEX_SUFFIX)
+}
+
+fn with_suffix(path: &Path, suffix: &str) -> PathBuf {
+    let mut path = path.as_os_str().to_owned();
+    path.push(suffix);
+    PathBuf::from(path)
+}
+
+fn io_error(e: &std::io::Error) -> libc::c_int {
+    libsql_php_error(&e.to_string(), ErrorCode::BootstrapFailed)
+}
+
+unsafe fn path_from_c(ptr: *const libc::c_char) -> Option<PathBuf> {
+    unsafe { std::ffi::CStr::from_ptr(ptr) }
+        .to_str()
+        .ok()
+        .map(PathBuf::from)
+}
+
+#[cfg(test)]
+mod tests {
+    use std::ffi::CString;
+
+    use super::*;
+    use crate::{
+        libsqlphp::local::libsql_php_connect_local,
+        types::encryption::{LibsqlPhpEncryption, LIBSQL_PHP_CIPHER_AES256CBC, LIBSQL_PHP_KEY_RAW},
+        utils::testing::{
+            close, connect_local, exec, last_error, seed_replica, temp_path, write_snapshot,
+            SNAPSHOT_LOG_ID,
+        },
+    };
+
+    fn bootstrap(path: &Path, source: &Path, kind: libc::c_int) -> (libc::c_int, i64) {
+        let path = CString::new(path.to_str().unwrap()).unwrap();
+        let source = CString::new(source.to_str().unwrap()).unwrap();
+        let mut frame_no = i64::MIN;
+        let status = unsafe {
+            libsql_php_bootstrap_replica(path.as_ptr(), source.as_ptr(), kind, &mut frame_no)
+        };
+        (status, frame_no)
+    }
+
+    /// Opens the replica at `path` without a primary and returns its replication index and rows.
+    fn open_replica(path: &Path) -> (Option<u64>, Vec<String>) {
+        runtime().block_on(async {
+            let db = libsql::Builder::new_local_replica(path)
+                .build()
+                .await
+                .unwrap();
+            let index = db.replication_index().await.unwrap();
+
+            let conn = db.connect().unwrap();
+            let mut rows = conn.query("SELECT name FROM t", ()).await.unwrap();
+            let mut names = Vec::new();
+            while let Some(row) = rows.next().await.unwrap() {
+                names.push(row.get::<String>(0).unwrap());
+            }
+            (index, names)
+        })
+    }
+
+    #[test]
+    fn resumes_from_the_frame_of_a_snapshot() {
+        let snapshot = temp_path("bootstrap_snapshot");
+        let end_frame = write_snapshot(&snapshot);
+        let path = temp_path("bootstrap_from_snapshot");
+
+        let (status, frame_no) = bootstrap(&path, &snapshot, LIBSQL_PHP_SEED_SNAPSHOT);
+        assert_eq!(status, LIBSQL_PHP_OK, "{}", last_error());
+        assert_eq!(frame_no, end_frame as i64);
+
+        let index = std::fs::read(wal_index_path(&path)).unwrap();
+        assert_eq!(index[..16], SNAPSHOT_LOG_ID.to_le_bytes());
+        assert_eq!(committed_frame_no(&index), Some(end_frame));
+
+        let (index, names) = open_replica(&path);
+        assert_eq!(index, Some(end_frame));
+        assert_eq!(names, ["seeded"]);
+
+        let (status, _) = bootstrap(&path, &snapshot, LIBSQL_PHP_SEED_SNAPSHOT);
+        assert_eq!(status, ErrorCode::BootstrapFailed as libc::c_int);
+        assert_eq!(last_error(), ERR_BOOTSTRAP_EXISTS);
+
+        remove_replica(&path);
+        let _ = std::fs::remove_file(snapshot);
+    }
+
+    #[test]
+    fn refuses_to_copy_a_replica_that_is_syncing() {
+        let snapshot = temp_path("bootstrap_busy_snapshot");
+        let end_frame = write_snapshot(&snapshot);
+        let source = temp_path("bootstrap_busy_source");
+        let (status, _) = bootstrap(&source, &snapshot, LIBSQL_PHP_SEED_SNAPSHOT);
+        assert_eq!(status, LIBSQL_PHP_OK, "{}", last_error());
+
+        // A sync holds the write lock of the replica while it applies frames.
+        let syncing = connect_local(source.to_str().unwrap());
+        exec(syncing, "BEGIN IMMEDIATE");
+
+        let path = temp_path("bootstrap_busy_copy");
+        let (status, _) = bootstrap(&path, &source, LIBSQL_PHP_SEED_REPLICA);
+        assert_eq!(status, ErrorCode::BootstrapFailed as libc::c_int);
+        assert_eq!(last_error(), ERR_BOOTSTRAP_SOURCE_BUSY);
+        assert!(!path.exists());
+
+        exec(syncing, "ROLLBACK");
+        close(syncing);
+
+        let (status, frame_no) = bootstrap(&path, &source, LIBSQL_PHP_SEED_REPLICA);
+        assert_eq!(status, LIBSQL_PHP_OK, "{}", last_error());
+        assert_eq!(frame_no, end_frame as i64);
+        assert_eq!(
+            open_replica(&path),
+            (Some(end_frame), vec!["seeded".to_string()])
+        );
+
+        remove_replica(&path);
+        remove_replica(&source);
+        let _ = std::fs::remove_file(snapshot);
+    }
+
+    #[test]
+    fn reads_the_replication_index_libsql_writes() {
+        let snapshot = temp_path("bootstrap_layout_snapshot");
+        let end_frame = write_snapshot(&snapshot);
+        let path = temp_path("bootstrap_layout");
+
+        runtime().block_on(async {
+            let db = libsql::Builder::new_local_replica(&path)
+                .build()
+                .await
+                .unwrap();
+            let snapshot = SnapshotFile::open(&snapshot, None).await.unwrap();
+            db.sync_frames(Frames::Snapshot(snapshot)).await.unwrap();
+        });
+
+        // If libsql changes the layout of the index, seeding a replica breaks: fail here first.
+        let index = std::fs::read(wal_index_path(&path)).unwrap();
+        assert_eq!(index.len(), 32);
+        assert_eq!(index[..16], 0u128.to_le_bytes());
+        assert_eq!(committed_frame_no(&index), Some(end_frame));
+
+        set_log_id(&path, SNAPSHOT_LOG_ID).unwrap();
+        let (index, names) = open_replica(&path);
+        assert_eq!(index, Some(end_frame));
+        assert_eq!(names, ["seeded"]);
+        let index = std::fs::read(wal_index_path(&path)).unwrap();
+        assert_eq!(index[..16], SNAPSHOT_LOG_ID.to_le_bytes());
+
+        remove_replica(&path);
+        let _ = std::fs::remove_file(snapshot);
+    }
+
+    #[test]
+    fn rejects_encrypted_replicas() {
+        let source = temp_path("bootstrap_encrypted_source");
+        let key = b"key";
+        let encryption = LibsqlPhpEncryption {
+            cipher: LIBSQL_PHP_CIPHER_AES256CBC,
+            key_format: LIBSQL_PHP_KEY_RAW,
+            key: key.as_ptr(),
+            key_len: key.len(),
+        };
+        let c_source = CString::new(source.to_str().unwrap()).unwrap();
+        let mut handle = std::ptr::null_mut();
+        let status =
+            unsafe { libsql_php_connect_local(c_source.as_ptr(), 0, &encryption, &mut handle) };
+        assert_eq!(status, LIBSQL_PHP_OK, "{}", last_error());
+        let handle = handle as *mut libc::c_void;
+        exec(
+            handle,
+            "CREATE TABLE t (name TEXT); INSERT INTO t VALUES ('secret')",
+        );
+        close(handle);
+
+        // Give the encrypted file the replication index of a replica.
+        let seeded = temp_path("bootstrap_encrypted_seeded");
+        seed_replica(&seeded);
+        std::fs::copy(wal_index_path(&seeded), wal_index_path(&source)).unwrap();
+        remove_replica(&seeded);
+
+        let path = temp_path("bootstrap_encrypted_copy");
+        let (status, _) = bootstrap(&path, &source, LIBSQL_PHP_SEED_REPLICA);
+        assert_eq!(status, ErrorCode::BootstrapFailed as libc::c_int);
+        assert_eq!(last_error(), ERR_BOOTSTRAP_SOURCE_ENCRYPTED);
+        assert!(!path.exists());
+        assert!(!wal_index_path(&path).exists());
+
+        remove_replica(&source);
+    }
+}
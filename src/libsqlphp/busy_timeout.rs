@@ -0,0 +1,125 @@
+use crate::{
+    types::handle::{ConnectionMode, LibsqlHandle},
+    utils::{
+        errors::{
+            catch_panic, libsql_php_error, libsql_php_libsql_error, ErrorCode, LIBSQL_PHP_OK,
+        },
+        runtime::runtime,
+    },
+    ERR_NULL_CLIENT_PTR, ERR_UNSUPPORTED_MODE,
+};
+
+/// Sets how long SQLite waits for a lock held by another connection before a statement fails with
+/// `SQLITE_BUSY`, using `PRAGMA busy_timeout`.
+///
+/// Remote connections are not supported, because the server manages its own locks. Remote replica
+/// connections are not supported either, because they do not run pragmas that set a value.
+///
+/// # Safety
+///
+/// This function is marked as unsafe because it dereferences raw pointers.
+///
+/// # Arguments
+///
+/// * `client_ptr` - A raw pointer to the `LibsqlHandle` of the connection.
+/// * `timeout_ms` - How long to wait, in milliseconds, or `0` to fail at once.
+///
+/// # Returns
+///
+/// Returns `0` on success. If an error occurs, returns the error code and records the error for
+/// `libsql_php_last_error_message`.
+#[no_mangle]
+pub unsafe extern "C" fn libsql_php_busy_timeout(
+    client_ptr: *mut libc::c_void,
+    timeout_ms: libc::c_uint,
+) -> libc::c_int {
+    catch_panic(|| {
+        if client_ptr.is_null() {
+            return libsql_php_error(ERR_NULL_CLIENT_PTR, ErrorCode::NullClientPtr);
+        }
+
+        let handle = unsafe { &*(client_ptr as *mut LibsqlHandle) };
+
+        if matches!(
+            handle.mode,
+            ConnectionMode::Remote | ConnectionMode::RemoteReplica
+        ) {
+            return libsql_php_error(ERR_UNSUPPORTED_MODE, ErrorCode::UnsupportedMode);
+        }
+
+        let pragma = format!("PRAGMA busy_timeout = {timeout_ms}");
+
+        let result = runtime().block_on(async {
+            let mut rows = handle.conn.query(&pragma, ()).await?;
+            rows.next().await.map(|_| ())
+        });
+
+        match result {
+            Ok(()) => LIBSQL_PHP_OK,
+            Err(e) => libsql_php_libsql_error(&e, ErrorCode::ExecutionFailed),
+        }
+    })
+}
+
+#[cfg(test)]
+mod tests {
+    use std::{
+        ffi::CString,
+        time::{Duration, Instant},
+    };
+
+    use super::*;
+    use crate::{
+        libsqlphp::execute_batch::libsql_php_execute_batch,
+        utils::testing::{close, connect_local, connect_memory, connect_remote, exec, temp_path},
+    };
+
+    #[test]
+    fn rejects_connections_without_local_locks() {
+        let remote = connect_remote("http://127.0.0.1:9");
+        let status = unsafe { libsql_php_busy_timeout(remote, 100) };
+        assert_eq!(status, ErrorCode::UnsupportedMode as libc::c_int);
+        close(remote);
+
+        let memory = connect_memory();
+        assert_eq!(
+            unsafe { libsql_php_busy_timeout(memory, 100) },
+            LIBSQL_PHP_OK
+        );
+        close(memory);
+    }
+
+    #[test]
+    fn waits_for_a_locked_database() {
+        let path = temp_path("busy_timeout");
+        let holder = connect_local(path.to_str().unwrap());
+        let waiter = connect_local(path.to_str().unwrap());
+        exec(holder, "CREATE TABLE t (a INTEGER)");
+
+        let insert = CString::new("INSERT INTO t VALUES (1)").unwrap();
+        exec(holder, "BEGIN IMMEDIATE");
+
+        assert_eq!(unsafe { libsql_php_busy_timeout(waiter, 0) }, LIBSQL_PHP_OK);
+        let status = unsafe { libsql_php_execute_batch(waiter, insert.as_ptr()) };
+        assert_ne!(status, LIBSQL_PHP_OK);
+
+        assert_eq!(
+            unsafe { libsql_php_busy_timeout(waiter, 5000) },
+            LIBSQL_PHP_OK
+        );
+        let started = Instant::now();
+        let waiting = waiter as usize;
+        let write = std::thread::spawn(move || unsafe {
+            libsql_php_execute_batch(waiting as *mut libc::c_void, insert.as_ptr())
+        });
+        std::thread::sleep(Duration::from_millis(200));
+        exec(holder, "COMMIT");
+
+        assert_eq!(write.join().unwrap(), LIBSQL_PHP_OK);
+        assert!(started.elapsed() >= Duration::from_millis(200));
+
+        close(waiter);
+        close(holder);
+        let _ = std::fs::remove_file(path);
+    }
+}
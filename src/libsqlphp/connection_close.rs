@@ -1,7 +1,17 @@
-use crate::{utils::errors::libsql_php_error, ERR_NULL_CLIENT_PTR};
+use std::sync::Weak;
+
+use crate::{
+    types::handle::LibsqlHandle,
+    utils::errors::{catch_panic, libsql_php_error, ErrorCode, LIBSQL_PHP_OK},
+    ERR_NULL_CLIENT_PTR,
+};
 
 /// Calls the `libsql_php_error` function if the provided client pointer is null,
-/// otherwise closes the LibSQL connection and deallocates memory.
+/// otherwise closes the LibSQL connection and its database, and deallocates the handle.
+///
+/// A connection checked out of a pool is closed instead of being reused, and its slot is given back to the pool.
+/// Statements, cursors and transactions opened on the connection stay usable until they are freed with
+/// their own functions.
 ///
 /// # Safety
 ///
@@ -10,23 +20,112 @@ use crate::{utils::errors::libsql_php_error, ERR_NULL_CLIENT_PTR};
 ///
 /// # Arguments
 ///
-/// * `client_ptr` - A raw pointer to the LibSQL connection client.
+/// * `client_ptr` - A raw pointer to the `LibsqlHandle` of the connection.
+///
+/// # Returns
+///
+/// Returns `0` when the connection is closed, or the error code if the client pointer is null.
 ///
 /// # Examples
 ///
-/// ```
+/// ```no_run
 /// use std::ptr;
+/// use sql_php_client::libsqlphp::connection_close::libsql_php_close;
 ///
 /// let client_ptr: *mut std::ffi::c_void = ptr::null_mut();
-/// libsql_php_close(client_ptr);
+/// assert_ne!(unsafe { libsql_php_close(client_ptr) }, 0);
 /// ```
 #[no_mangle]
-pub extern "C" fn libsql_php_close(client_ptr: *mut libc::c_void) {
-    if client_ptr.is_null() {
-        libsql_php_error(ERR_NULL_CLIENT_PTR, "ERR_NULL_CLIENT_PTR");
-        return;
-    }
+pub unsafe extern "C" fn libsql_php_close(client_ptr: *mut libc::c_void) -> libc::c_int {
+    catch_panic(|| {
+        if client_ptr.is_null() {
+            return libsql_php_error(ERR_NULL_CLIENT_PTR, ErrorCode::NullClientPtr);
+        }
+
+        let handle = unsafe { Box::from_raw(client_ptr as *mut LibsqlHandle) };
+
+        // A connection checked out of a pool gives its slot back rather than holding it forever.
+        if let Some(pool) = handle.pool.as_ref().and_then(Weak::upgrade) {
+            pool.release_closed(&*handle);
+        }
+
+        drop(handle);
+        LIBSQL_PHP_OK
+    })
+}
+
+#[cfg(test)]
+mod tests {
+    use std::ffi::CString;
 
-    let client = unsafe { Box::from_raw(client_ptr as *mut libsql::Connection) };
-    drop(client);
+    use super::*;
+    use crate::{
+        libsqlphp::{
+            prepare::libsql_php_prepare,
+            query_open::libsql_php_query_open,
+            rows::{libsql_php_rows_free, libsql_php_rows_next},
+            statement_execute::libsql_php_stmt_execute,
+            statement_finalize::libsql_php_stmt_finalize,
+            transaction_free::libsql_php_transaction_free,
+        },
+        utils::{
+            counting_alloc::assert_no_growth,
+            testing::{begin, close, connect_local, exec, last_error, temp_path, trx_exec},
+        },
+    };
+
+    #[test]
+    fn frees_everything_left_open() {
+        let path = temp_path("close_open_handles");
+        let conn = connect_local(path.to_str().unwrap());
+        exec(
+            conn,
+            "CREATE TABLE t (id INTEGER); INSERT INTO t VALUES (1), (2)",
+        );
+        close(conn);
+
+        let select = CString::new("SELECT id FROM t").unwrap();
+        let update = CString::new("UPDATE t SET id = id").unwrap();
+
+        assert_no_growth(20, || unsafe {
+            let conn = connect_local(path.to_str().unwrap());
+
+            let mut stmt = std::ptr::null_mut();
+            let status = libsql_php_prepare(conn, update.as_ptr(), &mut stmt);
+            assert_eq!(status, LIBSQL_PHP_OK, "{}", last_error());
+            let stmt = stmt as *mut libc::c_void;
+
+            let mut rows = std::ptr::null_mut();
+            let status = libsql_php_query_open(
+                conn,
+                select.as_ptr(),
+                std::ptr::null(),
+                std::ptr::null(),
+                0,
+                &mut rows,
+            );
+            assert_eq!(status, LIBSQL_PHP_OK, "{}", last_error());
+            let rows = rows as *mut libc::c_void;
+            let mut row = std::ptr::null();
+            assert_eq!(libsql_php_rows_next(rows, &mut row), LIBSQL_PHP_OK);
+            assert!(!row.is_null());
+
+            let trx = begin(conn);
+            trx_exec(trx, "INSERT INTO t VALUES (3)");
+
+            assert_eq!(libsql_php_close(conn), LIBSQL_PHP_OK);
+
+            // What the connection handed out stays usable until it is freed.
+            let status = libsql_php_stmt_execute(stmt, std::ptr::null_mut());
+            assert_eq!(status, LIBSQL_PHP_OK, "{}", last_error());
+            assert_eq!(libsql_php_rows_next(rows, &mut row), LIBSQL_PHP_OK);
+            assert!(!row.is_null());
+
+            assert_eq!(libsql_php_rows_free(rows), LIBSQL_PHP_OK);
+            assert_eq!(libsql_php_stmt_finalize(stmt), LIBSQL_PHP_OK);
+            assert_eq!(libsql_php_transaction_free(trx), LIBSQL_PHP_OK);
+        });
+
+        let _ = std::fs::remove_file(path);
+    }
 }
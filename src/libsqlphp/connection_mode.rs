@@ -0,0 +1,100 @@
+use crate::{
+    types::handle::LibsqlHandle,
+    utils::errors::{catch_panic, libsql_php_error, ErrorCode, LIBSQL_PHP_OK},
+    ERR_NULL_CLIENT_PTR, ERR_NULL_OUT_PTR,
+};
+
+/// Retrieves how the database of a LibSQL connection was opened.
+///
+/// # Safety
+///
+/// This function is marked as unsafe because it dereferences raw pointers.
+///
+/// # Arguments
+///
+/// * `client_ptr` - A raw pointer to the `LibsqlHandle` of the connection.
+/// * `out_mode` - A pointer where the `ConnectionMode` value is written:
+///   `1` (local), `2` (memory), `3` (remote) or `4` (remote replica).
+///
+/// # Returns
+///
+/// Returns `0` on success. If a pointer is null, returns the error code and records the error
+/// for `libsql_php_last_error_message`.
+#[no_mangle]
+pub unsafe extern "C" fn libsql_php_connection_mode(
+    client_ptr: *mut libc::c_void,
+    out_mode: *mut libc::c_int,
+) -> libc::c_int {
+    catch_panic(|| {
+        if client_ptr.is_null() {
+            return libsql_php_error(ERR_NULL_CLIENT_PTR, ErrorCode::NullClientPtr);
+        }
+
+        if out_mode.is_null() {
+            return libsql_php_error(ERR_NULL_OUT_PTR, ErrorCode::InvalidArguments);
+        }
+
+        let handle = unsafe { &*(client_ptr as *mut LibsqlHandle) };
+
+        unsafe { *out_mode = handle.mode as libc::c_int };
+        LIBSQL_PHP_OK
+    })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::{
+        libsqlphp::bootstrap::remove_replica,
+        utils::testing::{
+            close, connect_local, connect_memory, connect_offline_replica, connect_remote,
+            last_error, seed_replica, temp_path, UNREACHABLE_URL,
+        },
+    };
+
+    fn mode(conn: *mut libc::c_void) -> libc::c_int {
+        let mut mode = 0;
+        let status = unsafe { libsql_php_connection_mode(conn, &mut mode) };
+        assert_eq!(status, LIBSQL_PHP_OK, "{}", last_error());
+        mode
+    }
+
+    #[test]
+    fn reports_how_the_database_was_opened() {
+        let conn = connect_memory();
+        assert_eq!(mode(conn), 2);
+        close(conn);
+
+        let path = temp_path("connection_mode_local");
+        let conn = connect_local(path.to_str().unwrap());
+        assert_eq!(mode(conn), 1);
+        close(conn);
+        let _ = std::fs::remove_file(path);
+
+        let conn = connect_remote(UNREACHABLE_URL);
+        assert_eq!(mode(conn), 3);
+        close(conn);
+
+        let path = temp_path("connection_mode_replica");
+        seed_replica(&path);
+        let (status, conn) = connect_offline_replica(&path);
+        assert_eq!(status, LIBSQL_PHP_OK, "{}", last_error());
+        assert_eq!(mode(conn), 4);
+        close(conn);
+        remove_replica(&path);
+    }
+
+    #[test]
+    fn rejects_null_pointers() {
+        let mut mode = 0;
+        let status = unsafe { libsql_php_connection_mode(std::ptr::null_mut(), &mut mode) };
+        assert_eq!(status, ErrorCode::NullClientPtr as libc::c_int);
+        assert_eq!(last_error(), ERR_NULL_CLIENT_PTR);
+
+        let conn = connect_memory();
+        let status = unsafe { libsql_php_connection_mode(conn, std::ptr::null_mut()) };
+        assert_eq!(status, ErrorCode::InvalidArguments as libc::c_int);
+        assert_eq!(last_error(), ERR_NULL_OUT_PTR);
+        close(conn);
+    }
+}
@@ -0,0 +1,142 @@
+use crate::{
+    libsqlphp::local::open_local,
+    types::{
+        encryption::LibsqlPhpEncryption,
+        handle::{LibsqlDatabase, LibsqlHandle},
+    },
+    utils::errors::{
+        catch_panic, libsql_php_error, libsql_php_libsql_error, ErrorCode, LIBSQL_PHP_OK,
+    },
+    ERR_NULL_DATABASE_PTR, ERR_NULL_OUT_PTR,
+};
+
+/// Opens a local LibSQL database without connecting to it, so several connections
+/// can be made to the same database object.
+///
+/// # Safety
+///
+/// This function manipulates raw pointers and interfaces with asynchronous code,
+/// requiring careful handling to ensure memory safety and avoid undefined behavior.
+///
+/// # Arguments
+///
+/// * `path` - A pointer to a null-terminated C string representing the path to the database.
+/// * `flags` - A bitmask of `LIBSQL_PHP_OPEN_*` flags for database opening, or `0` for
+///   `LIBSQL_PHP_OPEN_READWRITE | LIBSQL_PHP_OPEN_CREATE`.
+/// * `encryption` - A pointer to the `LibsqlPhpEncryption` settings of an encrypted database,
+///   or a null pointer for an unencrypted one.
+/// * `out_db` - A pointer where the raw pointer to the `LibsqlDatabase` is written.
+///
+/// # Returns
+///
+/// Returns `0` if the database is opened. Otherwise returns the error code
+/// and records the error for `libsql_php_last_error_message`.
+/// The database must be released with `libsql_php_db_close`.
+///
+/// # Examples
+///
+/// ```no_run
+/// use std::ptr;
+/// use sql_php_client::libsqlphp::database::{libsql_php_db_close, libsql_php_db_open};
+/// use sql_php_client::utils::open_flags::{LIBSQL_PHP_OPEN_CREATE, LIBSQL_PHP_OPEN_READWRITE};
+///
+/// let path = "example.db\0".as_ptr().cast();
+/// let flags = LIBSQL_PHP_OPEN_READWRITE | LIBSQL_PHP_OPEN_CREATE;
+/// let mut db_ptr = ptr::null_mut();
+/// let status = unsafe { libsql_php_db_open(path, flags, ptr::null(), &mut db_ptr) };
+/// assert_eq!(status, 0);
+/// unsafe { libsql_php_db_close(db_ptr.cast()) };
+/// ```
+#[no_mangle]
+pub unsafe extern "C" fn libsql_php_db_open(
+    path: *const libc::c_char,
+    flags: libc::c_int,
+    encryption: *const LibsqlPhpEncryption,
+    out_db: *mut *mut LibsqlDatabase,
+) -> libc::c_int {
+    catch_panic(|| {
+        if out_db.is_null() {
+            return libsql_php_error(ERR_NULL_OUT_PTR, ErrorCode::InvalidArguments);
+        }
+
+        match unsafe { open_local(path, flags, encryption) } {
+            Ok(database) => {
+                unsafe { *out_db = Box::into_raw(Box::new(database)) };
+                LIBSQL_PHP_OK
+            }
+            Err(status) => status,
+        }
+    })
+}
+
+/// Opens a new connection to a database opened with `libsql_php_db_open`.
+///
+/// Each connection to a `:memory:` database sees its own, separate database.
+///
+/// # Safety
+///
+/// This function is marked as unsafe because it dereferences raw pointers.
+///
+/// # Arguments
+///
+/// * `db_ptr` - A raw pointer to the `LibsqlDatabase`.
+/// * `out_conn` - A pointer where the raw pointer to the `LibsqlHandle` is written.
+///
+/// # Returns
+///
+/// Returns `0` if the connection is established. Otherwise returns the error code
+/// and records the error for `libsql_php_last_error_message`.
+/// The connection is released with `libsql_php_close` and keeps the database open until then.
+#[no_mangle]
+pub unsafe extern "C" fn libsql_php_db_connect(
+    db_ptr: *mut libc::c_void,
+    out_conn: *mut *mut LibsqlHandle,
+) -> libc::c_int {
+    catch_panic(|| {
+        if db_ptr.is_null() {
+            return libsql_php_error(ERR_NULL_DATABASE_PTR, ErrorCode::NullDatabasePtr);
+        }
+
+        if out_conn.is_null() {
+            return libsql_php_error(ERR_NULL_OUT_PTR, ErrorCode::InvalidArguments);
+        }
+
+        let database = unsafe { &*(db_ptr as *mut LibsqlDatabase) };
+
+        match LibsqlHandle::connect(database) {
+            Ok(handle) => {
+                unsafe { *out_conn = Box::into_raw(Box::new(handle)) };
+                LIBSQL_PHP_OK
+            }
+            Err(e) => libsql_php_libsql_error(&e, ErrorCode::ConnectionFailed),
+        }
+    })
+}
+
+/// Releases a database opened with `libsql_php_db_open`.
+///
+/// Connections and pools made from the database stay usable; the database is closed
+/// once the last of them is released.
+///
+/// # Safety
+///
+/// This function takes ownership of the raw pointer, which must not be used afterwards.
+///
+/// # Arguments
+///
+/// * `db_ptr` - A raw pointer to the `LibsqlDatabase`.
+///
+/// # Returns
+///
+/// Returns `0` when the database is released, or the error code if the pointer is null.
+#[no_mangle]
+pub unsafe extern "C" fn libsql_php_db_close(db_ptr: *mut libc::c_void) -> libc::c_int {
+    catch_panic(|| {
+        if db_ptr.is_null() {
+            return libsql_php_error(ERR_NULL_DATABASE_PTR, ErrorCode::NullDatabasePtr);
+        }
+
+        drop(unsafe { Box::from_raw(db_ptr as *mut LibsqlDatabase) });
+        LIBSQL_PHP_OK
+    })
+}
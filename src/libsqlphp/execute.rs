@@ -1,5 +1,13 @@
 use crate::{
-    utils::{errors::libsql_php_error, runtime::runtime},
+    types::handle::LibsqlHandle,
+    utils::{
+        errors::{
+            catch_panic, libsql_php_error, libsql_php_libsql_error, ErrorCode, LIBSQL_PHP_OK,
+        },
+        params::text_from_raw,
+        retry::with_retry,
+        runtime::runtime,
+    },
     ERR_INVALID_ARGUMENTS, ERR_INVALID_QUERY_CONVERT,
 };
 
@@ -11,79 +19,67 @@ use crate::{
 ///
 /// # Arguments
 ///
-/// * `client_ptr` - A raw pointer to the LibSQL connection client.
+/// * `client_ptr` - A raw pointer to the `LibsqlHandle` of the connection.
 /// * `query` - A raw pointer to a C-style string representing the SQL query to execute.
-/// * `query_params` - A pointer to an array of raw pointers to C-style strings representing query parameters.
+/// * `query_params` - A pointer to an array of raw pointers to C-style strings representing query parameters,
+///   bound as text. A null or non-UTF-8 parameter is rejected with `InvalidParameter`.
 /// * `query_params_len` - The length of the `query_params` array.
+/// * `out_rows_affected` - A pointer where the number of rows affected by the query is written. May be null.
 ///
 /// # Returns
 ///
-/// Returns a pointer to a memory location containing the number of rows affected by the query execution. If an error occurs, returns a null pointer.
+/// Returns `0` on success. If an error occurs, returns the error code and records the error for `libsql_php_last_error_message`.
 #[no_mangle]
-pub extern "C" fn libsql_php_exec(
+pub unsafe extern "C" fn libsql_php_exec(
     client_ptr: *mut libc::c_void,
     query: *const libc::c_char,
     query_params: *const *const libc::c_char,
     query_params_len: usize,
-) -> *const i64 {
-    if client_ptr.is_null() || query.is_null() {
-        libsql_php_error(ERR_INVALID_ARGUMENTS, "ERR_INVALID_ARGUMENTS");
-        return std::ptr::null_mut();
-    }
+    out_rows_affected: *mut u64,
+) -> libc::c_int {
+    catch_panic(|| {
+        if client_ptr.is_null() || query.is_null() {
+            return libsql_php_error(ERR_INVALID_ARGUMENTS, ErrorCode::InvalidArguments);
+        }
 
-    let client = unsafe { &mut *(client_ptr as *mut libsql::Connection) };
+        let handle = unsafe { &*(client_ptr as *mut LibsqlHandle) };
+        let client = &handle.conn;
 
-    let c_str_query = unsafe { std::ffi::CStr::from_ptr(query) };
+        let c_str_query = unsafe { std::ffi::CStr::from_ptr(query) };
 
-    let query_str = match c_str_query.to_str() {
-        Ok(str) => str,
-        Err(_) => {
-            libsql_php_error(ERR_INVALID_QUERY_CONVERT, "ERR_INVALID_QUERY_CONVERT");
-            return std::ptr::null();
-        }
-    };
+        let query_str = match c_str_query.to_str() {
+            Ok(str) => str,
+            Err(_) => {
+                return libsql_php_error(ERR_INVALID_QUERY_CONVERT, ErrorCode::InvalidQueryConvert);
+            }
+        };
 
-    let params = if !query_params.is_null() && query_params_len > 0 {
-        let params_slice = unsafe { std::slice::from_raw_parts(query_params, query_params_len) };
-        params_slice
-            .iter()
-            .filter_map(|&param_ptr| {
-                if param_ptr.is_null() {
-                    None
-                } else {
-                    let param_cstr = unsafe { std::ffi::CStr::from_ptr(param_ptr) };
-                    param_cstr
-                        .to_str()
-                        .ok()
-                        .map(|s| libsql::Value::from(s.to_string()))
-                }
-            })
-            .collect::<Vec<libsql::Value>>()
-    } else {
-        Vec::new()
-    };
+        let params = match unsafe { text_from_raw(query_params, query_params_len) } {
+            Ok(params) => params,
+            Err(e) => return libsql_php_error(&e, ErrorCode::InvalidParameter),
+        };
 
-    let is_empty_or_all_empty_strings = params.iter().all(|value| match value {
-        libsql::Value::Text(s) => s.is_empty(),
-        _ => false,
-    });
+        let is_empty_or_all_empty_strings = params.iter().all(|value| match value {
+            libsql::Value::Text(s) => s.is_empty(),
+            _ => false,
+        });
 
-    let exec_result = runtime().block_on(async {
-        if is_empty_or_all_empty_strings {
-            client.execute(query_str, ()).await
-        } else {
-            client.execute(query_str, params).await
-        }
-    });
+        let exec_result = runtime().block_on(with_retry(&handle.retry, &handle.conn, || async {
+            if is_empty_or_all_empty_strings {
+                client.execute(query_str, ()).await
+            } else {
+                client.execute(query_str, params.clone()).await
+            }
+        }));
 
-    match exec_result {
-        Ok(_) => {
-            let rows_affected = Box::new(client.last_insert_rowid());
-            Box::into_raw(rows_affected)
-        }
-        Err(e) => {
-            libsql_php_error(&format!("Error: {e}"), "ERR_QUERY_EXECUTION");
-            std::ptr::null_mut()
+        match exec_result {
+            Ok(rows_affected) => {
+                if !out_rows_affected.is_null() {
+                    unsafe { *out_rows_affected = rows_affected };
+                }
+                LIBSQL_PHP_OK
+            }
+            Err(e) => libsql_php_libsql_error(&e, ErrorCode::QueryExecution),
         }
-    }
+    })
 }
@@ -0,0 +1,79 @@
+use crate::{
+    types::{handle::LibsqlHandle, value::LibsqlPhpValue},
+    utils::{
+        errors::{
+            catch_panic, libsql_php_error, libsql_php_libsql_error, ErrorCode, LIBSQL_PHP_OK,
+        },
+        params::{named_from_raw, resolve_named},
+        retry::with_retry,
+        runtime::runtime,
+    },
+    ERR_INVALID_ARGUMENTS, ERR_INVALID_QUERY_CONVERT,
+};
+
+/// Executes a SQL query with named parameters (`:name`, `@name` or `$name`), interfacing with a provided LibSQL connection.
+///
+/// # Safety
+///
+/// This function is marked as unsafe because it dereferences raw pointers and performs FFI operations.
+///
+/// # Arguments
+///
+/// * `client_ptr` - A raw pointer to the `LibsqlHandle` of the connection.
+/// * `query` - A raw pointer to a C-style string representing the SQL query to execute.
+/// * `names` - A pointer to an array of C-style strings holding the parameter names, with or without their prefix.
+/// * `values` - A pointer to an array of `LibsqlPhpValue` holding the value of each name.
+/// * `values_len` - The length of the `names` and `values` arrays.
+/// * `out_rows_affected` - A pointer where the number of rows affected by the query is written. May be null.
+///
+/// # Returns
+///
+/// Returns `0` on success. If an error occurs, returns the error code and records the error for `libsql_php_last_error_message`.
+#[no_mangle]
+pub unsafe extern "C" fn libsql_php_exec_named(
+    client_ptr: *mut libc::c_void,
+    query: *const libc::c_char,
+    names: *const *const libc::c_char,
+    values: *const LibsqlPhpValue,
+    values_len: usize,
+    out_rows_affected: *mut u64,
+) -> libc::c_int {
+    catch_panic(|| {
+        if client_ptr.is_null() || query.is_null() {
+            return libsql_php_error(ERR_INVALID_ARGUMENTS, ErrorCode::InvalidArguments);
+        }
+
+        let handle = unsafe { &*(client_ptr as *mut LibsqlHandle) };
+        let client = &handle.conn;
+
+        let c_str_query = unsafe { std::ffi::CStr::from_ptr(query) };
+
+        let query_str = match c_str_query.to_str() {
+            Ok(str) => str,
+            Err(_) => {
+                return libsql_php_error(ERR_INVALID_QUERY_CONVERT, ErrorCode::InvalidQueryConvert);
+            }
+        };
+
+        let params = match unsafe { named_from_raw(names, values, values_len) } {
+            Ok(params) => params,
+            Err(e) => return libsql_php_error(&e, ErrorCode::InvalidParameter),
+        };
+
+        let exec_result = runtime().block_on(with_retry(&handle.retry, &handle.conn, || async {
+            let mut stmt = client.prepare(query_str).await?;
+            let params = resolve_named(&stmt, params.clone());
+            stmt.execute(libsql::params::Params::Named(params)).await
+        }));
+
+        match exec_result {
+            Ok(rows_affected) => {
+                if !out_rows_affected.is_null() {
+                    unsafe { *out_rows_affected = rows_affected as u64 };
+                }
+                LIBSQL_PHP_OK
+            }
+            Err(e) => libsql_php_libsql_error(&e, ErrorCode::QueryExecution),
+        }
+    })
+}
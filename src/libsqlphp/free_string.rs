@@ -0,0 +1,232 @@
+use crate::utils::errors::catch_panic_or;
+
+/// Frees a string returned by the library, such as the JSON written by `libsql_php_query`.
+///
+/// Strings owned by a handle, like the rows of a `RowCursor` or the last error message, are
+/// freed with their owner and must not be passed here.
+///
+/// # Safety
+///
+/// This function is marked as unsafe because it takes ownership of a raw pointer.
+/// The string must not be used after this call.
+///
+/// # Arguments
+///
+/// * `ptr` - A pointer to the string, or a null pointer, which is ignored.
+#[no_mangle]
+pub unsafe extern "C" fn libsql_php_free_string(ptr: *const libc::c_char) {
+    catch_panic_or((), || {
+        if !ptr.is_null() {
+            drop(unsafe { std::ffi::CString::from_raw(ptr as *mut libc::c_char) });
+        }
+    })
+}
+
+#[cfg(test)]
+mod tests {
+    use std::ffi::CString;
+
+    use super::*;
+    use crate::{
+        libsqlphp::{
+            batch::libsql_php_batch,
+            prepare::libsql_php_prepare,
+            query::libsql_php_query,
+            query_open::libsql_php_query_open,
+            rows::{libsql_php_rows_free, libsql_php_rows_next},
+            savepoint::{
+                libsql_php_transaction_release, libsql_php_transaction_rollback_to,
+                libsql_php_transaction_savepoint,
+            },
+            statement_bind::libsql_php_stmt_bind,
+            statement_execute::libsql_php_stmt_execute,
+            statement_finalize::libsql_php_stmt_finalize,
+            statement_query::libsql_php_stmt_query,
+            transaction::libsql_php_transaction,
+            transaction_commit::libsql_php_transaction_commit,
+            transaction_exec::libsql_php_transaction_exec,
+            transaction_free::libsql_php_transaction_free,
+        },
+        types::batch::LibsqlPhpBatchStatement,
+        utils::{
+            counting_alloc::assert_no_growth,
+            errors::LIBSQL_PHP_OK,
+            testing::{c_value, close, connect_memory, exec, last_error},
+        },
+    };
+
+    fn setup() -> *mut libc::c_void {
+        let conn = connect_memory();
+        exec(
+            conn,
+            "CREATE TABLE t (id INTEGER PRIMARY KEY, name TEXT); INSERT INTO t (name) VALUES ('a')",
+        );
+        conn
+    }
+
+    #[test]
+    fn queries_and_transactions_do_not_leak() {
+        let conn = setup();
+        let select = CString::new("SELECT id, name FROM t").unwrap();
+        let insert = CString::new("INSERT INTO t (name) VALUES ('b')").unwrap();
+        let delete = CString::new("DELETE FROM t WHERE name = 'b'").unwrap();
+
+        assert_no_growth(100, || unsafe {
+            let mut json = std::ptr::null();
+            let status = libsql_php_query(conn, select.as_ptr(), std::ptr::null(), 0, &mut json);
+            assert_eq!(status, LIBSQL_PHP_OK, "{}", last_error());
+            libsql_php_free_string(json);
+
+            let mut rows = std::ptr::null_mut();
+            let status = libsql_php_query_open(
+                conn,
+                select.as_ptr(),
+                std::ptr::null(),
+                std::ptr::null(),
+                0,
+                &mut rows,
+            );
+            assert_eq!(status, LIBSQL_PHP_OK, "{}", last_error());
+            loop {
+                let mut row = std::ptr::null();
+                let status = libsql_php_rows_next(rows as *mut libc::c_void, &mut row);
+                assert_eq!(status, LIBSQL_PHP_OK, "{}", last_error());
+                if row.is_null() {
+                    break;
+                }
+            }
+            assert_eq!(
+                libsql_php_rows_free(rows as *mut libc::c_void),
+                LIBSQL_PHP_OK
+            );
+
+            let mut trx = std::ptr::null_mut();
+            let status = libsql_php_transaction(conn, std::ptr::null(), &mut trx);
+            assert_eq!(status, LIBSQL_PHP_OK, "{}", last_error());
+            let trx = trx as *mut libc::c_void;
+            for sql in [&insert, &delete] {
+                let status = libsql_php_transaction_exec(trx, sql.as_ptr(), std::ptr::null(), 0);
+                assert_eq!(status, LIBSQL_PHP_OK, "{}", last_error());
+            }
+            assert_eq!(
+                libsql_php_transaction_commit(trx),
+                LIBSQL_PHP_OK,
+                "{}",
+                last_error()
+            );
+            assert_eq!(libsql_php_transaction_free(trx), LIBSQL_PHP_OK);
+        });
+
+        close(conn);
+    }
+
+    #[test]
+    fn statement_handles_do_not_leak() {
+        let conn = setup();
+        let insert = CString::new("INSERT INTO t (name) VALUES (?)").unwrap();
+        let select = CString::new("SELECT id, name FROM t WHERE name = ?").unwrap();
+        let text = libsql::Value::Text("b".to_string());
+        let name = c_value(&text);
+
+        assert_no_growth(100, || unsafe {
+            for sql in [&insert, &select] {
+                let mut stmt = std::ptr::null_mut();
+                let status = libsql_php_prepare(conn, sql.as_ptr(), &mut stmt);
+                assert_eq!(status, LIBSQL_PHP_OK, "{}", last_error());
+                let stmt = stmt as *mut libc::c_void;
+
+                assert_eq!(libsql_php_stmt_bind(stmt, 1, &name), LIBSQL_PHP_OK);
+                if sql == &insert {
+                    let status = libsql_php_stmt_execute(stmt, std::ptr::null_mut());
+                    assert_eq!(status, LIBSQL_PHP_OK, "{}", last_error());
+                } else {
+                    let mut json = std::ptr::null();
+                    let status = libsql_php_stmt_query(stmt, &mut json);
+                    assert_eq!(status, LIBSQL_PHP_OK, "{}", last_error());
+                    libsql_php_free_string(json);
+                }
+                assert_eq!(libsql_php_stmt_finalize(stmt), LIBSQL_PHP_OK);
+            }
+        });
+
+        close(conn);
+    }
+
+    #[test]
+    fn savepoint_names_do_not_leak() {
+        let conn = setup();
+        let named = CString::new("named").unwrap();
+        let insert = CString::new("INSERT INTO t (name) VALUES ('b')").unwrap();
+
+        assert_no_growth(100, || unsafe {
+            let mut trx = std::ptr::null_mut();
+            let status = libsql_php_transaction(conn, std::ptr::null(), &mut trx);
+            assert_eq!(status, LIBSQL_PHP_OK, "{}", last_error());
+            let trx = trx as *mut libc::c_void;
+
+            let mut generated = std::ptr::null();
+            let status = libsql_php_transaction_savepoint(trx, std::ptr::null(), &mut generated);
+            assert_eq!(status, LIBSQL_PHP_OK, "{}", last_error());
+            let mut name = std::ptr::null();
+            let status = libsql_php_transaction_savepoint(trx, named.as_ptr(), &mut name);
+            assert_eq!(status, LIBSQL_PHP_OK, "{}", last_error());
+
+            let status = libsql_php_transaction_exec(trx, insert.as_ptr(), std::ptr::null(), 0);
+            assert_eq!(status, LIBSQL_PHP_OK, "{}", last_error());
+            assert_eq!(libsql_php_transaction_rollback_to(trx, name), LIBSQL_PHP_OK);
+            assert_eq!(
+                libsql_php_transaction_release(trx, generated),
+                LIBSQL_PHP_OK
+            );
+            libsql_php_free_string(name);
+            libsql_php_free_string(generated);
+
+            assert_eq!(
+                libsql_php_transaction_commit(trx),
+                LIBSQL_PHP_OK,
+                "{}",
+                last_error()
+            );
+            assert_eq!(libsql_php_transaction_free(trx), LIBSQL_PHP_OK);
+        });
+
+        close(conn);
+    }
+
+    #[test]
+    fn batch_results_do_not_leak() {
+        let conn = setup();
+        let insert = CString::new("INSERT INTO t (name) VALUES (?)").unwrap();
+        let select = CString::new("SELECT id, name FROM t").unwrap();
+        let delete = CString::new("DELETE FROM t WHERE name = 'b'").unwrap();
+        let text = libsql::Value::Text("b".to_string());
+        let name = c_value(&text);
+        let statements: Vec<_> = [(&insert, 1), (&select, 0), (&delete, 0)]
+            .into_iter()
+            .map(|(sql, values_len)| LibsqlPhpBatchStatement {
+                sql: sql.as_ptr(),
+                names: std::ptr::null(),
+                values: &name,
+                values_len,
+            })
+            .collect();
+
+        assert_no_growth(100, || unsafe {
+            for atomic in [true, false] {
+                let mut json = std::ptr::null();
+                let status = libsql_php_batch(
+                    conn,
+                    statements.as_ptr(),
+                    statements.len(),
+                    atomic,
+                    &mut json,
+                    std::ptr::null_mut(),
+                );
+                assert_eq!(status, LIBSQL_PHP_OK, "{}", last_error());
+                libsql_php_free_string(json);
+            }
+        });
+
+        close(conn);
+    }
+}
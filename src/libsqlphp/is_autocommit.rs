@@ -1,4 +1,8 @@
-use crate::{utils::errors::libsql_php_error, ERR_NULL_CLIENT_PTR};
+use crate::{
+    types::handle::LibsqlHandle,
+    utils::errors::{catch_panic, libsql_php_error, ErrorCode, LIBSQL_PHP_OK},
+    ERR_NULL_CLIENT_PTR, ERR_NULL_OUT_PTR,
+};
 
 /// Checks if autocommit is enabled for a LibSQL connection in a PHP extension.
 ///
@@ -8,24 +12,30 @@ use crate::{utils::errors::libsql_php_error, ERR_NULL_CLIENT_PTR};
 ///
 /// # Arguments
 ///
-/// * `client_ptr` - A raw pointer to the LibSQL connection client.
+/// * `client_ptr` - A raw pointer to the `LibsqlHandle` of the connection.
+/// * `out_autocommit` - A pointer where `1` is written if autocommit is enabled, `0` otherwise.
 ///
 /// # Returns
 ///
-/// Returns an integer representing whether autocommit is enabled (`1`) or not (`0`). If an error occurs or the client pointer is null, returns `0`.
+/// Returns `0` on success. If the client pointer or the output pointer is null, returns the error code
+/// and records the error for `libsql_php_last_error_message`.
 #[no_mangle]
-pub extern "C" fn libsql_php_is_autocommit(client_ptr: *mut libc::c_void) -> i64 {
-    if client_ptr.is_null() {
-        libsql_php_error(ERR_NULL_CLIENT_PTR, "ERR_NULL_CLIENT_PTR");
-        return 0;
-    }
+pub unsafe extern "C" fn libsql_php_is_autocommit(
+    client_ptr: *mut libc::c_void,
+    out_autocommit: *mut libc::c_int,
+) -> libc::c_int {
+    catch_panic(|| {
+        if client_ptr.is_null() {
+            return libsql_php_error(ERR_NULL_CLIENT_PTR, ErrorCode::NullClientPtr);
+        }
 
-    let client = unsafe { &mut *(client_ptr as *mut libsql::Connection) };
+        if out_autocommit.is_null() {
+            return libsql_php_error(ERR_NULL_OUT_PTR, ErrorCode::InvalidArguments);
+        }
 
-    let is_autocommit = client.is_autocommit();
-    if is_autocommit {
-        1
-    } else {
-        0
-    }
+        let client = unsafe { &(*(client_ptr as *mut LibsqlHandle)).conn };
+
+        unsafe { *out_autocommit = libc::c_int::from(client.is_autocommit()) };
+        LIBSQL_PHP_OK
+    })
 }
@@ -0,0 +1,41 @@
+use crate::utils::errors::{
+    catch_panic, catch_panic_or, clear_last_error, last_error_code, last_error_extended_code,
+    last_error_message,
+};
+
+/// Retrieves the error code of the most recent failing LibSQL call on the current thread.
+///
+/// # Returns
+///
+/// Returns the error code reported by the failing call, or `0` if no error was recorded.
+#[no_mangle]
+pub extern "C" fn libsql_php_last_error_code() -> libc::c_int {
+    catch_panic(last_error_code)
+}
+
+/// Retrieves the SQLite extended result code of the most recent failing LibSQL call on the current thread.
+///
+/// # Returns
+///
+/// Returns the SQLite extended result code, or `0` if the error did not come from SQLite.
+#[no_mangle]
+pub extern "C" fn libsql_php_last_error_extended_code() -> libc::c_int {
+    catch_panic(last_error_extended_code)
+}
+
+/// Retrieves the message of the most recent failing LibSQL call on the current thread.
+///
+/// # Returns
+///
+/// Returns a pointer to a C-style string containing the error message, or a null pointer if no error was recorded.
+/// The string is owned by the library and stays valid until the next error on the same thread.
+#[no_mangle]
+pub extern "C" fn libsql_php_last_error_message() -> *const libc::c_char {
+    catch_panic_or(std::ptr::null(), last_error_message)
+}
+
+/// Clears the error recorded on the current thread.
+#[no_mangle]
+pub extern "C" fn libsql_php_clear_error() {
+    catch_panic_or((), clear_last_error)
+}
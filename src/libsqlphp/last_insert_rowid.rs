@@ -1,4 +1,8 @@
-use crate::{utils::errors::libsql_php_error, ERR_NULL_CLIENT_PTR};
+use crate::{
+    types::handle::LibsqlHandle,
+    utils::errors::{catch_panic, libsql_php_error, ErrorCode, LIBSQL_PHP_OK},
+    ERR_NULL_CLIENT_PTR, ERR_NULL_OUT_PTR,
+};
 
 /// Retrieves the last inserted row ID from a SQL connection in a PHP extension.
 ///
@@ -8,20 +12,30 @@ use crate::{utils::errors::libsql_php_error, ERR_NULL_CLIENT_PTR};
 ///
 /// # Arguments
 ///
-/// * `client_ptr` - A raw pointer to the LibSQL connection client.
+/// * `client_ptr` - A raw pointer to the `LibsqlHandle` of the connection.
+/// * `out_rowid` - A pointer where the last inserted row ID is written.
 ///
 /// # Returns
 ///
-/// Returns the last inserted row ID as an integer. If an error occurs or the client pointer is null, returns `0`.
+/// Returns `0` on success. If the client pointer or the output pointer is null, returns the error code
+/// and records the error for `libsql_php_last_error_message`.
 #[no_mangle]
-pub extern "C" fn libsql_php_last_insert_rowid(client_ptr: *mut libc::c_void) -> i64 {
-    if client_ptr.is_null() {
-        libsql_php_error(ERR_NULL_CLIENT_PTR, "ERR_NULL_CLIENT_PTR");
-        return 0;
-    }
+pub unsafe extern "C" fn libsql_php_last_insert_rowid(
+    client_ptr: *mut libc::c_void,
+    out_rowid: *mut i64,
+) -> libc::c_int {
+    catch_panic(|| {
+        if client_ptr.is_null() {
+            return libsql_php_error(ERR_NULL_CLIENT_PTR, ErrorCode::NullClientPtr);
+        }
 
-    let client = unsafe { &mut *(client_ptr as *mut libsql::Connection) };
+        if out_rowid.is_null() {
+            return libsql_php_error(ERR_NULL_OUT_PTR, ErrorCode::InvalidArguments);
+        }
 
-    let last_insert_rowid = client.last_insert_rowid();
-    last_insert_rowid
+        let client = unsafe { &(*(client_ptr as *mut LibsqlHandle)).conn };
+
+        unsafe { *out_rowid = client.last_insert_rowid() };
+        LIBSQL_PHP_OK
+    })
 }
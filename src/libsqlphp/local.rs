@@ -1,6 +1,18 @@
+use std::sync::Arc;
+
 use crate::{
-    utils::{errors::libsql_php_error, runtime::runtime},
-    ERR_INVALID_PATH_CONVERT, ERR_PATH_IS_EMPTY,
+    types::{
+        encryption::LibsqlPhpEncryption,
+        handle::{ConnectionMode, LibsqlDatabase, LibsqlHandle},
+    },
+    utils::{
+        errors::{
+            catch_panic, libsql_php_error, libsql_php_libsql_error, ErrorCode, LIBSQL_PHP_OK,
+        },
+        open_flags::{open_flags, LIBSQL_PHP_OPEN_MEMORY},
+        runtime::runtime,
+    },
+    ERR_INVALID_PATH_CONVERT, ERR_NULL_OUT_PTR, ERR_PATH_IS_EMPTY,
 };
 
 /// Establishes a connection to a local LibSQL database with optional encryption,
@@ -17,55 +29,82 @@ use crate::{
 /// # Arguments
 ///
 /// * `path` - A pointer to a null-terminated C string representing the path to the database.
-/// * `flags` - A pointer to a null-terminated C string representing the flags for database opening.
-/// * `encryption_key` - A pointer to a null-terminated C string representing the encryption key.
+/// * `flags` - A bitmask of `LIBSQL_PHP_OPEN_*` flags for database opening, or `0` for
+///   `LIBSQL_PHP_OPEN_READWRITE | LIBSQL_PHP_OPEN_CREATE`.
+/// * `encryption` - A pointer to the `LibsqlPhpEncryption` settings of an encrypted database,
+///   or a null pointer for an unencrypted one.
+/// * `out_conn` - A pointer where the raw pointer to the `LibsqlHandle` is written.
 ///
 /// # Returns
 ///
-/// A raw pointer to a `Connection` object if the connection is successfully established,
-/// otherwise a null pointer.
+/// Returns `0` if the connection is successfully established. Otherwise returns the error code
+/// and records the error for `libsql_php_last_error_message`.
 ///
 /// # Examples
 ///
-/// ```
+/// ```no_run
 /// use std::ptr;
+/// use sql_php_client::libsqlphp::{connection_close::libsql_php_close, local::libsql_php_connect_local};
+/// use sql_php_client::utils::open_flags::{LIBSQL_PHP_OPEN_CREATE, LIBSQL_PHP_OPEN_READWRITE};
 ///
-/// let path = "example_path\0".as_ptr();
-/// let flags = "LIBSQLPHP_OPEN_READWRITE\0".as_ptr();
-/// let encryption_key = "example_key\0".as_ptr();
-/// let conn_ptr = libsql_php_connect_local(path, flags, encryption_key);
-/// assert!(!conn_ptr.is_null());
+/// let path = "example.db\0".as_ptr().cast();
+/// let flags = LIBSQL_PHP_OPEN_READWRITE | LIBSQL_PHP_OPEN_CREATE;
+/// let mut conn_ptr = ptr::null_mut();
+/// let status = unsafe { libsql_php_connect_local(path, flags, ptr::null(), &mut conn_ptr) };
+/// assert_eq!(status, 0);
+/// unsafe { libsql_php_close(conn_ptr.cast()) };
 /// ```
 #[no_mangle]
-pub extern "C" fn libsql_php_connect_local(
+pub unsafe extern "C" fn libsql_php_connect_local(
     path: *const libc::c_char,
-    flags: *const libc::c_char,
-    encryption_key: *const libc::c_char,
-) -> *mut libsql::Connection {
-    if path.is_null() {
-        libsql_php_error(ERR_PATH_IS_EMPTY, "ERR_PATH_IS_EMPTY");
-        return std::ptr::null_mut();
-    }
+    flags: libc::c_int,
+    encryption: *const LibsqlPhpEncryption,
+    out_conn: *mut *mut LibsqlHandle,
+) -> libc::c_int {
+    catch_panic(|| {
+        if out_conn.is_null() {
+            return libsql_php_error(ERR_NULL_OUT_PTR, ErrorCode::InvalidArguments);
+        }
 
-    let flags_str = if flags.is_null() {
-        None
-    } else {
-        Some(
-            unsafe { std::ffi::CStr::from_ptr(flags) }
-                .to_str()
-                .unwrap_or(""),
-        )
-    };
+        let database = match unsafe { open_local(path, flags, encryption) } {
+            Ok(database) => database,
+            Err(status) => return status,
+        };
 
-    let open_flags = match flags_str {
-        Some("LIBSQLPHP_OPEN_READONLY") => libsql::OpenFlags::SQLITE_OPEN_READ_ONLY,
-        Some("LIBSQLPHP_OPEN_READWRITE") => libsql::OpenFlags::SQLITE_OPEN_READ_WRITE,
-        Some("LIBSQLPHP_OPEN_CREATE") => libsql::OpenFlags::SQLITE_OPEN_CREATE,
-        Some("LIBSQLPHP_OPEN_READWRITE_LIBSQLPHP_OPEN_CREATE") => libsql::OpenFlags::default(),
-        Some("LIBSQLPHP_OPEN_READONLY_LIBSQLPHP_OPEN_CREATE") => {
-            libsql::OpenFlags::SQLITE_OPEN_READ_ONLY | libsql::OpenFlags::SQLITE_OPEN_CREATE
+        match LibsqlHandle::connect(&database) {
+            Ok(handle) => {
+                unsafe { *out_conn = Box::into_raw(Box::new(handle)) };
+                LIBSQL_PHP_OK
+            }
+            Err(e) => libsql_php_libsql_error(&e, ErrorCode::ConnectionFailed),
         }
-        _ => libsql::OpenFlags::default(),
+    })
+}
+
+/// Opens a local LibSQL database with optional encryption, using the provided path,
+/// flags, and encryption key.
+///
+/// # Safety
+///
+/// `path` must point to a null-terminated C string, and `encryption` must be null
+/// or point to valid `LibsqlPhpEncryption` settings.
+/// Invalid flag combinations are rejected before the database is opened.
+///
+/// # Returns
+///
+/// The opened database, or the status to return after the error has been recorded.
+pub(crate) unsafe fn open_local(
+    path: *const libc::c_char,
+    flags: libc::c_int,
+    encryption: *const LibsqlPhpEncryption,
+) -> Result<LibsqlDatabase, libc::c_int> {
+    if path.is_null() {
+        return Err(libsql_php_error(ERR_PATH_IS_EMPTY, ErrorCode::PathIsEmpty));
+    }
+
+    let open_flags = match open_flags(flags) {
+        Ok(open_flags) => open_flags,
+        Err(e) => return Err(libsql_php_error(e, ErrorCode::InvalidOpenFlags)),
     };
 
     let c_str = unsafe { std::ffi::CStr::from_ptr(path) };
@@ -73,36 +112,112 @@ pub extern "C" fn libsql_php_connect_local(
     let path_str = match c_str.to_str() {
         Ok(str) => str,
         Err(_) => {
-            libsql_php_error(ERR_INVALID_PATH_CONVERT, "ERR_INVALID_PATH_CONVERT");
-            return std::ptr::null_mut();
+            return Err(libsql_php_error(
+                ERR_INVALID_PATH_CONVERT,
+                ErrorCode::InvalidPathConvert,
+            ));
         }
     };
 
-    let encryption_config = if !encryption_key.is_null() {
-        let key_c_str = unsafe { std::ffi::CStr::from_ptr(encryption_key) };
-        let key_str = key_c_str.to_str().unwrap_or("");
-        Some(libsql::EncryptionConfig::new(
-            libsql::Cipher::Aes256Cbc,
-            key_str.as_bytes().to_vec().into(),
-        ))
+    let encryption_config = match unsafe { encryption.as_ref() } {
+        Some(encryption) => match unsafe { encryption.to_config() } {
+            Ok(config) => Some(config),
+            Err(e) => return Err(libsql_php_error(&e, ErrorCode::InvalidEncryption)),
+        },
+        None => None,
+    };
+
+    let mode = if is_memory(path_str, flags) {
+        ConnectionMode::Memory
     } else {
-        None
+        ConnectionMode::Local
     };
 
     let rt = runtime();
 
-    let conn = rt.block_on(async {
+    let db = rt.block_on(async {
         let mut builder = libsql::Builder::new_local(path_str).flags(open_flags);
 
         if let Some(enc_config) = encryption_config {
             builder = builder.encryption_config(enc_config);
         }
 
-        let db = builder.build().await.unwrap();
-        let conn = db.connect().unwrap();
-
-        Box::new(conn)
+        builder.build().await
     });
 
-    Box::into_raw(conn)
+    match db {
+        Ok(db) => Ok(LibsqlDatabase {
+            db: Arc::new(db),
+            mode,
+        }),
+        Err(e) => Err(libsql_php_libsql_error(&e, ErrorCode::ConnectionFailed)),
+    }
+}
+
+/// Returns `true` if the database is opened in memory: with `LIBSQL_PHP_OPEN_MEMORY`, as `:memory:`,
+/// or as a URI filename such as `file::memory:` or `file:name?mode=memory`.
+fn is_memory(path: &str, flags: libc::c_int) -> bool {
+    if flags & LIBSQL_PHP_OPEN_MEMORY != 0 || path == ":memory:" {
+        return true;
+    }
+
+    let Some(uri) = path.strip_prefix("file:") else {
+        return false;
+    };
+
+    let (name, query) = uri.split_once('?').unwrap_or((uri, ""));
+    name == ":memory:" || query.split('&').any(|param| param == "mode=memory")
+}
+
+#[cfg(test)]
+mod tests {
+    use std::ffi::CString;
+
+    use super::*;
+    use crate::{
+        libsqlphp::connection_mode::libsql_php_connection_mode,
+        utils::{
+            open_flags::{LIBSQL_PHP_OPEN_CREATE, LIBSQL_PHP_OPEN_READWRITE, LIBSQL_PHP_OPEN_URI},
+            testing::{close, last_error},
+        },
+    };
+
+    fn mode_of(path: &str, flags: libc::c_int) -> libc::c_int {
+        let path = CString::new(path).unwrap();
+        let mut handle = std::ptr::null_mut();
+        let status = unsafe {
+            libsql_php_connect_local(path.as_ptr(), flags, std::ptr::null(), &mut handle)
+        };
+        assert_eq!(status, LIBSQL_PHP_OK, "{}", last_error());
+
+        let mut mode = 0;
+        let handle = handle as *mut libc::c_void;
+        assert_eq!(
+            unsafe { libsql_php_connection_mode(handle, &mut mode) },
+            LIBSQL_PHP_OK
+        );
+        close(handle);
+        mode
+    }
+
+    #[test]
+    fn detects_in_memory_databases() {
+        let uri = LIBSQL_PHP_OPEN_READWRITE | LIBSQL_PHP_OPEN_CREATE | LIBSQL_PHP_OPEN_URI;
+        let memory = ConnectionMode::Memory as libc::c_int;
+
+        assert_eq!(mode_of(":memory:", 0), memory);
+        assert_eq!(mode_of("file::memory:", uri), memory);
+        assert_eq!(mode_of("file:memdb?mode=memory&cache=shared", uri), memory);
+        let flagged = crate::utils::testing::temp_path("memory_flag");
+        let flags = LIBSQL_PHP_OPEN_READWRITE | LIBSQL_PHP_OPEN_CREATE | LIBSQL_PHP_OPEN_MEMORY;
+        assert_eq!(mode_of(flagged.to_str().unwrap(), flags), memory);
+        assert!(!flagged.exists());
+
+        let path = crate::utils::testing::temp_path("local_mode");
+        assert_eq!(
+            mode_of(path.to_str().unwrap(), 0),
+            ConnectionMode::Local as libc::c_int
+        );
+        let _ = std::fs::remove_file(path);
+    }
 }
@@ -1,16 +1,53 @@
+pub mod last_error;
 pub mod transaction_rollback;
 pub mod transaction_commit;
 pub mod transaction_exec;
+pub mod transaction_exec_values;
+pub mod transaction_exec_named;
 pub mod transaction;
 pub mod last_insert_rowid;
 pub mod is_autocommit;
 pub mod reset;
 pub mod execute_batch;
 pub mod execute;
+pub mod execute_values;
+pub mod execute_named;
 pub mod query;
+pub mod query_values;
+pub mod query_named;
+pub mod query_open;
+pub mod rows;
+pub mod prepare;
+pub mod statement_bind;
+pub mod statement_execute;
+pub mod statement_query;
+pub mod statement_reset;
+pub mod statement_parameters;
+pub mod statement_columns;
+pub mod statement_finalize;
 pub mod local;
+pub mod rekey;
+pub mod database;
+pub mod pool;
 pub mod sync;
+pub mod background_sync;
+pub mod bootstrap;
+pub mod remote;
 pub mod remote_replica;
 pub mod connection_close;
+pub mod connection_mode;
 pub mod affected_rows;
 pub mod version;
+pub mod free_string;
+pub mod transaction_free;
+pub mod transaction_query;
+pub mod transaction_query_values;
+pub mod transaction_query_named;
+pub mod transaction_execute_batch;
+pub mod transaction_prepare;
+pub mod transaction_changes;
+pub mod transaction_last_insert_rowid;
+pub mod savepoint;
+pub mod retry_policy;
+pub mod busy_timeout;
+pub mod batch;
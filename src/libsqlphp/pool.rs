@@ -0,0 +1,408 @@
+use std::time::Duration;
+
+use crate::{
+    types::{
+        handle::{ConnectionMode, LibsqlDatabase, LibsqlHandle},
+        pool::{CheckoutError, ConnectionPool},
+    },
+    utils::errors::{
+        catch_panic, libsql_php_error, libsql_php_libsql_error, ErrorCode, LIBSQL_PHP_OK,
+    },
+    ERR_FOREIGN_CONNECTION, ERR_INVALID_POOL_SIZE, ERR_NULL_CLIENT_PTR, ERR_NULL_DATABASE_PTR,
+    ERR_NULL_OUT_PTR, ERR_NULL_POOL_PTR, ERR_POOL_EXHAUSTED, ERR_UNSUPPORTED_MODE,
+};
+
+/// Creates a bounded pool of connections to a database opened with `libsql_php_db_open`.
+///
+/// # Safety
+///
+/// This function is marked as unsafe because it dereferences raw pointers.
+///
+/// # Arguments
+///
+/// * `db_ptr` - A raw pointer to the `LibsqlDatabase`.
+/// * `max_size` - The maximum number of connections the pool opens.
+/// * `idle_timeout_secs` - How long, in seconds, a checked in connection is kept before it is closed.
+///   `0` keeps idle connections until the pool is freed.
+/// * `out_pool` - A pointer where the raw pointer to the `ConnectionPool` is written.
+///
+/// # Returns
+///
+/// Returns `0` on success. Otherwise returns the error code and records the error for
+/// `libsql_php_last_error_message`. In-memory databases cannot be pooled, because every
+/// connection to them sees a separate database.
+/// The pool must be released with `libsql_php_pool_free`.
+#[no_mangle]
+pub unsafe extern "C" fn libsql_php_pool_new(
+    db_ptr: *mut libc::c_void,
+    max_size: libc::c_uint,
+    idle_timeout_secs: libc::c_uint,
+    out_pool: *mut *mut ConnectionPool,
+) -> libc::c_int {
+    catch_panic(|| {
+        if db_ptr.is_null() {
+            return libsql_php_error(ERR_NULL_DATABASE_PTR, ErrorCode::NullDatabasePtr);
+        }
+
+        if out_pool.is_null() {
+            return libsql_php_error(ERR_NULL_OUT_PTR, ErrorCode::InvalidArguments);
+        }
+
+        if max_size == 0 {
+            return libsql_php_error(ERR_INVALID_POOL_SIZE, ErrorCode::InvalidArguments);
+        }
+
+        let database = unsafe { &*(db_ptr as *mut LibsqlDatabase) };
+
+        if database.mode == ConnectionMode::Memory {
+            return libsql_php_error(ERR_UNSUPPORTED_MODE, ErrorCode::UnsupportedMode);
+        }
+
+        let idle_timeout = match idle_timeout_secs {
+            0 => None,
+            secs => Some(Duration::from_secs(secs.into())),
+        };
+
+        let pool = ConnectionPool::new(database, max_size as usize, idle_timeout);
+
+        unsafe { *out_pool = Box::into_raw(Box::new(pool)) };
+        LIBSQL_PHP_OK
+    })
+}
+
+/// Checks a connection out of a pool, waiting for one to be checked in if the pool is at its max size.
+///
+/// # Safety
+///
+/// This function is marked as unsafe because it dereferences raw pointers.
+///
+/// # Arguments
+///
+/// * `pool_ptr` - A raw pointer to the `ConnectionPool`.
+/// * `timeout_ms` - How long, in milliseconds, to wait for a connection. `0` fails at once when none is available.
+/// * `out_conn` - A pointer where the raw pointer to the `LibsqlHandle` is written.
+///
+/// # Returns
+///
+/// Returns `0` on success. Otherwise returns the error code and records the error for
+/// `libsql_php_last_error_message`.
+/// The connection is used like any other `LibsqlHandle` and is given back with
+/// `libsql_php_pool_checkin`. Closing it with `libsql_php_close` instead frees its slot in the pool
+/// without reusing the connection.
+/// A reused connection gets the default retry policy and busy timeout back on checkin, but any other
+/// state an earlier caller set, such as pragmas run with `libsql_php_exec`, carries over.
+#[no_mangle]
+pub unsafe extern "C" fn libsql_php_pool_checkout(
+    pool_ptr: *mut libc::c_void,
+    timeout_ms: libc::c_uint,
+    out_conn: *mut *mut LibsqlHandle,
+) -> libc::c_int {
+    catch_panic(|| {
+        if pool_ptr.is_null() {
+            return libsql_php_error(ERR_NULL_POOL_PTR, ErrorCode::NullPoolPtr);
+        }
+
+        if out_conn.is_null() {
+            return libsql_php_error(ERR_NULL_OUT_PTR, ErrorCode::InvalidArguments);
+        }
+
+        let pool = unsafe { &*(pool_ptr as *mut ConnectionPool) };
+
+        match pool.checkout(Duration::from_millis(timeout_ms.into())) {
+            Ok(handle) => {
+                unsafe { *out_conn = Box::into_raw(handle) };
+                LIBSQL_PHP_OK
+            }
+            Err(CheckoutError::Exhausted) => {
+                libsql_php_error(ERR_POOL_EXHAUSTED, ErrorCode::PoolExhausted)
+            }
+            Err(CheckoutError::Connect(e)) => {
+                libsql_php_libsql_error(&e, ErrorCode::ConnectionFailed)
+            }
+        }
+    })
+}
+
+/// Gives a connection checked out with `libsql_php_pool_checkout` back to its pool.
+///
+/// A connection left inside a transaction is closed instead of being reused. The retry policy and
+/// busy timeout of a reused connection are reset to their defaults.
+///
+/// # Safety
+///
+/// This function takes ownership of the connection pointer, which must not be used afterwards.
+///
+/// # Arguments
+///
+/// * `pool_ptr` - A raw pointer to the `ConnectionPool`.
+/// * `client_ptr` - A raw pointer to the `LibsqlHandle` of the connection.
+///
+/// # Returns
+///
+/// Returns `0` on success. Otherwise returns the error code and records the error for
+/// `libsql_php_last_error_message`; the connection then still belongs to the caller.
+#[no_mangle]
+pub unsafe extern "C" fn libsql_php_pool_checkin(
+    pool_ptr: *mut libc::c_void,
+    client_ptr: *mut libc::c_void,
+) -> libc::c_int {
+    catch_panic(|| {
+        if pool_ptr.is_null() {
+            return libsql_php_error(ERR_NULL_POOL_PTR, ErrorCode::NullPoolPtr);
+        }
+
+        if client_ptr.is_null() {
+            return libsql_php_error(ERR_NULL_CLIENT_PTR, ErrorCode::NullClientPtr);
+        }
+
+        let pool = unsafe { &*(pool_ptr as *mut ConnectionPool) };
+
+        if !pool.owns(client_ptr as *const LibsqlHandle) {
+            return libsql_php_error(ERR_FOREIGN_CONNECTION, ErrorCode::InvalidArguments);
+        }
+
+        pool.checkin(unsafe { Box::from_raw(client_ptr as *mut LibsqlHandle) });
+        LIBSQL_PHP_OK
+    })
+}
+
+/// Retrieves how many connections of a pool are idle and how many are checked out.
+///
+/// # Safety
+///
+/// This function is marked as unsafe because it dereferences raw pointers.
+///
+/// # Arguments
+///
+/// * `pool_ptr` - A raw pointer to the `ConnectionPool`.
+/// * `out_idle` - A pointer where the number of idle connections is written.
+/// * `out_in_use` - A pointer where the number of checked out connections is written.
+///
+/// # Returns
+///
+/// Returns `0` on success. If a pointer is null, returns the error code and records the error
+/// for `libsql_php_last_error_message`.
+#[no_mangle]
+pub unsafe extern "C" fn libsql_php_pool_stats(
+    pool_ptr: *mut libc::c_void,
+    out_idle: *mut libc::c_uint,
+    out_in_use: *mut libc::c_uint,
+) -> libc::c_int {
+    catch_panic(|| {
+        if pool_ptr.is_null() {
+            return libsql_php_error(ERR_NULL_POOL_PTR, ErrorCode::NullPoolPtr);
+        }
+
+        if out_idle.is_null() || out_in_use.is_null() {
+            return libsql_php_error(ERR_NULL_OUT_PTR, ErrorCode::InvalidArguments);
+        }
+
+        let pool = unsafe { &*(pool_ptr as *mut ConnectionPool) };
+        let (idle, in_use) = pool.stats();
+
+        unsafe {
+            *out_idle = idle as libc::c_uint;
+            *out_in_use = in_use as libc::c_uint;
+        }
+        LIBSQL_PHP_OK
+    })
+}
+
+/// Closes the idle connections of a pool and releases it.
+///
+/// Connections still checked out stay usable and are released with `libsql_php_close`.
+///
+/// # Safety
+///
+/// This function takes ownership of the raw pointer, which must not be used afterwards.
+///
+/// # Arguments
+///
+/// * `pool_ptr` - A raw pointer to the `ConnectionPool`.
+///
+/// # Returns
+///
+/// Returns `0` when the pool is released, or the error code if the pointer is null.
+#[no_mangle]
+pub unsafe extern "C" fn libsql_php_pool_free(pool_ptr: *mut libc::c_void) -> libc::c_int {
+    catch_panic(|| {
+        if pool_ptr.is_null() {
+            return libsql_php_error(ERR_NULL_POOL_PTR, ErrorCode::NullPoolPtr);
+        }
+
+        drop(unsafe { Box::from_raw(pool_ptr as *mut ConnectionPool) });
+        LIBSQL_PHP_OK
+    })
+}
+
+#[cfg(test)]
+mod tests {
+    use std::ffi::CString;
+
+    use super::*;
+    use crate::{
+        libsqlphp::{
+            busy_timeout::libsql_php_busy_timeout,
+            connection_close::libsql_php_close,
+            database::{libsql_php_db_close, libsql_php_db_open},
+            is_autocommit::libsql_php_is_autocommit,
+            retry_policy::{libsql_php_retry_policy, libsql_php_set_retry_policy},
+        },
+        types::retry::LibsqlPhpRetryPolicy,
+        utils::testing::{exec, last_error, query, temp_path},
+    };
+    use serde_json::json;
+
+    fn open_pool(name: &str, max_size: libc::c_uint) -> (*mut libc::c_void, std::path::PathBuf) {
+        let path = temp_path(name);
+        let c_path = CString::new(path.to_str().unwrap()).unwrap();
+        let mut db = std::ptr::null_mut();
+        let status = unsafe { libsql_php_db_open(c_path.as_ptr(), 0, std::ptr::null(), &mut db) };
+        assert_eq!(status, LIBSQL_PHP_OK, "{}", last_error());
+
+        let mut pool = std::ptr::null_mut();
+        let status =
+            unsafe { libsql_php_pool_new(db as *mut libc::c_void, max_size, 0, &mut pool) };
+        assert_eq!(status, LIBSQL_PHP_OK, "{}", last_error());
+        assert_eq!(
+            unsafe { libsql_php_db_close(db as *mut libc::c_void) },
+            LIBSQL_PHP_OK
+        );
+
+        (pool as *mut libc::c_void, path)
+    }
+
+    fn checkout(pool: *mut libc::c_void) -> *mut libc::c_void {
+        let mut conn = std::ptr::null_mut();
+        let status = unsafe { libsql_php_pool_checkout(pool, 0, &mut conn) };
+        assert_eq!(status, LIBSQL_PHP_OK, "{}", last_error());
+        conn as *mut libc::c_void
+    }
+
+    fn stats(pool: *mut libc::c_void) -> (libc::c_uint, libc::c_uint) {
+        let (mut idle, mut in_use) = (0, 0);
+        let status = unsafe { libsql_php_pool_stats(pool, &mut idle, &mut in_use) };
+        assert_eq!(status, LIBSQL_PHP_OK);
+        (idle, in_use)
+    }
+
+    #[test]
+    fn closing_a_checked_out_connection_frees_its_slot() {
+        let (pool, path) = open_pool("pool_close", 1);
+
+        let conn = checkout(pool);
+        assert_eq!(stats(pool), (0, 1));
+        assert_eq!(unsafe { libsql_php_close(conn) }, LIBSQL_PHP_OK);
+        assert_eq!(stats(pool), (0, 0));
+
+        let conn = checkout(pool);
+        assert_eq!(
+            unsafe { libsql_php_pool_checkin(pool, conn) },
+            LIBSQL_PHP_OK
+        );
+        assert_eq!(stats(pool), (1, 0));
+
+        assert_eq!(unsafe { libsql_php_pool_free(pool) }, LIBSQL_PHP_OK);
+        let _ = std::fs::remove_file(path);
+    }
+
+    #[test]
+    fn checkin_resets_the_retry_policy() {
+        let (pool, path) = open_pool("pool_retry", 1);
+
+        let conn = checkout(pool);
+        let policy = LibsqlPhpRetryPolicy {
+            max_attempts: 5,
+            ..LibsqlPhpRetryPolicy::default()
+        };
+        assert_eq!(
+            unsafe { libsql_php_set_retry_policy(conn, &policy) },
+            LIBSQL_PHP_OK
+        );
+        assert_eq!(
+            unsafe { libsql_php_pool_checkin(pool, conn) },
+            LIBSQL_PHP_OK
+        );
+
+        let conn = checkout(pool);
+        let mut policy = LibsqlPhpRetryPolicy {
+            max_attempts: 0,
+            ..LibsqlPhpRetryPolicy::default()
+        };
+        assert_eq!(
+            unsafe { libsql_php_retry_policy(conn, &mut policy) },
+            LIBSQL_PHP_OK
+        );
+        assert_eq!(policy.max_attempts, 1);
+
+        assert_eq!(
+            unsafe { libsql_php_pool_checkin(pool, conn) },
+            LIBSQL_PHP_OK
+        );
+        assert_eq!(unsafe { libsql_php_pool_free(pool) }, LIBSQL_PHP_OK);
+        let _ = std::fs::remove_file(path);
+    }
+
+    #[test]
+    fn checkin_resets_the_busy_timeout() {
+        let (pool, path) = open_pool("pool_busy_timeout", 1);
+
+        let conn = checkout(pool);
+        assert_eq!(
+            unsafe { libsql_php_busy_timeout(conn, 5000) },
+            LIBSQL_PHP_OK
+        );
+        assert_eq!(
+            unsafe { libsql_php_pool_checkin(pool, conn) },
+            LIBSQL_PHP_OK
+        );
+        assert_eq!(stats(pool), (1, 0));
+
+        let conn = checkout(pool);
+        assert_eq!(
+            query(conn, "PRAGMA busy_timeout")["rows"],
+            json!([[{"type": 1, "value": 0}]])
+        );
+
+        assert_eq!(
+            unsafe { libsql_php_pool_checkin(pool, conn) },
+            LIBSQL_PHP_OK
+        );
+        assert_eq!(unsafe { libsql_php_pool_free(pool) }, LIBSQL_PHP_OK);
+        let _ = std::fs::remove_file(path);
+    }
+
+    #[test]
+    fn discards_connections_checked_in_inside_a_transaction() {
+        let (pool, path) = open_pool("pool_open_transaction", 1);
+
+        let conn = checkout(pool);
+        exec(conn, "CREATE TABLE t (id INTEGER)");
+        exec(conn, "BEGIN IMMEDIATE; INSERT INTO t VALUES (1);");
+        assert_eq!(
+            unsafe { libsql_php_pool_checkin(pool, conn) },
+            LIBSQL_PHP_OK
+        );
+        assert_eq!(stats(pool), (0, 0));
+
+        // The discarded connection rolled back and released its write lock, so a new one can take it.
+        let conn = checkout(pool);
+        assert_eq!(stats(pool), (0, 1));
+        let mut autocommit = 0;
+        assert_eq!(
+            unsafe { libsql_php_is_autocommit(conn, &mut autocommit) },
+            LIBSQL_PHP_OK
+        );
+        assert_eq!(autocommit, 1);
+        exec(conn, "BEGIN IMMEDIATE; COMMIT;");
+        assert_eq!(query(conn, "SELECT * FROM t")["rows"], json!([]));
+
+        assert_eq!(
+            unsafe { libsql_php_pool_checkin(pool, conn) },
+            LIBSQL_PHP_OK
+        );
+        assert_eq!(stats(pool), (1, 0));
+        assert_eq!(unsafe { libsql_php_pool_free(pool) }, LIBSQL_PHP_OK);
+        let _ = std::fs::remove_file(path);
+    }
+}
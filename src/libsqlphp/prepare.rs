@@ -0,0 +1,239 @@
+use crate::{
+    types::{handle::LibsqlHandle, statement::PreparedStatement},
+    utils::{
+        errors::{
+            catch_panic, libsql_php_error, libsql_php_libsql_error, ErrorCode, LIBSQL_PHP_OK,
+        },
+        runtime::runtime,
+    },
+    ERR_INVALID_ARGUMENTS, ERR_INVALID_QUERY_CONVERT, ERR_NULL_OUT_PTR,
+};
+
+/// Prepares a SQL statement on a LibSQL connection so it can be executed many times without re-parsing.
+///
+/// # Safety
+///
+/// This function is marked as unsafe because it dereferences raw pointers.
+///
+/// # Arguments
+///
+/// * `client_ptr` - A raw pointer to the `LibsqlHandle` of the connection.
+/// * `query` - A raw pointer to a C-style string representing the SQL statement to prepare.
+/// * `out_stmt` - A pointer where the prepared statement handle is written.
+///
+/// # Returns
+///
+/// Returns `0` on success. If an error occurs, returns the error code and records the error for `libsql_php_last_error_message`.
+/// The statement must be released with `libsql_php_stmt_finalize`.
+///
+/// # Examples
+///
+/// ```c
+/// PreparedStatement *stmt = NULL;
+/// if (libsql_php_prepare(conn, "INSERT INTO users (name) VALUES (?)", &stmt) == 0) {
+///     // Bind and execute the statement
+///     libsql_php_stmt_finalize(stmt);
+/// }
+/// ```
+#[no_mangle]
+pub unsafe extern "C" fn libsql_php_prepare(
+    client_ptr: *mut libc::c_void,
+    query: *const libc::c_char,
+    out_stmt: *mut *mut PreparedStatement,
+) -> libc::c_int {
+    catch_panic(|| {
+        if client_ptr.is_null() || query.is_null() {
+            return libsql_php_error(ERR_INVALID_ARGUMENTS, ErrorCode::InvalidArguments);
+        }
+
+        if out_stmt.is_null() {
+            return libsql_php_error(ERR_NULL_OUT_PTR, ErrorCode::InvalidArguments);
+        }
+
+        let client = unsafe { &(*(client_ptr as *mut LibsqlHandle)).conn };
+
+        let c_str_query = unsafe { std::ffi::CStr::from_ptr(query) };
+
+        let query_str = match c_str_query.to_str() {
+            Ok(str) => str,
+            Err(_) => {
+                return libsql_php_error(ERR_INVALID_QUERY_CONVERT, ErrorCode::InvalidQueryConvert);
+            }
+        };
+
+        match runtime().block_on(client.prepare(query_str)) {
+            Ok(stmt) => {
+                unsafe { *out_stmt = Box::into_raw(Box::new(PreparedStatement::new(stmt))) };
+                LIBSQL_PHP_OK
+            }
+            Err(e) => libsql_php_libsql_error(&e, ErrorCode::QueryExecution),
+        }
+    })
+}
+
+#[cfg(test)]
+mod tests {
+    use std::ffi::{CStr, CString};
+
+    use serde_json::json;
+
+    use super::*;
+    use crate::{
+        libsqlphp::{
+            statement_bind::{libsql_php_stmt_bind, libsql_php_stmt_bind_named},
+            statement_columns::libsql_php_stmt_columns,
+            statement_execute::libsql_php_stmt_execute,
+            statement_finalize::libsql_php_stmt_finalize,
+            statement_parameters::{
+                libsql_php_stmt_parameter_count, libsql_php_stmt_parameter_name,
+            },
+            statement_query::libsql_php_stmt_query,
+            statement_reset::{libsql_php_stmt_clear_bindings, libsql_php_stmt_reset},
+        },
+        utils::testing::{c_value, close, connect_memory, exec, last_error, query, take_json},
+    };
+
+    fn prepare(conn: *mut libc::c_void, sql: &str) -> *mut libc::c_void {
+        let sql = CString::new(sql).unwrap();
+        let mut stmt = std::ptr::null_mut();
+        let status = unsafe { libsql_php_prepare(conn, sql.as_ptr(), &mut stmt) };
+        assert_eq!(status, LIBSQL_PHP_OK, "{}", last_error());
+        stmt as *mut libc::c_void
+    }
+
+    fn bind(stmt: *mut libc::c_void, index: libc::c_int, value: libsql::Value) -> libc::c_int {
+        unsafe { libsql_php_stmt_bind(stmt, index, &c_value(&value)) }
+    }
+
+    fn bind_named(stmt: *mut libc::c_void, name: &str, value: libsql::Value) {
+        let name = CString::new(name).unwrap();
+        let status = unsafe { libsql_php_stmt_bind_named(stmt, name.as_ptr(), &c_value(&value)) };
+        assert_eq!(status, LIBSQL_PHP_OK, "{}", last_error());
+    }
+
+    fn parameter_name(stmt: *mut libc::c_void, index: libc::c_int) -> Option<String> {
+        let mut name = std::ptr::null();
+        let status = unsafe { libsql_php_stmt_parameter_name(stmt, index, &mut name) };
+        assert_eq!(status, LIBSQL_PHP_OK, "{}", last_error());
+        (!name.is_null()).then(|| {
+            unsafe { CStr::from_ptr(name) }
+                .to_string_lossy()
+                .into_owned()
+        })
+    }
+
+    fn stmt_query(stmt: *mut libc::c_void) -> serde_json::Value {
+        let mut json = std::ptr::null();
+        let status = unsafe { libsql_php_stmt_query(stmt, &mut json) };
+        assert_eq!(status, LIBSQL_PHP_OK, "{}", last_error());
+        take_json(json)
+    }
+
+    #[test]
+    fn executes_a_statement_many_times() {
+        let conn = connect_memory();
+        exec(conn, "CREATE TABLE t (id INTEGER PRIMARY KEY, name TEXT)");
+
+        let insert = prepare(conn, "INSERT INTO t (id, name) VALUES (?, ?)");
+        let mut count = 0;
+        assert_eq!(
+            unsafe { libsql_php_stmt_parameter_count(insert, &mut count) },
+            LIBSQL_PHP_OK
+        );
+        assert_eq!(count, 2);
+        assert_eq!(parameter_name(insert, 1), None);
+
+        for (id, name) in [(1, "a"), (2, "b"), (3, "c")] {
+            assert_eq!(bind(insert, 1, libsql::Value::Integer(id)), LIBSQL_PHP_OK);
+            assert_eq!(
+                bind(insert, 2, libsql::Value::Text(name.to_string())),
+                LIBSQL_PHP_OK
+            );
+            let mut rows_affected = 0;
+            let status = unsafe { libsql_php_stmt_execute(insert, &mut rows_affected) };
+            assert_eq!(status, LIBSQL_PHP_OK, "{}", last_error());
+            assert_eq!(rows_affected, 1);
+            assert_eq!(unsafe { libsql_php_stmt_reset(insert) }, LIBSQL_PHP_OK);
+        }
+
+        // Parameters left unbound are NULL rather than the value of the previous execution.
+        assert_eq!(
+            unsafe { libsql_php_stmt_clear_bindings(insert) },
+            LIBSQL_PHP_OK
+        );
+        assert_eq!(bind(insert, 1, libsql::Value::Integer(4)), LIBSQL_PHP_OK);
+        let status = unsafe { libsql_php_stmt_execute(insert, std::ptr::null_mut()) };
+        assert_eq!(status, LIBSQL_PHP_OK, "{}", last_error());
+
+        let status = bind(insert, 0, libsql::Value::Null);
+        assert_eq!(status, ErrorCode::InvalidParameter as libc::c_int);
+        assert_eq!(unsafe { libsql_php_stmt_finalize(insert) }, LIBSQL_PHP_OK);
+
+        assert_eq!(
+            query(conn, "SELECT name FROM t ORDER BY id")["rows"],
+            json!([
+                [{"type": 3, "value": "a"}],
+                [{"type": 3, "value": "b"}],
+                [{"type": 3, "value": "c"}],
+                [{"type": 5, "value": null}],
+            ])
+        );
+
+        close(conn);
+    }
+
+    #[test]
+    fn queries_with_named_parameters() {
+        let conn = connect_memory();
+        exec(
+            conn,
+            "CREATE TABLE t (id INTEGER PRIMARY KEY, name TEXT);
+             INSERT INTO t VALUES (1, 'a'), (2, 'b'), (3, 'c');",
+        );
+
+        let select = prepare(
+            conn,
+            "SELECT id, name AS label FROM t WHERE id > :min ORDER BY id",
+        );
+        assert_eq!(parameter_name(select, 1).as_deref(), Some(":min"));
+        assert_eq!(parameter_name(select, 0), None);
+        assert_eq!(parameter_name(select, 2), None);
+
+        let mut json = std::ptr::null();
+        assert_eq!(
+            unsafe { libsql_php_stmt_columns(select, &mut json) },
+            LIBSQL_PHP_OK
+        );
+        assert_eq!(
+            take_json(json),
+            json!([
+                {"name": "id", "decltype": "INTEGER", "table": "t", "origin": "id"},
+                {"name": "label", "decltype": "TEXT", "table": "t", "origin": "name"},
+            ])
+        );
+
+        bind_named(select, "min", libsql::Value::Integer(1));
+        assert_eq!(stmt_query(select)["rows"].as_array().unwrap().len(), 2);
+
+        // Binding again replaces the value, whatever prefix the name is given with.
+        bind_named(select, ":min", libsql::Value::Integer(2));
+        assert_eq!(
+            stmt_query(select)["rows"],
+            json!([[{"type": 1, "value": 3}, {"type": 3, "value": "c"}]])
+        );
+
+        assert_eq!(bind(select, 1, libsql::Value::Integer(0)), LIBSQL_PHP_OK);
+        let mut json = std::ptr::null();
+        let status = unsafe { libsql_php_stmt_query(select, &mut json) };
+        assert_eq!(status, ErrorCode::InvalidParameter as libc::c_int);
+
+        assert_eq!(
+            unsafe { libsql_php_stmt_clear_bindings(select) },
+            LIBSQL_PHP_OK
+        );
+        assert_eq!(stmt_query(select)["rows"], json!([]));
+
+        assert_eq!(unsafe { libsql_php_stmt_finalize(select) }, LIBSQL_PHP_OK);
+        close(conn);
+    }
+}
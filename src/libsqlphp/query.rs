@@ -1,6 +1,15 @@
 use crate::{
-    utils::{errors::libsql_php_error, runtime::runtime},
-    ERR_INVALID_QUERY_CONVERT, ERR_NULL_CLIENT_PTR,
+    types::handle::LibsqlHandle,
+    utils::{
+        errors::{
+            catch_panic, libsql_php_error, libsql_php_libsql_error, ErrorCode, LIBSQL_PHP_OK,
+        },
+        params::text_from_raw,
+        query_result::{collect_rows, column_info, into_c_json},
+        retry::with_retry,
+        runtime::runtime,
+    },
+    ERR_INVALID_QUERY_CONVERT, ERR_NULL_CLIENT_PTR, ERR_NULL_OUT_PTR,
 };
 
 /// Executes an LibSQL query using the provided client pointer, query string, and query parameters,
@@ -16,106 +25,170 @@ use crate::{
 ///
 /// # Arguments
 ///
-/// * `client_ptr` - A raw pointer to the SQL connection client.
+/// * `client_ptr` - A raw pointer to the `LibsqlHandle` of the connection.
 /// * `query` - A pointer to a null-terminated C string representing the SQL query.
-/// * `query_params` - A pointer to an array of null-terminated C strings representing query parameters.
+/// * `query_params` - A pointer to an array of null-terminated C strings representing query parameters,
+///   bound as text. A null or non-UTF-8 parameter is rejected with `InvalidParameter`.
 /// * `query_params_len` - The number of query parameters in the array.
+/// * `out_json` - A pointer where the null-terminated C string holding the JSON-formatted query result is written.
+///   Free it with `libsql_php_free_string`.
 ///
 /// # Returns
 ///
-/// A pointer to a null-terminated C string representing the JSON-formatted query result,
-/// or a null pointer if an error occurs.
+/// Returns `0` on success. If an error occurs, returns the error code and records the error
+/// for `libsql_php_last_error_message`.
 ///
 /// # Examples
 ///
 /// ```
 /// use std::ptr;
+/// use sql_php_client::libsqlphp::{
+///     connection_close::libsql_php_close, free_string::libsql_php_free_string,
+///     local::libsql_php_connect_local, query::libsql_php_query,
+/// };
 ///
-/// let client_ptr: *mut std::ffi::c_void = ptr::null_mut();
-/// let query = "SELECT * FROM table\0".as_ptr();
-/// let query_params: *const *const std::os::raw::c_char = ptr::null();
-/// let query_params_len = 0;
-/// let result = libsql_php_query(client_ptr, query, query_params, query_params_len);
-/// assert!(!result.is_null());
+/// let mut conn_ptr = ptr::null_mut();
+/// let path = ":memory:\0".as_ptr().cast();
+/// let status = unsafe { libsql_php_connect_local(path, 0, ptr::null(), &mut conn_ptr) };
+/// assert_eq!(status, 0);
+///
+/// let client_ptr = conn_ptr.cast();
+/// let query = "SELECT 1\0".as_ptr().cast();
+/// let mut result = ptr::null();
+/// let status = unsafe { libsql_php_query(client_ptr, query, ptr::null(), 0, &mut result) };
+/// assert_eq!(status, 0);
+///
+/// unsafe {
+///     libsql_php_free_string(result);
+///     libsql_php_close(client_ptr);
+/// }
 /// ```
 #[no_mangle]
-pub extern "C" fn libsql_php_query(
+pub unsafe extern "C" fn libsql_php_query(
     client_ptr: *mut libc::c_void,
     query: *const libc::c_char,
     query_params: *const *const libc::c_char,
     query_params_len: usize,
-) -> *const libc::c_char {
-    if client_ptr.is_null() || query.is_null() {
-        libsql_php_error(
-            ERR_NULL_CLIENT_PTR,
-            "ERR_NULL_CLIENT_PTR",
-        );
-        return std::ptr::null_mut();
-    }
+    out_json: *mut *const libc::c_char,
+) -> libc::c_int {
+    catch_panic(|| {
+        if client_ptr.is_null() || query.is_null() {
+            return libsql_php_error(ERR_NULL_CLIENT_PTR, ErrorCode::NullClientPtr);
+        }
 
-    let client = unsafe { &mut *(client_ptr as *mut libsql::Connection) };
+        if out_json.is_null() {
+            return libsql_php_error(ERR_NULL_OUT_PTR, ErrorCode::InvalidArguments);
+        }
 
-    let c_str_query = unsafe { std::ffi::CStr::from_ptr(query) };
+        let handle = unsafe { &*(client_ptr as *mut LibsqlHandle) };
+        let client = &handle.conn;
 
-    let query_str = match c_str_query.to_str() {
-        Ok(str) => str,
-        Err(_) => {
-            libsql_php_error(ERR_INVALID_QUERY_CONVERT, "ERR_INVALID_QUERY_CONVERT");
-            return std::ptr::null();
-        }
-    };
+        let c_str_query = unsafe { std::ffi::CStr::from_ptr(query) };
 
-    let params = if !query_params.is_null() && query_params_len > 0 {
-        let params_slice = unsafe { std::slice::from_raw_parts(query_params, query_params_len) };
-        params_slice
-            .iter()
-            .filter_map(|&param_ptr| {
-                if param_ptr.is_null() {
-                    None
-                } else {
-                    let param_cstr = unsafe { std::ffi::CStr::from_ptr(param_ptr) };
-                    param_cstr
-                        .to_str()
-                        .ok()
-                        .map(|s| libsql::Value::from(s.to_string()))
-                }
-            })
-            .collect::<Vec<libsql::Value>>()
-    } else {
-        Vec::new()
-    };
+        let query_str = match c_str_query.to_str() {
+            Ok(str) => str,
+            Err(_) => {
+                return libsql_php_error(ERR_INVALID_QUERY_CONVERT, ErrorCode::InvalidQueryConvert);
+            }
+        };
+
+        let params = match unsafe { text_from_raw(query_params, query_params_len) } {
+            Ok(params) => params,
+            Err(e) => return libsql_php_error(&e, ErrorCode::InvalidParameter),
+        };
+
+        let query_result = runtime().block_on(with_retry(&handle.retry, &handle.conn, || async {
+            let mut stmt = client.prepare(query_str).await?;
+            let columns = column_info(&stmt);
+            let rows = stmt.query(params.clone()).await?;
+            collect_rows(columns, rows).await
+        }));
 
-    let query_result = runtime().block_on(async {
-        match client.query(query_str, params).await {
-            Ok(mut rows) => {
-                let mut results: Vec<std::collections::HashMap<String, libsql::Value>> = Vec::new();
-                while let Ok(Some(row)) = rows.next().await {
-                    let mut result = std::collections::HashMap::new();
-                    for idx in 0..rows.column_count() {
-                        let column_name = row.column_name(idx as i32).unwrap();
-                        let value = row.get_value(idx).unwrap();
-                        result.insert(column_name.to_string(), value);
-                    }
-                    results.push(result);
-                }
-                Ok(results)
+        let results = match query_result {
+            Ok(results) => results,
+            Err(e) => return libsql_php_libsql_error(&e, ErrorCode::QueryExecution),
+        };
+
+        match into_c_json(&results) {
+            Ok(c_json) => {
+                unsafe { *out_json = c_json };
+                LIBSQL_PHP_OK
             }
-            Err(e) => Err(e),
+            Err(e) => libsql_php_error(&e, ErrorCode::Serialization),
         }
-    });
+    })
+}
 
-    match query_result {
-        Ok(results) => {
-            let json = serde_json::to_string(&results).unwrap();
-            let c_json = std::ffi::CString::new(json).unwrap();
-            c_json.into_raw()
-        }
-        Err(e) => {
-            libsql_php_error(
-                &format!("{e}"),
-                "ERR_QUERY_EXECUTION",
-            );
-            std::ptr::null()
+#[cfg(test)]
+mod tests {
+    use std::ffi::CString;
+
+    use super::*;
+    use crate::{
+        libsqlphp::execute::libsql_php_exec,
+        utils::testing::{close, connect_memory, exec, last_error, query, take_json},
+    };
+
+    fn query_with(conn: *mut libc::c_void, params: &[*const libc::c_char]) -> libc::c_int {
+        let sql = CString::new("SELECT ?, ?").unwrap();
+        let mut json = std::ptr::null();
+        let status = unsafe {
+            libsql_php_query(conn, sql.as_ptr(), params.as_ptr(), params.len(), &mut json)
+        };
+        if !json.is_null() {
+            take_json(json);
         }
+        status
+    }
+
+    #[test]
+    fn rejects_null_and_invalid_parameters() {
+        let conn = connect_memory();
+        let a = CString::new("a").unwrap();
+        let b = CString::new("b").unwrap();
+        assert_eq!(
+            query_with(conn, &[a.as_ptr(), b.as_ptr()]),
+            LIBSQL_PHP_OK,
+            "{}",
+            last_error()
+        );
+
+        let status = query_with(conn, &[a.as_ptr(), std::ptr::null()]);
+        assert_eq!(status, ErrorCode::InvalidParameter as libc::c_int);
+        assert_eq!(last_error(), "Parameter 2: value is null");
+
+        let invalid = CString::new(vec![0xff]).unwrap();
+        let status = query_with(conn, &[invalid.as_ptr(), b.as_ptr()]);
+        assert_eq!(status, ErrorCode::InvalidParameter as libc::c_int);
+        assert!(last_error().starts_with("Parameter 1: value is not valid UTF-8"));
+
+        close(conn);
+    }
+
+    #[test]
+    fn never_shifts_parameters_into_other_columns() {
+        let conn = connect_memory();
+        exec(conn, "CREATE TABLE t (a TEXT, b TEXT)");
+
+        // Skipping the null parameter would bind `b` to the first column.
+        let sql = CString::new("INSERT INTO t (a, b) VALUES (?, ?)").unwrap();
+        let b = CString::new("b").unwrap();
+        let params = [std::ptr::null(), b.as_ptr()];
+        let status = unsafe {
+            libsql_php_exec(
+                conn,
+                sql.as_ptr(),
+                params.as_ptr(),
+                params.len(),
+                std::ptr::null_mut(),
+            )
+        };
+        assert_eq!(status, ErrorCode::InvalidParameter as libc::c_int);
+        assert_eq!(
+            query(conn, "SELECT a, b FROM t")["rows"],
+            serde_json::json!([])
+        );
+
+        close(conn);
     }
 }
@@ -0,0 +1,92 @@
+use crate::{
+    types::{handle::LibsqlHandle, value::LibsqlPhpValue},
+    utils::{
+        errors::{
+            catch_panic, libsql_php_error, libsql_php_libsql_error, ErrorCode, LIBSQL_PHP_OK,
+        },
+        params::{named_from_raw, resolve_named},
+        query_result::{collect_rows, column_info, into_c_json},
+        retry::with_retry,
+        runtime::runtime,
+    },
+    ERR_INVALID_QUERY_CONVERT, ERR_NULL_CLIENT_PTR, ERR_NULL_OUT_PTR,
+};
+
+/// Executes an LibSQL query with named parameters (`:name`, `@name` or `$name`), returning the query result in JSON format.
+///
+/// # Safety
+///
+/// This function manipulates raw pointers and interfaces with asynchronous code,
+/// requiring careful handling to ensure memory safety and avoid undefined behavior.
+///
+/// # Arguments
+///
+/// * `client_ptr` - A raw pointer to the `LibsqlHandle` of the connection.
+/// * `query` - A pointer to a null-terminated C string representing the SQL query.
+/// * `names` - A pointer to an array of C-style strings holding the parameter names, with or without their prefix.
+/// * `values` - A pointer to an array of `LibsqlPhpValue` holding the value of each name.
+/// * `values_len` - The length of the `names` and `values` arrays.
+/// * `out_json` - A pointer where the null-terminated C string holding the JSON-formatted query result is written.
+///   Free it with `libsql_php_free_string`.
+///
+/// # Returns
+///
+/// Returns `0` on success. If an error occurs, returns the error code and records the error
+/// for `libsql_php_last_error_message`.
+#[no_mangle]
+pub unsafe extern "C" fn libsql_php_query_named(
+    client_ptr: *mut libc::c_void,
+    query: *const libc::c_char,
+    names: *const *const libc::c_char,
+    values: *const LibsqlPhpValue,
+    values_len: usize,
+    out_json: *mut *const libc::c_char,
+) -> libc::c_int {
+    catch_panic(|| {
+        if client_ptr.is_null() || query.is_null() {
+            return libsql_php_error(ERR_NULL_CLIENT_PTR, ErrorCode::NullClientPtr);
+        }
+
+        if out_json.is_null() {
+            return libsql_php_error(ERR_NULL_OUT_PTR, ErrorCode::InvalidArguments);
+        }
+
+        let handle = unsafe { &*(client_ptr as *mut LibsqlHandle) };
+        let client = &handle.conn;
+
+        let c_str_query = unsafe { std::ffi::CStr::from_ptr(query) };
+
+        let query_str = match c_str_query.to_str() {
+            Ok(str) => str,
+            Err(_) => {
+                return libsql_php_error(ERR_INVALID_QUERY_CONVERT, ErrorCode::InvalidQueryConvert);
+            }
+        };
+
+        let params = match unsafe { named_from_raw(names, values, values_len) } {
+            Ok(params) => params,
+            Err(e) => return libsql_php_error(&e, ErrorCode::InvalidParameter),
+        };
+
+        let query_result = runtime().block_on(with_retry(&handle.retry, &handle.conn, || async {
+            let mut stmt = client.prepare(query_str).await?;
+            let params = resolve_named(&stmt, params.clone());
+            let columns = column_info(&stmt);
+            let rows = stmt.query(libsql::params::Params::Named(params)).await?;
+            collect_rows(columns, rows).await
+        }));
+
+        let results = match query_result {
+            Ok(results) => results,
+            Err(e) => return libsql_php_libsql_error(&e, ErrorCode::QueryExecution),
+        };
+
+        match into_c_json(&results) {
+            Ok(c_json) => {
+                unsafe { *out_json = c_json };
+                LIBSQL_PHP_OK
+            }
+            Err(e) => libsql_php_error(&e, ErrorCode::Serialization),
+        }
+    })
+}
@@ -0,0 +1,107 @@
+use crate::{
+    types::{cursor::RowCursor, handle::LibsqlHandle, value::LibsqlPhpValue},
+    utils::{
+        errors::{
+            catch_panic, libsql_php_error, libsql_php_libsql_error, ErrorCode, LIBSQL_PHP_OK,
+        },
+        params::{named_from_raw, resolve_named, values_from_raw},
+        query_result::column_info,
+        retry::with_retry,
+        runtime::runtime,
+    },
+    ERR_INVALID_QUERY_CONVERT, ERR_NULL_CLIENT_PTR, ERR_NULL_OUT_PTR,
+};
+
+/// Opens a cursor over the rows of a LibSQL query, so they can be read one at a time with `libsql_php_rows_next`.
+///
+/// # Safety
+///
+/// This function manipulates raw pointers and interfaces with asynchronous code,
+/// requiring careful handling to ensure memory safety and avoid undefined behavior.
+///
+/// # Arguments
+///
+/// * `client_ptr` - A raw pointer to the `LibsqlHandle` of the connection.
+/// * `query` - A pointer to a null-terminated C string representing the SQL query.
+/// * `names` - A pointer to an array of C-style strings holding the parameter names, or null for positional parameters.
+/// * `values` - A pointer to an array of `LibsqlPhpValue` representing query parameters.
+/// * `values_len` - The length of the `values` array, and of the `names` array when it is given.
+/// * `out_rows` - A pointer where the cursor handle is written.
+///
+/// # Returns
+///
+/// Returns `0` on success. If an error occurs, returns the error code and records the error
+/// for `libsql_php_last_error_message`. The cursor must be released with `libsql_php_rows_free`
+/// before the connection is closed.
+#[no_mangle]
+pub unsafe extern "C" fn libsql_php_query_open(
+    client_ptr: *mut libc::c_void,
+    query: *const libc::c_char,
+    names: *const *const libc::c_char,
+    values: *const LibsqlPhpValue,
+    values_len: usize,
+    out_rows: *mut *mut RowCursor,
+) -> libc::c_int {
+    catch_panic(|| {
+        if client_ptr.is_null() || query.is_null() {
+            return libsql_php_error(ERR_NULL_CLIENT_PTR, ErrorCode::NullClientPtr);
+        }
+
+        if out_rows.is_null() {
+            return libsql_php_error(ERR_NULL_OUT_PTR, ErrorCode::InvalidArguments);
+        }
+
+        let handle = unsafe { &*(client_ptr as *mut LibsqlHandle) };
+        let client = &handle.conn;
+
+        let c_str_query = unsafe { std::ffi::CStr::from_ptr(query) };
+
+        let query_str = match c_str_query.to_str() {
+            Ok(str) => str,
+            Err(_) => {
+                return libsql_php_error(ERR_INVALID_QUERY_CONVERT, ErrorCode::InvalidQueryConvert);
+            }
+        };
+
+        let named = if names.is_null() {
+            None
+        } else {
+            match unsafe { named_from_raw(names, values, values_len) } {
+                Ok(params) => Some(params),
+                Err(e) => return libsql_php_error(&e, ErrorCode::InvalidParameter),
+            }
+        };
+
+        let positional = match named {
+            Some(_) => Vec::new(),
+            None => match unsafe { values_from_raw(values, values_len) } {
+                Ok(params) => params,
+                Err(e) => return libsql_php_error(&e, ErrorCode::InvalidParameter),
+            },
+        };
+
+        let query_result = runtime().block_on(with_retry(&handle.retry, &handle.conn, || async {
+            let mut stmt = client.prepare(query_str).await?;
+            let columns = column_info(&stmt);
+            let rows = match &named {
+                Some(params) => {
+                    let params = resolve_named(&stmt, params.clone());
+                    stmt.query(libsql::params::Params::Named(params)).await?
+                }
+                None => {
+                    stmt.query(libsql::params::Params::Positional(positional.clone()))
+                        .await?
+                }
+            };
+            Ok::<_, libsql::Error>(RowCursor::new(rows, columns))
+        }));
+
+        match query_result {
+            Ok(cursor) => {
+                unsafe { *out_rows = Box::into_raw(Box::new(cursor)) };
+                LIBSQL_PHP_OK
+            }
+            Err(e) => libsql_php_libsql_error(&e, ErrorCode::QueryExecution),
+        }
+    })
+}
@@ -0,0 +1,190 @@
+use crate::{
+    types::{handle::LibsqlHandle, value::LibsqlPhpValue},
+    utils::{
+        errors::{
+            catch_panic, libsql_php_error, libsql_php_libsql_error, ErrorCode, LIBSQL_PHP_OK,
+        },
+        params::values_from_raw,
+        query_result::{collect_rows, column_info, into_c_json},
+        retry::with_retry,
+        runtime::runtime,
+    },
+    ERR_INVALID_QUERY_CONVERT, ERR_NULL_CLIENT_PTR, ERR_NULL_OUT_PTR,
+};
+
+/// Executes an LibSQL query with typed positional parameters, returning the query result in JSON format.
+///
+/// # Safety
+///
+/// This function manipulates raw pointers and interfaces with asynchronous code,
+/// requiring careful handling to ensure memory safety and avoid undefined behavior.
+///
+/// # Arguments
+///
+/// * `client_ptr` - A raw pointer to the `LibsqlHandle` of the connection.
+/// * `query` - A pointer to a null-terminated C string representing the SQL query.
+/// * `values` - A pointer to an array of `LibsqlPhpValue` representing query parameters.
+/// * `values_len` - The number of query parameters in the array.
+/// * `out_json` - A pointer where the null-terminated C string holding the JSON-formatted query result is written.
+///   Free it with `libsql_php_free_string`.
+///
+/// # Returns
+///
+/// Returns `0` on success. If an error occurs, returns the error code and records the error
+/// for `libsql_php_last_error_message`.
+#[no_mangle]
+pub unsafe extern "C" fn libsql_php_query_values(
+    client_ptr: *mut libc::c_void,
+    query: *const libc::c_char,
+    values: *const LibsqlPhpValue,
+    values_len: usize,
+    out_json: *mut *const libc::c_char,
+) -> libc::c_int {
+    catch_panic(|| {
+        if client_ptr.is_null() || query.is_null() {
+            return libsql_php_error(ERR_NULL_CLIENT_PTR, ErrorCode::NullClientPtr);
+        }
+
+        if out_json.is_null() {
+            return libsql_php_error(ERR_NULL_OUT_PTR, ErrorCode::InvalidArguments);
+        }
+
+        let handle = unsafe { &*(client_ptr as *mut LibsqlHandle) };
+        let client = &handle.conn;
+
+        let c_str_query = unsafe { std::ffi::CStr::from_ptr(query) };
+
+        let query_str = match c_str_query.to_str() {
+            Ok(str) => str,
+            Err(_) => {
+                return libsql_php_error(ERR_INVALID_QUERY_CONVERT, ErrorCode::InvalidQueryConvert);
+            }
+        };
+
+        let params = match unsafe { values_from_raw(values, values_len) } {
+            Ok(params) => params,
+            Err(e) => return libsql_php_error(&e, ErrorCode::InvalidParameter),
+        };
+
+        let query_result = runtime().block_on(with_retry(&handle.retry, &handle.conn, || async {
+            let mut stmt = client.prepare(query_str).await?;
+            let columns = column_info(&stmt);
+            let rows = stmt.query(params.clone()).await?;
+            collect_rows(columns, rows).await
+        }));
+
+        let results = match query_result {
+            Ok(results) => results,
+            Err(e) => return libsql_php_libsql_error(&e, ErrorCode::QueryExecution),
+        };
+
+        match into_c_json(&results) {
+            Ok(c_json) => {
+                unsafe { *out_json = c_json };
+                LIBSQL_PHP_OK
+            }
+            Err(e) => libsql_php_error(&e, ErrorCode::Serialization),
+        }
+    })
+}
+
+#[cfg(test)]
+mod tests {
+    use std::ffi::CString;
+
+    use serde_json::json;
+
+    use super::*;
+    use crate::{
+        types::value::LIBSQL_PHP_TEXT,
+        utils::testing::{c_value, close, connect_memory, last_error, take_json},
+    };
+
+    fn query_values(
+        conn: *mut libc::c_void,
+        sql: &str,
+        values: &[LibsqlPhpValue],
+    ) -> Result<serde_json::Value, libc::c_int> {
+        let sql = CString::new(sql).unwrap();
+        let mut json = std::ptr::null();
+        let status = unsafe {
+            libsql_php_query_values(conn, sql.as_ptr(), values.as_ptr(), values.len(), &mut json)
+        };
+        match status {
+            LIBSQL_PHP_OK => Ok(take_json(json)),
+            status => Err(status),
+        }
+    }
+
+    #[test]
+    fn binds_every_storage_class() {
+        let conn = connect_memory();
+        let values = [
+            libsql::Value::Integer(42),
+            libsql::Value::Real(1.5),
+            libsql::Value::Text("héllo".to_string()),
+            libsql::Value::Blob(vec![0, 1, 255]),
+            libsql::Value::Null,
+        ];
+        let c_values: Vec<LibsqlPhpValue> = values.iter().map(c_value).collect();
+
+        let result = query_values(
+            conn,
+            "SELECT typeof(?1), typeof(?2), typeof(?3), typeof(?4), typeof(?5)",
+            &c_values,
+        )
+        .unwrap_or_else(|_| panic!("{}", last_error()));
+        let types: Vec<&str> = result["rows"][0]
+            .as_array()
+            .unwrap()
+            .iter()
+            .map(|cell| cell["value"].as_str().unwrap())
+            .collect();
+        assert_eq!(types, ["integer", "real", "text", "blob", "null"]);
+
+        let result = query_values(conn, "SELECT ?, ?, ?, ?, ?", &c_values)
+            .unwrap_or_else(|_| panic!("{}", last_error()));
+        assert_eq!(
+            result["rows"],
+            json!([[
+                {"type": 1, "value": 42},
+                {"type": 2, "value": 1.5},
+                {"type": 3, "value": "héllo"},
+                {"type": 4, "value": "AAH/"},
+                {"type": 5, "value": null},
+            ]])
+        );
+
+        close(conn);
+    }
+
+    #[test]
+    fn rejects_values_that_cannot_be_bound() {
+        let conn = connect_memory();
+        let one = c_value(&libsql::Value::Integer(1));
+
+        let mut unknown = c_value(&libsql::Value::Null);
+        unknown.tag = 9;
+        let status = query_values(conn, "SELECT ?, ?", &[one, unknown]).unwrap_err();
+        assert_eq!(status, ErrorCode::InvalidParameter as libc::c_int);
+        assert_eq!(last_error(), "Parameter 2: Unknown parameter type tag: 9");
+
+        let invalid = [0xff, 0xfe];
+        let text = LibsqlPhpValue {
+            tag: LIBSQL_PHP_TEXT,
+            int_value: 0,
+            real_value: 0.0,
+            ptr: invalid.as_ptr(),
+            len: invalid.len(),
+        };
+        let status = query_values(conn, "SELECT ?", &[text]).unwrap_err();
+        assert_eq!(status, ErrorCode::InvalidParameter as libc::c_int);
+        assert!(
+            last_error().starts_with("Parameter 1: Text parameter is not valid UTF-8"),
+            "{}",
+            last_error()
+        );
+
+        close(conn);
+    }
+}
@@ -0,0 +1,175 @@
+use crate::{
+    types::{
+        encryption::LibsqlPhpEncryption,
+        handle::{ConnectionMode, LibsqlHandle},
+    },
+    utils::{
+        errors::{
+            catch_panic, libsql_php_error, libsql_php_libsql_error, ErrorCode, LIBSQL_PHP_OK,
+        },
+        runtime::runtime,
+    },
+    ERR_NULL_CLIENT_PTR, ERR_UNSUPPORTED_MODE,
+};
+
+/// Rotates the encryption key of the database file of a local connection.
+///
+/// Replica connections are rejected with `ErrorCode::UnsupportedMode`: their connections do not
+/// run key pragmas, and re-encrypting the replica file would break the frames the replicator
+/// injects into it. The file is re-encrypted in place, so it must not be in WAL journal mode. Connections opened
+/// afterwards from the same database object still use the key it was opened with, so the database
+/// should be reopened with the new key.
+///
+/// # Safety
+///
+/// This function is marked as unsafe because it dereferences raw pointers.
+///
+/// # Arguments
+///
+/// * `client_ptr` - A raw pointer to the `LibsqlHandle` of the connection.
+/// * `encryption` - A pointer to the new `LibsqlPhpEncryption` settings, or a null pointer to
+///   decrypt the file.
+///
+/// # Returns
+///
+/// Returns `0` on success. If an error occurs, returns the error code and records the error for
+/// `libsql_php_last_error_message`.
+#[no_mangle]
+pub unsafe extern "C" fn libsql_php_rekey(
+    client_ptr: *mut libc::c_void,
+    encryption: *const LibsqlPhpEncryption,
+) -> libc::c_int {
+    catch_panic(|| {
+        if client_ptr.is_null() {
+            return libsql_php_error(ERR_NULL_CLIENT_PTR, ErrorCode::NullClientPtr);
+        }
+
+        let handle = unsafe { &*(client_ptr as *mut LibsqlHandle) };
+
+        if !matches!(handle.mode, ConnectionMode::Local) {
+            return libsql_php_error(ERR_UNSUPPORTED_MODE, ErrorCode::UnsupportedMode);
+        }
+
+        let pragma = match unsafe { encryption.as_ref() } {
+            Some(encryption) => {
+                let key = match unsafe { encryption.to_config() } {
+                    Ok(config) => config.encryption_key,
+                    Err(e) => return libsql_php_error(&e, ErrorCode::InvalidEncryption),
+                };
+                let hex: String = key.iter().map(|byte| format!("{byte:02x}")).collect();
+                format!("PRAGMA hexrekey = '{hex}'")
+            }
+            None => "PRAGMA rekey = ''".to_string(),
+        };
+
+        let result = runtime().block_on(async {
+            let mut rows = handle.conn.query(&pragma, ()).await?;
+            rows.next().await.map(|_| ())
+        });
+
+        match result {
+            Ok(()) => LIBSQL_PHP_OK,
+            Err(e) => libsql_php_libsql_error(&e, ErrorCode::RekeyFailed),
+        }
+    })
+}
+
+#[cfg(test)]
+mod tests {
+    use std::ffi::CString;
+
+    use super::*;
+    use crate::{
+        libsqlphp::{local::libsql_php_connect_local, query_values::libsql_php_query_values},
+        types::encryption::{
+            LIBSQL_PHP_CIPHER_AES256CBC, LIBSQL_PHP_KEY_HEX, LIBSQL_PHP_KEY_PASSPHRASE,
+            LIBSQL_PHP_KEY_RAW,
+        },
+        utils::testing::{close, connect_memory, exec, last_error, take_json, temp_path},
+    };
+
+    fn encryption(key_format: libc::c_int, key: &[u8]) -> LibsqlPhpEncryption {
+        LibsqlPhpEncryption {
+            cipher: LIBSQL_PHP_CIPHER_AES256CBC,
+            key_format,
+            key: key.as_ptr(),
+            key_len: key.len(),
+        }
+    }
+
+    fn connect(path: &std::path::Path, encryption: &LibsqlPhpEncryption) -> *mut libc::c_void {
+        let path = CString::new(path.to_str().unwrap()).unwrap();
+        let mut handle = std::ptr::null_mut();
+        let status = unsafe { libsql_php_connect_local(path.as_ptr(), 0, encryption, &mut handle) };
+        assert_eq!(status, LIBSQL_PHP_OK, "{}", last_error());
+        handle as *mut libc::c_void
+    }
+
+    /// Returns the rows of `t`, or `None` if the file cannot be read with the connection's key.
+    fn rows(handle: *mut libc::c_void) -> Option<serde_json::Value> {
+        let sql = CString::new("SELECT a FROM t").unwrap();
+        let mut json = std::ptr::null();
+        let status = unsafe {
+            libsql_php_query_values(handle, sql.as_ptr(), std::ptr::null(), 0, &mut json)
+        };
+        (status == LIBSQL_PHP_OK).then(|| take_json(json)["rows"].take())
+    }
+
+    fn rotates_key(key_format: libc::c_int, old_key: &[u8], new_key: &[u8]) {
+        let path = temp_path("rekey");
+        let (old, new) = (
+            encryption(key_format, old_key),
+            encryption(key_format, new_key),
+        );
+
+        let handle = connect(&path, &old);
+        exec(
+            handle,
+            "CREATE TABLE t (a INTEGER); INSERT INTO t VALUES (1);",
+        );
+        let status = unsafe { libsql_php_rekey(handle, &new) };
+        assert_eq!(status, LIBSQL_PHP_OK, "{}", last_error());
+        close(handle);
+
+        let handle = connect(&path, &new);
+        assert_eq!(
+            rows(handle),
+            Some(serde_json::json!([[{"type": 1, "value": 1}]]))
+        );
+        close(handle);
+
+        let handle = connect(&path, &old);
+        assert_eq!(rows(handle), None);
+        close(handle);
+
+        let _ = std::fs::remove_file(path);
+    }
+
+    #[test]
+    fn rotates_raw_keys() {
+        rotates_key(LIBSQL_PHP_KEY_RAW, b"old\0raw\xffkey", b"new raw key");
+    }
+
+    #[test]
+    fn rotates_hex_keys() {
+        rotates_key(LIBSQL_PHP_KEY_HEX, b"00ff7d2fa91c", b"A91C7D2F00FF");
+    }
+
+    #[test]
+    fn rotates_passphrase_keys() {
+        rotates_key(
+            LIBSQL_PHP_KEY_PASSPHRASE,
+            b"correct horse battery staple",
+            b"new secret",
+        );
+    }
+
+    #[test]
+    fn rejects_connections_without_a_local_file() {
+        let handle = connect_memory();
+        let new = encryption(LIBSQL_PHP_KEY_RAW, b"key");
+        let status = unsafe { libsql_php_rekey(handle, &new) };
+        assert_eq!(status, ErrorCode::UnsupportedMode as libc::c_int);
+        close(handle);
+    }
+}
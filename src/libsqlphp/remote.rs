@@ -0,0 +1,185 @@
+use std::sync::Arc;
+
+use crate::{
+    types::{
+        handle::{ConnectionMode, LibsqlHandle},
+        retry::LibsqlPhpRetryPolicy,
+    },
+    utils::{
+        errors::{
+            catch_panic, libsql_php_error, libsql_php_libsql_error, ErrorCode, LIBSQL_PHP_OK,
+        },
+        runtime::runtime,
+    },
+    ERR_INVALID_PATH_CONVERT, ERR_NULL_OUT_PTR, ERR_REMOTE_CONFIGURATION,
+};
+
+/// Establishes a connection to a remote LibSQL database over HTTP (Hrana), without a local replica.
+///
+/// # Safety
+///
+/// This function manipulates raw pointers and interfaces with asynchronous code,
+/// requiring careful handling to ensure memory safety and avoid undefined behavior.
+///
+/// # Arguments
+///
+/// * `url` - A pointer to a C-style string representing the URL of the database (`libsql://`, `https://` or `http://`).
+/// * `token` - A pointer to a C-style string representing the authentication token. May be empty.
+/// * `out_conn` - A pointer where the raw pointer to the `LibsqlHandle` is written.
+///
+/// # Returns
+///
+/// Returns `0` if the connection is successfully established. Otherwise returns the error code
+/// and records the error for `libsql_php_last_error_message`. The server is not contacted until
+/// the first statement runs, so an unreachable server is reported by that statement.
+///
+/// # Examples
+///
+/// ```no_run
+/// use std::ptr;
+/// use sql_php_client::libsqlphp::{connection_close::libsql_php_close, remote::libsql_php_connect_remote};
+///
+/// let url = "http://127.0.0.1:8080\0".as_ptr().cast();
+/// let token = "\0".as_ptr().cast();
+/// let mut conn_ptr = ptr::null_mut();
+/// let status = unsafe { libsql_php_connect_remote(url, token, &mut conn_ptr) };
+/// assert_eq!(status, 0);
+/// unsafe { libsql_php_close(conn_ptr.cast()) };
+/// ```
+#[no_mangle]
+pub unsafe extern "C" fn libsql_php_connect_remote(
+    url: *const libc::c_char,
+    token: *const libc::c_char,
+    out_conn: *mut *mut LibsqlHandle,
+) -> libc::c_int {
+    catch_panic(|| {
+        if url.is_null() || token.is_null() {
+            return libsql_php_error(ERR_REMOTE_CONFIGURATION, ErrorCode::RemoteConfiguration);
+        }
+
+        if out_conn.is_null() {
+            return libsql_php_error(ERR_NULL_OUT_PTR, ErrorCode::InvalidArguments);
+        }
+
+        let u_str = unsafe { std::ffi::CStr::from_ptr(url) };
+
+        let url_str = match u_str.to_str() {
+            Ok(str) => str,
+            Err(_) => {
+                return libsql_php_error(ERR_INVALID_PATH_CONVERT, ErrorCode::InvalidPathConvert);
+            }
+        };
+
+        let t_str = unsafe { std::ffi::CStr::from_ptr(token) };
+
+        let token_str = match t_str.to_str() {
+            Ok(str) => str,
+            Err(_) => {
+                return libsql_php_error(ERR_INVALID_PATH_CONVERT, ErrorCode::InvalidPathConvert);
+            }
+        };
+
+        let rt = runtime();
+
+        let handle = rt.block_on(async {
+            let db = libsql::Builder::new_remote(url_str.to_string(), token_str.to_string())
+                .build()
+                .await?;
+            let conn = db.connect()?;
+
+            Ok::<_, libsql::Error>(LibsqlHandle {
+                db: Arc::new(db),
+                conn,
+                mode: ConnectionMode::Remote,
+                replica: None,
+                retry: LibsqlPhpRetryPolicy::default(),
+                pool: None,
+            })
+        });
+
+        match handle {
+            Ok(handle) => {
+                unsafe { *out_conn = Box::into_raw(Box::new(handle)) };
+                LIBSQL_PHP_OK
+            }
+            Err(e) => libsql_php_libsql_error(&e, ErrorCode::ConnectionFailed),
+        }
+    })
+}
+
+#[cfg(test)]
+mod tests {
+    use std::ffi::CString;
+
+    use super::*;
+    use crate::{
+        libsqlphp::{
+            connection_mode::libsql_php_connection_mode, execute::libsql_php_exec,
+            query::libsql_php_query,
+        },
+        utils::{
+            mock_hrana,
+            testing::{close, connect_remote, last_error, take_json},
+        },
+    };
+
+    fn exec(conn: *mut libc::c_void, sql: &str, params: &[&str]) -> (libc::c_int, u64) {
+        let sql = CString::new(sql).unwrap();
+        let params: Vec<CString> = params.iter().map(|p| CString::new(*p).unwrap()).collect();
+        let params: Vec<*const libc::c_char> = params.iter().map(|p| p.as_ptr()).collect();
+        let mut rows_affected = 0;
+        let status = unsafe {
+            libsql_php_exec(
+                conn,
+                sql.as_ptr(),
+                params.as_ptr(),
+                params.len(),
+                &mut rows_affected,
+            )
+        };
+        (status, rows_affected)
+    }
+
+    #[test]
+    fn runs_statements_on_a_hrana_server() {
+        let conn = connect_remote(&mock_hrana::start());
+
+        let mut mode = 0;
+        assert_eq!(
+            unsafe { libsql_php_connection_mode(conn, &mut mode) },
+            LIBSQL_PHP_OK
+        );
+        assert_eq!(mode, ConnectionMode::Remote as libc::c_int);
+
+        let (status, _) = exec(conn, "CREATE TABLE t (name TEXT)", &[]);
+        assert_eq!(status, LIBSQL_PHP_OK, "{}", last_error());
+        let (status, rows_affected) = exec(conn, "INSERT INTO t VALUES (?), (?)", &["a", "b"]);
+        assert_eq!(status, LIBSQL_PHP_OK, "{}", last_error());
+        assert_eq!(rows_affected, 2);
+
+        let sql = CString::new("SELECT name FROM t ORDER BY name").unwrap();
+        let mut json = std::ptr::null();
+        let status =
+            unsafe { libsql_php_query(conn, sql.as_ptr(), std::ptr::null(), 0, &mut json) };
+        assert_eq!(status, LIBSQL_PHP_OK, "{}", last_error());
+        assert_eq!(
+            take_json(json)["rows"],
+            serde_json::json!([[{"type": 3, "value": "a"}], [{"type": 3, "value": "b"}]])
+        );
+
+        let (status, _) = exec(conn, "INSERT INTO missing VALUES (1)", &[]);
+        assert_eq!(status, ErrorCode::QueryExecution as libc::c_int);
+        assert!(last_error().contains("no such table"), "{}", last_error());
+
+        close(conn);
+    }
+
+    #[test]
+    fn reports_an_unreachable_server_when_used() {
+        // Nothing listens on port 9 of the loopback interface.
+        let conn = connect_remote("http://127.0.0.1:9");
+        let (status, _) = exec(conn, "SELECT 1", &[]);
+        assert_ne!(status, LIBSQL_PHP_OK);
+        close(conn);
+    }
+}
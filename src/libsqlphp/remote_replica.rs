@@ -1,98 +1,394 @@
+use std::{sync::Arc, time::Duration};
+
+use libsql::{EncryptionConfig, OpenFlags};
+
 use crate::{
-    types::db_pair::DbConnPair,
-    utils::{errors::libsql_php_error, runtime::runtime},
-    ERR_INVALID_PATH_CONVERT, ERR_REMOTE_REPLICA_CONFIGURATION,
+    types::{
+        encryption::LibsqlPhpEncryption,
+        handle::{ConnectionMode, LibsqlHandle},
+        replica::{sync_with_timeout, ReplicaState, ReplicaSyncError},
+        retry::LibsqlPhpRetryPolicy,
+    },
+    utils::{
+        errors::{
+            catch_panic, libsql_php_error, libsql_php_libsql_error, libsql_php_libsql_error_in,
+            ErrorCode, LIBSQL_PHP_OK,
+        },
+        runtime::runtime,
+    },
+    ERR_INVALID_PATH_CONVERT, ERR_NULL_OUT_PTR, ERR_REMOTE_REPLICA_CONFIGURATION,
+    ERR_REPLICA_UNREACHABLE,
 };
 
 /// Connects to a new remote replica in a PHP extension.
 ///
+/// The replica syncs in the background every `sync_duration` seconds, which
+/// `libsql_php_background_sync_status` reports on.
+///
+/// # Safety
+///
+/// This function is marked as unsafe because it dereferences raw pointers.
+///
 /// # Arguments
 ///
 /// * `path` - A pointer to a C-style string representing the path.
 /// * `url` - A pointer to a C-style string representing the URL.
 /// * `token` - A pointer to a C-style string representing the token.
-/// * `sync_duration` - The synchronization duration in seconds.
+/// * `sync_duration` - The synchronization duration in seconds, or `0` for 5 seconds.
 /// * `read_your_writes` - An integer representing whether "read your writes" mode is enabled (1) or not (0).
+/// * `encryption` - A pointer to the `LibsqlPhpEncryption` settings of the local copy,
+///   or a null pointer to keep it unencrypted.
+/// * `out_conn` - A pointer where the raw pointer to the `LibsqlHandle` is written.
 ///
 /// # Returns
 ///
-/// Returns a pointer to a memory location containing a pair of database connection objects (`DbConnPair`). If an error occurs or null pointers are provided, returns a null pointer.
+/// Returns `0` on success. If an error occurs or null pointers are provided, returns the error code
+/// and records the error for `libsql_php_last_error_message`.
 #[no_mangle]
-pub extern "C" fn libsql_php_connect_new_remote_replica(
+pub unsafe extern "C" fn libsql_php_connect_new_remote_replica(
     path: *const libc::c_char,
     url: *const libc::c_char,
     token: *const libc::c_char,
     sync_duration: usize,
     read_your_writes: libc::c_int,
-) -> *mut DbConnPair {
-    if path.is_null() && url.is_null() && token.is_null() {
-        libsql_php_error(
-            ERR_REMOTE_REPLICA_CONFIGURATION,
-            "ERR_REMOTE_REPLICA_CONFIGURATION",
-        );
-        return std::ptr::null_mut();
-    }
+    encryption: *const LibsqlPhpEncryption,
+    out_conn: *mut *mut LibsqlHandle,
+) -> libc::c_int {
+    catch_panic(|| {
+        if path.is_null() || url.is_null() || token.is_null() {
+            return libsql_php_error(
+                ERR_REMOTE_REPLICA_CONFIGURATION,
+                ErrorCode::RemoteReplicaConfiguration,
+            );
+        }
+
+        if out_conn.is_null() {
+            return libsql_php_error(ERR_NULL_OUT_PTR, ErrorCode::InvalidArguments);
+        }
+
+        let config = match unsafe { replica_config(path, url, token, encryption) } {
+            Ok(config) => config,
+            Err(status) => return status,
+        };
+
+        let rt = runtime();
+
+        let handle = rt.block_on(async {
+            let db = build_replica(config, read_your_writes != 0).await?;
+            let conn = db.connect()?;
 
-    let c_str = unsafe { std::ffi::CStr::from_ptr(path) };
+            Ok::<_, libsql::Error>(LibsqlHandle {
+                db: Arc::new(db),
+                conn,
+                mode: ConnectionMode::RemoteReplica,
+                replica: Some(Arc::new(ReplicaState::new(false))),
+                retry: LibsqlPhpRetryPolicy::default(),
+                pool: None,
+            })
+        });
 
-    let path_str = match c_str.to_str() {
-        Ok(str) => str,
-        Err(_) => {
-            libsql_php_error(ERR_INVALID_PATH_CONVERT, "ERR_INVALID_PATH_CONVERT");
-            return std::ptr::null_mut();
+        match handle {
+            Ok(handle) => {
+                if let Some(state) = &handle.replica {
+                    let interval = sync_interval(sync_duration);
+                    unsafe {
+                        state.start_background_sync(
+                            &handle.db,
+                            interval,
+                            interval,
+                            None,
+                            std::ptr::null_mut(),
+                        )
+                    };
+                }
+                unsafe { *out_conn = Box::into_raw(Box::new(handle)) };
+                LIBSQL_PHP_OK
+            }
+            Err(e) => libsql_php_libsql_error(&e, ErrorCode::ConnectionFailed),
         }
-    };
+    })
+}
 
-    let u_str = unsafe { std::ffi::CStr::from_ptr(url) };
+/// Connects to a remote replica that keeps working while its primary can't be reached.
+///
+/// The primary is contacted once when the replica is opened. If it does not answer before the
+/// timeout, the connection reads the local replica file read-only instead, as long as the replica
+/// has synced before. Either way the replica keeps syncing in the background, and
+/// `libsql_php_replica_status` reports whether it is stale and how far behind it is.
+///
+/// A connection that fell back to the local replica file stays read-only until `libsql_php_sync`
+/// or `libsql_php_sync_until` succeeds on it, which switches it to the replica so writes work again.
+/// Background syncs keep the file up to date but do not switch the connection.
+///
+/// # Safety
+///
+/// This function is marked as unsafe because it dereferences raw pointers.
+///
+/// # Arguments
+///
+/// * `path` - A pointer to a C-style string representing the path.
+/// * `url` - A pointer to a C-style string representing the URL.
+/// * `token` - A pointer to a C-style string representing the token.
+/// * `sync_duration` - The synchronization duration in seconds, or `0` for 5 seconds.
+/// * `read_your_writes` - An integer representing whether "read your writes" mode is enabled (1) or not (0).
+/// * `encryption` - A pointer to the `LibsqlPhpEncryption` settings of the local copy,
+///   or a null pointer to keep it unencrypted.
+/// * `timeout_ms` - How long, in milliseconds, each attempt to reach the primary may take.
+/// * `out_conn` - A pointer where the raw pointer to the `LibsqlHandle` is written.
+///
+/// # Returns
+///
+/// Returns `0` on success, including when the connection fell back to the local replica file.
+/// If the primary cannot be reached and the replica has never synced, returns
+/// `ErrorCode::ConnectionFailed` with a message starting with "Primary could not be reached".
+/// If another error occurs or null pointers are provided, returns the error code and records the error
+/// for `libsql_php_last_error_message`.
+#[no_mangle]
+pub unsafe extern "C" fn libsql_php_connect_offline_replica(
+    path: *const libc::c_char,
+    url: *const libc::c_char,
+    token: *const libc::c_char,
+    sync_duration: usize,
+    read_your_writes: libc::c_int,
+    encryption: *const LibsqlPhpEncryption,
+    timeout_ms: libc::c_uint,
+    out_conn: *mut *mut LibsqlHandle,
+) -> libc::c_int {
+    catch_panic(|| {
+        if path.is_null() || url.is_null() || token.is_null() {
+            return libsql_php_error(
+                ERR_REMOTE_REPLICA_CONFIGURATION,
+                ErrorCode::RemoteReplicaConfiguration,
+            );
+        }
 
-    let url_str = match u_str.to_str() {
-        Ok(str) => str,
-        Err(_) => {
-            libsql_php_error(ERR_INVALID_PATH_CONVERT, "ERR_INVALID_PATH_CONVERT");
-            return std::ptr::null_mut();
+        if out_conn.is_null() {
+            return libsql_php_error(ERR_NULL_OUT_PTR, ErrorCode::InvalidArguments);
         }
+
+        let config = match unsafe { replica_config(path, url, token, encryption) } {
+            Ok(config) => config,
+            Err(status) => return status,
+        };
+
+        let path_str = config.path.clone();
+        let encryption_config = config.encryption_config.clone();
+        let timeout = Duration::from_millis(timeout_ms.into());
+
+        let rt = runtime();
+
+        let db = match rt.block_on(build_replica(config, read_your_writes != 0)) {
+            Ok(db) => Arc::new(db),
+            Err(e) => return libsql_php_libsql_error(&e, ErrorCode::ConnectionFailed),
+        };
+
+        let probe = rt.block_on(sync_with_timeout(&db, Some(timeout)));
+
+        let (conn, read_only) = match &probe {
+            Ok(_) => (db.connect(), false),
+            Err(error) => {
+                match rt.block_on(db.replication_index()) {
+                    Ok(Some(_)) => {}
+                    Ok(None) => {
+                        return match error {
+                            ReplicaSyncError::Timeout => libsql_php_error(
+                                ERR_REPLICA_UNREACHABLE,
+                                ErrorCode::ConnectionFailed,
+                            ),
+                            ReplicaSyncError::Sync(e) => libsql_php_libsql_error_in(
+                                ERR_REPLICA_UNREACHABLE,
+                                e,
+                                ErrorCode::ConnectionFailed,
+                            ),
+                        };
+                    }
+                    Err(e) => return libsql_php_libsql_error(&e, ErrorCode::ConnectionFailed),
+                }
+
+                let local = rt.block_on(async {
+                    let mut builder = libsql::Builder::new_local(&path_str)
+                        .flags(OpenFlags::SQLITE_OPEN_READ_ONLY);
+
+                    if let Some(enc_config) = encryption_config {
+                        builder = builder.encryption_config(enc_config);
+                    }
+
+                    builder.build().await
+                });
+
+                (local.and_then(|local| local.connect()), true)
+            }
+        };
+
+        let conn = match conn {
+            Ok(conn) => conn,
+            Err(e) => return libsql_php_libsql_error(&e, ErrorCode::ConnectionFailed),
+        };
+
+        let state = Arc::new(ReplicaState::new(read_only));
+        state.record_sync(&probe);
+        unsafe {
+            state.start_background_sync(
+                &db,
+                sync_interval(sync_duration),
+                timeout,
+                None,
+                std::ptr::null_mut(),
+            )
+        };
+
+        let handle = LibsqlHandle {
+            db,
+            conn,
+            mode: ConnectionMode::RemoteReplica,
+            replica: Some(state),
+            retry: LibsqlPhpRetryPolicy::default(),
+            pool: None,
+        };
+
+        unsafe { *out_conn = Box::into_raw(Box::new(handle)) };
+        LIBSQL_PHP_OK
+    })
+}
+
+/// The settings of a remote replica, read from the C arguments.
+struct ReplicaConfig {
+    path: String,
+    url: String,
+    token: String,
+    encryption_config: Option<EncryptionConfig>,
+}
+
+/// Reads the settings of a remote replica from the C arguments, or returns the status to return
+/// after the error has been recorded.
+unsafe fn replica_config(
+    path: *const libc::c_char,
+    url: *const libc::c_char,
+    token: *const libc::c_char,
+    encryption: *const LibsqlPhpEncryption,
+) -> Result<ReplicaConfig, libc::c_int> {
+    let to_string = |ptr: *const libc::c_char| {
+        unsafe { std::ffi::CStr::from_ptr(ptr) }
+            .to_str()
+            .map(str::to_string)
+            .map_err(|_| libsql_php_error(ERR_INVALID_PATH_CONVERT, ErrorCode::InvalidPathConvert))
     };
 
-    let t_str = unsafe { std::ffi::CStr::from_ptr(token) };
+    let path = to_string(path)?;
+    let url = to_string(url)?;
+    let token = to_string(token)?;
 
-    let token_str = match t_str.to_str() {
-        Ok(str) => str,
-        Err(_) => {
-            libsql_php_error(ERR_INVALID_PATH_CONVERT, "ERR_INVALID_PATH_CONVERT");
-            return std::ptr::null_mut();
-        }
+    let encryption_config = match unsafe { encryption.as_ref() } {
+        Some(encryption) => match unsafe { encryption.to_config() } {
+            Ok(config) => Some(config),
+            Err(e) => return Err(libsql_php_error(&e, ErrorCode::InvalidEncryption)),
+        },
+        None => None,
     };
 
-    let rt = runtime();
+    Ok(ReplicaConfig {
+        path,
+        url,
+        token,
+        encryption_config,
+    })
+}
+
+/// Builds a remote replica. Opening it does not contact the primary.
+///
+/// Periodic syncs are left to `ReplicaState::start_background_sync`, which records their outcome.
+async fn build_replica(
+    config: ReplicaConfig,
+    read_your_writes: bool,
+) -> libsql::Result<libsql::Database> {
+    let mut builder = libsql::Builder::new_remote_replica(config.path, config.url, config.token)
+        .read_your_writes(read_your_writes);
+
+    if let Some(enc_config) = config.encryption_config {
+        builder = builder.encryption_config(enc_config);
+    }
+
+    builder.build().await
+}
+
+/// The interval between periodic syncs for a `sync_duration` in seconds, at least 5 seconds when unset.
+fn sync_interval(sync_duration: usize) -> Duration {
+    let periodic_sync: u64 = if sync_duration < 1 {
+        5
+    } else {
+        sync_duration as u64
+    };
+
+    Duration::from_secs(periodic_sync)
+}
 
-    let pair = rt.block_on(async {
-        let mut builder = libsql::Builder::new_remote_replica(
-            path_str,
-            url_str.to_string(),
-            token_str.to_string(),
+#[cfg(test)]
+mod tests {
+    use std::ffi::CString;
+
+    use super::*;
+    use crate::{
+        libsqlphp::{
+            bootstrap::remove_replica, execute_batch::libsql_php_execute_batch,
+            sync::libsql_php_replica_status,
+        },
+        types::replica::LibsqlPhpReplicaStatus,
+        utils::testing::{
+            close, connect_offline_replica, last_error, query, seed_replica, temp_path,
+        },
+    };
+
+    #[test]
+    fn fails_without_a_primary_if_never_synced() {
+        let path = temp_path("offline_never_synced");
+
+        let (status, handle) = connect_offline_replica(&path);
+        assert_eq!(status, ErrorCode::ConnectionFailed as libc::c_int);
+        assert!(handle.is_null());
+        assert!(
+            last_error().starts_with(ERR_REPLICA_UNREACHABLE),
+            "{}",
+            last_error()
         );
 
-        let periodic_sync: u64 = if sync_duration < 1 {
-            5
-        } else {
-            sync_duration.try_into().unwrap()
-        };
+        remove_replica(&path);
+    }
 
-        builder = builder.sync_interval(std::time::Duration::from_secs(periodic_sync));
+    #[test]
+    fn reads_a_seeded_replica_without_a_primary() {
+        let path = temp_path("offline_seeded");
+        let frame_no = seed_replica(&path);
 
-        let read_your_writes_bool = if read_your_writes == 0 { false } else { true };
-        builder = builder.read_your_writes(read_your_writes_bool);
+        let (status, handle) = connect_offline_replica(&path);
+        assert_eq!(status, LIBSQL_PHP_OK, "{}", last_error());
 
-        let db = builder.build().await.unwrap();
-        let conn = db.connect().unwrap();
+        assert_eq!(
+            query(handle, "SELECT name FROM t")["rows"],
+            serde_json::json!([[{"type": 3, "value": "seeded"}]])
+        );
 
-        let pair = Box::new(DbConnPair {
-            db: Box::into_raw(Box::new(db)),
-            conn: Box::into_raw(Box::new(conn)),
-        });
+        let insert = CString::new("INSERT INTO t VALUES ('offline')").unwrap();
+        let status = unsafe { libsql_php_execute_batch(handle, insert.as_ptr()) };
+        assert_ne!(status, LIBSQL_PHP_OK);
+        assert!(last_error().contains("readonly"), "{}", last_error());
 
-        Box::into_raw(pair)
-    });
+        let mut replica = LibsqlPhpReplicaStatus {
+            online: -1,
+            read_only: -1,
+            stale: -1,
+            frame_no: -1,
+            frames_behind: -1,
+            seconds_since_sync: -1,
+        };
+        let status = unsafe { libsql_php_replica_status(handle, &mut replica) };
+        assert_eq!(status, LIBSQL_PHP_OK, "{}", last_error());
+        assert_eq!(
+            (replica.online, replica.stale, replica.read_only),
+            (0, 1, 1)
+        );
+        assert_eq!(replica.frame_no, frame_no as i64);
 
-    pair
+        close(handle);
+        remove_replica(&path);
+    }
 }
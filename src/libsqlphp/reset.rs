@@ -1,5 +1,9 @@
 use crate::{
-    utils::{errors::libsql_php_error, runtime::runtime},
+    types::handle::LibsqlHandle,
+    utils::{
+        errors::{catch_panic, libsql_php_error, ErrorCode, LIBSQL_PHP_OK},
+        runtime::runtime,
+    },
     ERR_NULL_CLIENT_PTR,
 };
 
@@ -11,20 +15,22 @@ use crate::{
 ///
 /// # Arguments
 ///
-/// * `client_ptr` - A raw pointer to the LibSQL connection client.
+/// * `client_ptr` - A raw pointer to the `LibsqlHandle` of the connection.
 ///
 /// # Returns
 ///
-/// Returns a pointer to a memory location indicating the success of the reset operation. If an error occurs or the client pointer is null, returns a null pointer.
+/// Returns `0` if the connection was reset. If the client pointer is null, returns the error code
+/// and records the error for `libsql_php_last_error_message`.
 #[no_mangle]
-pub extern "C" fn libsql_php_reset(client_ptr: *mut libc::c_void) -> *const libc::c_int {
-    if client_ptr.is_null() {
-        libsql_php_error(ERR_NULL_CLIENT_PTR, "ERR_NULL_CLIENT_PTR");
-        return std::ptr::null_mut();
-    }
+pub unsafe extern "C" fn libsql_php_reset(client_ptr: *mut libc::c_void) -> libc::c_int {
+    catch_panic(|| {
+        if client_ptr.is_null() {
+            return libsql_php_error(ERR_NULL_CLIENT_PTR, ErrorCode::NullClientPtr);
+        }
 
-    let client = unsafe { &mut *(client_ptr as *mut libsql::Connection) };
+        let client = unsafe { &(*(client_ptr as *mut LibsqlHandle)).conn };
 
-    runtime().block_on(client.reset());
-    std::ptr::null()
+        runtime().block_on(client.reset());
+        LIBSQL_PHP_OK
+    })
 }
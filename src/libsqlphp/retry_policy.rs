@@ -0,0 +1,193 @@
+use crate::{
+    types::{handle::LibsqlHandle, retry::LibsqlPhpRetryPolicy},
+    utils::errors::{catch_panic, libsql_php_error, ErrorCode, LIBSQL_PHP_OK},
+    ERR_NULL_CLIENT_PTR, ERR_NULL_OUT_PTR,
+};
+
+/// Sets how a connection retries statements that failed with a transient error.
+///
+/// The policy applies to statements executed and queried on the connection and to starting
+/// transactions. Batches and statements run inside a transaction are not retried, because running
+/// them again could repeat work that already succeeded. For the same reason, remote requests are only
+/// retried when their connection could not be opened.
+///
+/// # Safety
+///
+/// This function is marked as unsafe because it dereferences raw pointers.
+///
+/// # Arguments
+///
+/// * `client_ptr` - A raw pointer to the `LibsqlHandle` of the connection.
+/// * `policy` - A pointer to the `LibsqlPhpRetryPolicy` to use, or a null pointer to stop retrying.
+///
+/// # Returns
+///
+/// Returns `0` on success. If the client pointer is null or the policy is invalid, returns the error
+/// code and records the error for `libsql_php_last_error_message`.
+#[no_mangle]
+pub unsafe extern "C" fn libsql_php_set_retry_policy(
+    client_ptr: *mut libc::c_void,
+    policy: *const LibsqlPhpRetryPolicy,
+) -> libc::c_int {
+    catch_panic(|| {
+        if client_ptr.is_null() {
+            return libsql_php_error(ERR_NULL_CLIENT_PTR, ErrorCode::NullClientPtr);
+        }
+
+        let handle = unsafe { &mut *(client_ptr as *mut LibsqlHandle) };
+
+        let policy = unsafe { policy.as_ref() }.copied().unwrap_or_default();
+
+        if let Err(e) = policy.validate() {
+            return libsql_php_error(e, ErrorCode::InvalidRetryPolicy);
+        }
+
+        handle.retry = policy;
+        LIBSQL_PHP_OK
+    })
+}
+
+/// Retrieves how a connection retries statements that failed with a transient error.
+///
+/// # Safety
+///
+/// This function is marked as unsafe because it dereferences raw pointers.
+///
+/// # Arguments
+///
+/// * `client_ptr` - A raw pointer to the `LibsqlHandle` of the connection.
+/// * `out_policy` - A pointer where the `LibsqlPhpRetryPolicy` of the connection is written.
+///
+/// # Returns
+///
+/// Returns `0` on success. If the client pointer or the output pointer is null, returns the error code
+/// and records the error for `libsql_php_last_error_message`.
+#[no_mangle]
+pub unsafe extern "C" fn libsql_php_retry_policy(
+    client_ptr: *mut libc::c_void,
+    out_policy: *mut LibsqlPhpRetryPolicy,
+) -> libc::c_int {
+    catch_panic(|| {
+        if client_ptr.is_null() {
+            return libsql_php_error(ERR_NULL_CLIENT_PTR, ErrorCode::NullClientPtr);
+        }
+
+        if out_policy.is_null() {
+            return libsql_php_error(ERR_NULL_OUT_PTR, ErrorCode::InvalidArguments);
+        }
+
+        let handle = unsafe { &*(client_ptr as *mut LibsqlHandle) };
+
+        unsafe { *out_policy = handle.retry };
+        LIBSQL_PHP_OK
+    })
+}
+
+#[cfg(test)]
+mod tests {
+    use std::{
+        ffi::CString,
+        time::{Duration, Instant},
+    };
+
+    use super::*;
+    use crate::{
+        libsqlphp::{
+            execute_values::libsql_php_exec_values, last_error::libsql_php_last_error_extended_code,
+        },
+        utils::testing::{close, connect_local, exec, last_error, query, temp_path},
+    };
+
+    fn insert(conn: *mut libc::c_void) -> libc::c_int {
+        let sql = CString::new("INSERT INTO t VALUES (2)").unwrap();
+        unsafe {
+            libsql_php_exec_values(
+                conn,
+                sql.as_ptr(),
+                std::ptr::null(),
+                0,
+                std::ptr::null_mut(),
+            )
+        }
+    }
+
+    fn set_policy(conn: *mut libc::c_void, max_attempts: u32, initial_backoff_ms: u32) {
+        let policy = LibsqlPhpRetryPolicy {
+            max_attempts,
+            initial_backoff_ms,
+            max_backoff_ms: initial_backoff_ms * 4,
+            jitter_percent: 0,
+            ..Default::default()
+        };
+        let status = unsafe { libsql_php_set_retry_policy(conn, &policy) };
+        assert_eq!(status, LIBSQL_PHP_OK, "{}", last_error());
+    }
+
+    /// Opens two connections to a new database file, the first one holding its write lock.
+    fn contending_writers(
+        name: &str,
+    ) -> (std::path::PathBuf, *mut libc::c_void, *mut libc::c_void) {
+        let path = temp_path(name);
+        let holder = connect_local(path.to_str().unwrap());
+        exec(holder, "CREATE TABLE t (x INTEGER)");
+        let writer = connect_local(path.to_str().unwrap());
+        exec(holder, "BEGIN IMMEDIATE; INSERT INTO t VALUES (1)");
+        (path, holder, writer)
+    }
+
+    #[test]
+    fn retries_a_busy_write_until_the_lock_is_released() {
+        let (path, holder, writer) = contending_writers("retry_busy");
+
+        assert_eq!(insert(writer), ErrorCode::QueryExecution as libc::c_int);
+        assert_eq!(libsql_php_last_error_extended_code() & 0xff, 5);
+
+        set_policy(writer, 50, 20);
+        let holder_addr = holder as usize;
+        let release = std::thread::spawn(move || {
+            std::thread::sleep(Duration::from_millis(200));
+            exec(holder_addr as *mut libc::c_void, "COMMIT");
+        });
+
+        assert_eq!(insert(writer), LIBSQL_PHP_OK, "{}", last_error());
+        release.join().unwrap();
+        assert_eq!(
+            query(writer, "SELECT count(*) FROM t")["rows"][0][0]["value"],
+            2
+        );
+
+        close(writer);
+        close(holder);
+        let _ = std::fs::remove_file(path);
+    }
+
+    #[test]
+    fn gives_up_after_the_last_attempt() {
+        let (path, holder, writer) = contending_writers("retry_exhausted");
+        set_policy(writer, 3, 20);
+
+        let started = Instant::now();
+        assert_eq!(insert(writer), ErrorCode::QueryExecution as libc::c_int);
+        assert!(started.elapsed() >= Duration::from_millis(60));
+
+        close(writer);
+        close(holder);
+        let _ = std::fs::remove_file(path);
+    }
+
+    #[test]
+    fn does_not_retry_a_busy_write_inside_a_transaction() {
+        let (path, holder, writer) = contending_writers("retry_transaction");
+        set_policy(writer, 50, 500);
+        exec(writer, "BEGIN");
+
+        let started = Instant::now();
+        assert_eq!(insert(writer), ErrorCode::QueryExecution as libc::c_int);
+        assert!(started.elapsed() < Duration::from_millis(500));
+
+        exec(writer, "ROLLBACK");
+        close(writer);
+        close(holder);
+        let _ = std::fs::remove_file(path);
+    }
+}
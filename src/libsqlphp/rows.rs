@@ -0,0 +1,227 @@
+use crate::{
+    types::cursor::RowCursor,
+    utils::{
+        errors::{
+            catch_panic, libsql_php_error, libsql_php_libsql_error, ErrorCode, LIBSQL_PHP_OK,
+        },
+        query_result::{merge_column_names, row_values, to_c_json},
+        runtime::runtime,
+    },
+    ERR_NULL_OUT_PTR, ERR_NULL_ROWS_PTR,
+};
+
+/// Reads the next row of a cursor opened with `libsql_php_query_open`, in JSON format.
+///
+/// # Safety
+///
+/// This function is marked as unsafe because it dereferences raw pointers.
+///
+/// # Arguments
+///
+/// * `rows_ptr` - A raw pointer to the cursor.
+/// * `out_json` - A pointer where the null-terminated C string holding the row as a JSON array of
+///   `{"type": ..., "value": ...}` cells,
+///   in the order given by `libsql_php_rows_columns`, is written, or a null pointer once every row has been read.
+///   Every later call writes a null pointer too.
+///
+/// # Returns
+///
+/// Returns `0` on success. If an error occurs, returns the error code and records the error for `libsql_php_last_error_message`.
+/// The row is owned by the cursor and stays valid until the next call or until the cursor is freed.
+#[no_mangle]
+pub unsafe extern "C" fn libsql_php_rows_next(
+    rows_ptr: *mut libc::c_void,
+    out_json: *mut *const libc::c_char,
+) -> libc::c_int {
+    catch_panic(|| {
+        if rows_ptr.is_null() {
+            return libsql_php_error(ERR_NULL_ROWS_PTR, ErrorCode::NullRowsPtr);
+        }
+
+        if out_json.is_null() {
+            return libsql_php_error(ERR_NULL_OUT_PTR, ErrorCode::InvalidArguments);
+        }
+
+        let cursor = unsafe { &mut *(rows_ptr as *mut RowCursor) };
+
+        cursor.current = None;
+
+        if cursor.done {
+            unsafe { *out_json = std::ptr::null() };
+            return LIBSQL_PHP_OK;
+        }
+
+        let row = match runtime().block_on(cursor.rows.next()) {
+            Ok(Some(row)) => row,
+            Ok(None) => {
+                cursor.done = true;
+                unsafe { *out_json = std::ptr::null() };
+                return LIBSQL_PHP_OK;
+            }
+            Err(e) => return libsql_php_libsql_error(&e, ErrorCode::QueryExecution),
+        };
+
+        let result = match row_values(&row, cursor.rows.column_count()) {
+            Ok(result) => result,
+            Err(e) => return libsql_php_libsql_error(&e, ErrorCode::QueryExecution),
+        };
+
+        match to_c_json(&result) {
+            Ok(c_json) => {
+                unsafe { *out_json = c_json.as_ptr() };
+                cursor.current = Some(c_json);
+                LIBSQL_PHP_OK
+            }
+            Err(e) => libsql_php_error(&e, ErrorCode::Serialization),
+        }
+    })
+}
+
+/// Retrieves the columns of a cursor opened with `libsql_php_query_open`, in JSON format.
+///
+/// # Safety
+///
+/// This function is marked as unsafe because it dereferences raw pointers.
+///
+/// # Arguments
+///
+/// * `rows_ptr` - A raw pointer to the cursor.
+/// * `out_json` - A pointer where the null-terminated C string holding the JSON array of columns,
+///   in SELECT order, is written. Each column is an object with its `name`, `decltype`, `table` and `origin`.
+///
+/// # Returns
+///
+/// Returns `0` on success. If an error occurs, returns the error code and records the error for `libsql_php_last_error_message`.
+/// The columns are owned by the cursor and stay valid until the cursor is freed.
+#[no_mangle]
+pub unsafe extern "C" fn libsql_php_rows_columns(
+    rows_ptr: *mut libc::c_void,
+    out_json: *mut *const libc::c_char,
+) -> libc::c_int {
+    catch_panic(|| {
+        if rows_ptr.is_null() {
+            return libsql_php_error(ERR_NULL_ROWS_PTR, ErrorCode::NullRowsPtr);
+        }
+
+        if out_json.is_null() {
+            return libsql_php_error(ERR_NULL_OUT_PTR, ErrorCode::InvalidArguments);
+        }
+
+        let cursor = unsafe { &mut *(rows_ptr as *mut RowCursor) };
+
+        if cursor.columns.is_none() {
+            let columns = merge_column_names(std::mem::take(&mut cursor.column_info), &cursor.rows);
+            match to_c_json(&columns) {
+                Ok(c_json) => cursor.columns = Some(c_json),
+                Err(e) => return libsql_php_error(&e, ErrorCode::Serialization),
+            }
+        }
+
+        unsafe {
+            *out_json = cursor
+                .columns
+                .as_ref()
+                .map_or(std::ptr::null(), |c| c.as_ptr())
+        };
+        LIBSQL_PHP_OK
+    })
+}
+
+/// Frees a cursor opened with `libsql_php_query_open`, along with any row it still holds.
+///
+/// # Safety
+///
+/// This function is marked as unsafe because it takes ownership of a raw pointer.
+/// The cursor must not be used after this call.
+///
+/// # Arguments
+///
+/// * `rows_ptr` - A raw pointer to the cursor.
+///
+/// # Returns
+///
+/// Returns `0` if the cursor was freed. If the cursor pointer is null, returns the error code
+/// and records the error for `libsql_php_last_error_message`.
+#[no_mangle]
+pub unsafe extern "C" fn libsql_php_rows_free(rows_ptr: *mut libc::c_void) -> libc::c_int {
+    catch_panic(|| {
+        if rows_ptr.is_null() {
+            return libsql_php_error(ERR_NULL_ROWS_PTR, ErrorCode::NullRowsPtr);
+        }
+
+        drop(unsafe { Box::from_raw(rows_ptr as *mut RowCursor) });
+        LIBSQL_PHP_OK
+    })
+}
+
+#[cfg(test)]
+mod tests {
+    use std::ffi::{CStr, CString};
+
+    use serde_json::json;
+
+    use super::*;
+    use crate::{
+        libsqlphp::query_open::libsql_php_query_open,
+        utils::testing::{c_value, close, connect_memory, exec, last_error},
+    };
+
+    /// Reads the next row of a cursor, or `None` once every row has been read.
+    fn next(rows: *mut libc::c_void) -> Option<serde_json::Value> {
+        let mut json = std::ptr::null();
+        let status = unsafe { libsql_php_rows_next(rows, &mut json) };
+        assert_eq!(status, LIBSQL_PHP_OK, "{}", last_error());
+        (!json.is_null())
+            .then(|| serde_json::from_slice(unsafe { CStr::from_ptr(json) }.to_bytes()).unwrap())
+    }
+
+    #[test]
+    fn reads_rows_one_at_a_time() {
+        let conn = connect_memory();
+        exec(
+            conn,
+            "CREATE TABLE t (id INTEGER, name TEXT);
+             INSERT INTO t VALUES (1, 'a'), (2, 'b'), (3, 'c');",
+        );
+
+        let sql = CString::new("SELECT id, name FROM t WHERE id > ? ORDER BY id").unwrap();
+        let min = c_value(&libsql::Value::Integer(1));
+        let mut rows = std::ptr::null_mut();
+        let status = unsafe {
+            libsql_php_query_open(conn, sql.as_ptr(), std::ptr::null(), &min, 1, &mut rows)
+        };
+        assert_eq!(status, LIBSQL_PHP_OK, "{}", last_error());
+        let rows = rows as *mut libc::c_void;
+
+        let mut json = std::ptr::null();
+        assert_eq!(
+            unsafe { libsql_php_rows_columns(rows, &mut json) },
+            LIBSQL_PHP_OK
+        );
+        let columns: serde_json::Value =
+            serde_json::from_slice(unsafe { CStr::from_ptr(json) }.to_bytes()).unwrap();
+        assert_eq!(
+            columns,
+            json!([
+                {"name": "id", "decltype": "INTEGER", "table": "t", "origin": "id"},
+                {"name": "name", "decltype": "TEXT", "table": "t", "origin": "name"},
+            ])
+        );
+
+        assert_eq!(
+            next(rows),
+            Some(json!([{"type": 1, "value": 2}, {"type": 3, "value": "b"}]))
+        );
+        assert_eq!(
+            next(rows),
+            Some(json!([{"type": 1, "value": 3}, {"type": 3, "value": "c"}]))
+        );
+        assert_eq!(next(rows), None);
+        // Reading past the end keeps reporting the end rather than running the query again.
+        assert_eq!(next(rows), None);
+        assert_eq!(next(rows), None);
+
+        assert_eq!(unsafe { libsql_php_rows_free(rows) }, LIBSQL_PHP_OK);
+        close(conn);
+    }
+}
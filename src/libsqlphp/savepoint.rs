@@ -0,0 +1,300 @@
+use std::ffi::{CStr, CString};
+
+use crate::{
+    types::transaction::{quote_savepoint, TransactionHandle},
+    utils::{
+        errors::{
+            catch_panic, libsql_php_error, libsql_php_libsql_error, ErrorCode, LIBSQL_PHP_OK,
+        },
+        runtime::runtime,
+    },
+    ERR_NULL_CLIENT_PTR, ERR_NULL_OUT_PTR, ERR_STRING_CONVERTION,
+};
+
+/// Opens a savepoint inside a transaction, nested in the innermost savepoint already open.
+///
+/// # Safety
+///
+/// This function is marked as unsafe because it dereferences raw pointers.
+///
+/// # Arguments
+///
+/// * `trx_ptr` - A raw pointer to the transaction handle returned by `libsql_php_transaction`.
+/// * `name` - A raw pointer to a C-style string holding the name of the savepoint. If it is null or empty,
+///   a unique name is generated.
+/// * `out_name` - A pointer where the name of the savepoint is written, or null. Free it with
+///   `libsql_php_free_string`.
+///
+/// # Returns
+///
+/// Returns `0` if the savepoint was opened. If an error occurs, a null pointer is provided or the transaction
+/// was already committed or rolled back, returns the error code and records the error for
+/// `libsql_php_last_error_message`.
+#[no_mangle]
+pub unsafe extern "C" fn libsql_php_transaction_savepoint(
+    trx_ptr: *mut libc::c_void,
+    name: *const libc::c_char,
+    out_name: *mut *const libc::c_char,
+) -> libc::c_int {
+    catch_panic(|| {
+        if trx_ptr.is_null() {
+            return libsql_php_error(ERR_NULL_CLIENT_PTR, ErrorCode::NullClientPtr);
+        }
+
+        let handle = unsafe { &mut *(trx_ptr as *mut TransactionHandle) };
+
+        let name = match unsafe { savepoint_name(name) } {
+            Ok(Some(name)) => name.to_string(),
+            Ok(None) => handle.generate_savepoint_name(),
+            Err(code) => return code,
+        };
+
+        let sql = format!("SAVEPOINT {}", quote_savepoint(&name));
+        if let Err(code) = execute(handle, &sql) {
+            return code;
+        }
+
+        if !out_name.is_null() {
+            let c_name = CString::new(name.as_str()).unwrap_or_default();
+            unsafe { *out_name = c_name.into_raw() };
+        }
+
+        handle.push_savepoint(name);
+        LIBSQL_PHP_OK
+    })
+}
+
+/// Releases a savepoint, keeping its changes in the transaction and closing every savepoint opened inside it.
+///
+/// # Safety
+///
+/// This function is marked as unsafe because it dereferences raw pointers.
+///
+/// # Arguments
+///
+/// * `trx_ptr` - A raw pointer to the transaction handle returned by `libsql_php_transaction`.
+/// * `name` - A raw pointer to a C-style string holding the name of the savepoint, or null for the innermost one.
+///
+/// # Returns
+///
+/// Returns `0` if the savepoint was released. If no open savepoint has the name, an error occurs, a null pointer
+/// is provided or the transaction was already committed or rolled back, returns the error code and records the
+/// error for `libsql_php_last_error_message`.
+#[no_mangle]
+pub unsafe extern "C" fn libsql_php_transaction_release(
+    trx_ptr: *mut libc::c_void,
+    name: *const libc::c_char,
+) -> libc::c_int {
+    catch_panic(|| {
+        if trx_ptr.is_null() {
+            return libsql_php_error(ERR_NULL_CLIENT_PTR, ErrorCode::NullClientPtr);
+        }
+
+        let handle = unsafe { &mut *(trx_ptr as *mut TransactionHandle) };
+
+        let depth = match unsafe { open_savepoint(handle, name) } {
+            Ok(depth) => depth,
+            Err(code) => return code,
+        };
+
+        let sql = format!(
+            "RELEASE SAVEPOINT {}",
+            quote_savepoint(handle.savepoint_name(depth))
+        );
+        if let Err(code) = execute(handle, &sql) {
+            return code;
+        }
+
+        handle.truncate_savepoints(depth);
+        LIBSQL_PHP_OK
+    })
+}
+
+/// Rolls a transaction back to a savepoint, undoing its changes and closing every savepoint opened inside it.
+/// The savepoint itself stays open.
+///
+/// # Safety
+///
+/// This function is marked as unsafe because it dereferences raw pointers.
+///
+/// # Arguments
+///
+/// * `trx_ptr` - A raw pointer to the transaction handle returned by `libsql_php_transaction`.
+/// * `name` - A raw pointer to a C-style string holding the name of the savepoint, or null for the innermost one.
+///
+/// # Returns
+///
+/// Returns `0` if the transaction was rolled back to the savepoint. If no open savepoint has the name, an error
+/// occurs, a null pointer is provided or the transaction was already committed or rolled back, returns the error
+/// code and records the error for `libsql_php_last_error_message`.
+#[no_mangle]
+pub unsafe extern "C" fn libsql_php_transaction_rollback_to(
+    trx_ptr: *mut libc::c_void,
+    name: *const libc::c_char,
+) -> libc::c_int {
+    catch_panic(|| {
+        if trx_ptr.is_null() {
+            return libsql_php_error(ERR_NULL_CLIENT_PTR, ErrorCode::NullClientPtr);
+        }
+
+        let handle = unsafe { &mut *(trx_ptr as *mut TransactionHandle) };
+
+        let depth = match unsafe { open_savepoint(handle, name) } {
+            Ok(depth) => depth,
+            Err(code) => return code,
+        };
+
+        let sql = format!(
+            "ROLLBACK TO SAVEPOINT {}",
+            quote_savepoint(handle.savepoint_name(depth))
+        );
+        if let Err(code) = execute(handle, &sql) {
+            return code;
+        }
+
+        handle.truncate_savepoints(depth + 1);
+        LIBSQL_PHP_OK
+    })
+}
+
+/// Retrieves how many savepoints are open inside a transaction.
+///
+/// # Safety
+///
+/// This function is marked as unsafe because it dereferences raw pointers.
+///
+/// # Arguments
+///
+/// * `trx_ptr` - A raw pointer to the transaction handle returned by `libsql_php_transaction`.
+/// * `out_depth` - A pointer where the number of open savepoints is written.
+///
+/// # Returns
+///
+/// Returns `0` on success. If the transaction pointer or the output pointer is null, returns the error code
+/// and records the error for `libsql_php_last_error_message`.
+#[no_mangle]
+pub unsafe extern "C" fn libsql_php_transaction_savepoint_depth(
+    trx_ptr: *mut libc::c_void,
+    out_depth: *mut u32,
+) -> libc::c_int {
+    catch_panic(|| {
+        if trx_ptr.is_null() {
+            return libsql_php_error(ERR_NULL_CLIENT_PTR, ErrorCode::NullClientPtr);
+        }
+
+        if out_depth.is_null() {
+            return libsql_php_error(ERR_NULL_OUT_PTR, ErrorCode::InvalidArguments);
+        }
+
+        let handle = unsafe { &*(trx_ptr as *mut TransactionHandle) };
+
+        unsafe { *out_depth = handle.savepoint_depth() as u32 };
+        LIBSQL_PHP_OK
+    })
+}
+
+/// Reads a savepoint name, returning `None` when it is null or empty.
+unsafe fn savepoint_name<'a>(name: *const libc::c_char) -> Result<Option<&'a str>, libc::c_int> {
+    if name.is_null() {
+        return Ok(None);
+    }
+
+    match unsafe { CStr::from_ptr(name) }.to_str() {
+        Ok("") => Ok(None),
+        Ok(name) => Ok(Some(name)),
+        Err(_) => Err(libsql_php_error(
+            ERR_STRING_CONVERTION,
+            ErrorCode::StringConvertion,
+        )),
+    }
+}
+
+/// Finds the open savepoint called `name`, or the innermost one when `name` is null or empty, and
+/// returns its depth.
+unsafe fn open_savepoint(
+    handle: &TransactionHandle,
+    name: *const libc::c_char,
+) -> Result<usize, libc::c_int> {
+    if let Err(e) = handle.get() {
+        return Err(libsql_php_error(&e, ErrorCode::TransactionFinished));
+    }
+
+    let name = unsafe { savepoint_name(name) }?;
+
+    handle
+        .find_savepoint(name)
+        .map_err(|e| libsql_php_error(&e, ErrorCode::UnknownSavepoint))
+}
+
+/// Executes a savepoint statement within the transaction of `handle`.
+fn execute(handle: &TransactionHandle, sql: &str) -> Result<(), libc::c_int> {
+    let transaction = handle
+        .get()
+        .map_err(|e| libsql_php_error(&e, ErrorCode::TransactionFinished))?;
+
+    runtime()
+        .block_on(transaction.execute(sql, ()))
+        .map(|_| ())
+        .map_err(|e| libsql_php_libsql_error(&e, ErrorCode::ExecutionFailed))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::{
+        libsqlphp::{
+            free_string::libsql_php_free_string, transaction_commit::libsql_php_transaction_commit,
+            transaction_free::libsql_php_transaction_free,
+        },
+        utils::testing::{begin, close, connect_memory, exec, last_error, query, trx_exec},
+    };
+
+    fn depth(trx: *mut libc::c_void) -> u32 {
+        let mut depth = u32::MAX;
+        let status = unsafe { libsql_php_transaction_savepoint_depth(trx, &mut depth) };
+        assert_eq!(status, LIBSQL_PHP_OK, "{}", last_error());
+        depth
+    }
+
+    #[test]
+    fn rolls_back_to_a_savepoint_and_keeps_earlier_changes() {
+        let conn = connect_memory();
+        exec(conn, "CREATE TABLE t (id INTEGER PRIMARY KEY)");
+
+        let trx = begin(conn);
+        trx_exec(trx, "INSERT INTO t VALUES (1)");
+        assert_eq!(depth(trx), 0);
+
+        let mut name = std::ptr::null();
+        let status = unsafe { libsql_php_transaction_savepoint(trx, std::ptr::null(), &mut name) };
+        assert_eq!(status, LIBSQL_PHP_OK, "{}", last_error());
+        assert!(!name.is_null());
+        assert_eq!(depth(trx), 1);
+
+        trx_exec(trx, "INSERT INTO t VALUES (2)");
+
+        let status = unsafe { libsql_php_transaction_rollback_to(trx, name) };
+        assert_eq!(status, LIBSQL_PHP_OK, "{}", last_error());
+        assert_eq!(depth(trx), 1);
+
+        let status = unsafe { libsql_php_transaction_release(trx, name) };
+        assert_eq!(status, LIBSQL_PHP_OK, "{}", last_error());
+        assert_eq!(depth(trx), 0);
+        unsafe { libsql_php_free_string(name) };
+
+        assert_eq!(
+            unsafe { libsql_php_transaction_commit(trx) },
+            LIBSQL_PHP_OK,
+            "{}",
+            last_error()
+        );
+        assert_eq!(unsafe { libsql_php_transaction_free(trx) }, LIBSQL_PHP_OK);
+
+        assert_eq!(
+            query(conn, "SELECT id FROM t")["rows"],
+            serde_json::json!([[{"type": 1, "value": 1}]])
+        );
+
+        close(conn);
+    }
+}
@@ -0,0 +1,108 @@
+use crate::{
+    types::{statement::PreparedStatement, value::LibsqlPhpValue},
+    utils::errors::{catch_panic, libsql_php_error, ErrorCode, LIBSQL_PHP_OK},
+    ERR_NULL_STATEMENT_PTR, ERR_STRING_CONVERTION,
+};
+
+/// Binds a typed value to a positional parameter of a prepared statement.
+///
+/// # Safety
+///
+/// This function is marked as unsafe because it dereferences raw pointers.
+///
+/// # Arguments
+///
+/// * `stmt_ptr` - A raw pointer to the prepared statement.
+/// * `index` - The 1-based position of the parameter.
+/// * `value` - A pointer to the `LibsqlPhpValue` to bind.
+///
+/// # Returns
+///
+/// Returns `0` if the value was bound. If an error occurs, returns the error code and records the error
+/// for `libsql_php_last_error_message`. The value stays bound until it is replaced or the bindings are cleared.
+#[no_mangle]
+pub unsafe extern "C" fn libsql_php_stmt_bind(
+    stmt_ptr: *mut libc::c_void,
+    index: libc::c_int,
+    value: *const LibsqlPhpValue,
+) -> libc::c_int {
+    catch_panic(|| {
+        if stmt_ptr.is_null() {
+            return libsql_php_error(ERR_NULL_STATEMENT_PTR, ErrorCode::NullStatementPtr);
+        }
+
+        if value.is_null() || index < 1 {
+            return libsql_php_error(
+                "Parameter value is null or index is not positive",
+                ErrorCode::InvalidParameter,
+            );
+        }
+
+        let statement = unsafe { &mut *(stmt_ptr as *mut PreparedStatement) };
+
+        let result =
+            unsafe { (*value).to_value() }.and_then(|value| statement.bind(index as usize, value));
+
+        match result {
+            Ok(()) => LIBSQL_PHP_OK,
+            Err(e) => libsql_php_error(
+                &format!("Parameter {index}: {e}"),
+                ErrorCode::InvalidParameter,
+            ),
+        }
+    })
+}
+
+/// Binds a typed value to a named parameter (`:name`, `@name` or `$name`) of a prepared statement.
+///
+/// # Safety
+///
+/// This function is marked as unsafe because it dereferences raw pointers.
+///
+/// # Arguments
+///
+/// * `stmt_ptr` - A raw pointer to the prepared statement.
+/// * `name` - A raw pointer to a C-style string holding the parameter name, with or without its prefix.
+/// * `value` - A pointer to the `LibsqlPhpValue` to bind.
+///
+/// # Returns
+///
+/// Returns `0` if the value was bound. If an error occurs, returns the error code and records the error
+/// for `libsql_php_last_error_message`. The value stays bound until it is replaced or the bindings are cleared.
+#[no_mangle]
+pub unsafe extern "C" fn libsql_php_stmt_bind_named(
+    stmt_ptr: *mut libc::c_void,
+    name: *const libc::c_char,
+    value: *const LibsqlPhpValue,
+) -> libc::c_int {
+    catch_panic(|| {
+        if stmt_ptr.is_null() {
+            return libsql_php_error(ERR_NULL_STATEMENT_PTR, ErrorCode::NullStatementPtr);
+        }
+
+        if name.is_null() || value.is_null() {
+            return libsql_php_error(
+                "Parameter name or value is null",
+                ErrorCode::InvalidParameter,
+            );
+        }
+
+        let statement = unsafe { &mut *(stmt_ptr as *mut PreparedStatement) };
+
+        let name_str = match unsafe { std::ffi::CStr::from_ptr(name) }.to_str() {
+            Ok(str) => str.to_string(),
+            Err(_) => return libsql_php_error(ERR_STRING_CONVERTION, ErrorCode::StringConvertion),
+        };
+
+        match unsafe { (*value).to_value() } {
+            Ok(value) => {
+                statement.bind_named(name_str, value);
+                LIBSQL_PHP_OK
+            }
+            Err(e) => libsql_php_error(
+                &format!("Parameter {name_str}: {e}"),
+                ErrorCode::InvalidParameter,
+            ),
+        }
+    })
+}
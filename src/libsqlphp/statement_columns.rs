@@ -0,0 +1,53 @@
+use crate::{
+    types::statement::PreparedStatement,
+    utils::{
+        errors::{catch_panic, libsql_php_error, ErrorCode, LIBSQL_PHP_OK},
+        query_result::{column_info, to_c_json},
+    },
+    ERR_NULL_OUT_PTR, ERR_NULL_STATEMENT_PTR,
+};
+
+/// Describes the result columns of a prepared statement in JSON format.
+///
+/// # Safety
+///
+/// This function is marked as unsafe because it dereferences raw pointers.
+///
+/// # Arguments
+///
+/// * `stmt_ptr` - A raw pointer to the prepared statement.
+/// * `out_json` - A pointer where the null-terminated C string holding the JSON array of columns is written.
+///   Free it with `libsql_php_free_string`.
+///   Each column is an object with its `name`, declared type `decltype`, origin `table` and `origin` column name,
+///   where everything but the name may be `null`.
+///
+/// # Returns
+///
+/// Returns `0` on success. If an error occurs, returns the error code and records the error for `libsql_php_last_error_message`.
+#[no_mangle]
+pub unsafe extern "C" fn libsql_php_stmt_columns(
+    stmt_ptr: *mut libc::c_void,
+    out_json: *mut *const libc::c_char,
+) -> libc::c_int {
+    catch_panic(|| {
+        if stmt_ptr.is_null() {
+            return libsql_php_error(ERR_NULL_STATEMENT_PTR, ErrorCode::NullStatementPtr);
+        }
+
+        if out_json.is_null() {
+            return libsql_php_error(ERR_NULL_OUT_PTR, ErrorCode::InvalidArguments);
+        }
+
+        let statement = unsafe { &*(stmt_ptr as *mut PreparedStatement) };
+
+        let c_json = to_c_json(&column_info(&statement.stmt));
+
+        match c_json {
+            Ok(c_json) => {
+                unsafe { *out_json = c_json.into_raw() };
+                LIBSQL_PHP_OK
+            }
+            Err(e) => libsql_php_error(&e, ErrorCode::Serialization),
+        }
+    })
+}
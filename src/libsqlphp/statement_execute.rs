@@ -0,0 +1,56 @@
+use crate::{
+    types::statement::PreparedStatement,
+    utils::{
+        errors::{
+            catch_panic, libsql_php_error, libsql_php_libsql_error, ErrorCode, LIBSQL_PHP_OK,
+        },
+        runtime::runtime,
+    },
+    ERR_NULL_STATEMENT_PTR,
+};
+
+/// Executes a prepared statement with its bound values.
+///
+/// # Safety
+///
+/// This function is marked as unsafe because it dereferences raw pointers.
+///
+/// # Arguments
+///
+/// * `stmt_ptr` - A raw pointer to the prepared statement.
+/// * `out_rows_affected` - A pointer where the number of rows affected by the statement is written. May be null.
+///
+/// # Returns
+///
+/// Returns `0` on success. If an error occurs, returns the error code and records the error for `libsql_php_last_error_message`.
+/// The statement is reset before it runs, so it can be executed again right away.
+#[no_mangle]
+pub unsafe extern "C" fn libsql_php_stmt_execute(
+    stmt_ptr: *mut libc::c_void,
+    out_rows_affected: *mut u64,
+) -> libc::c_int {
+    catch_panic(|| {
+        if stmt_ptr.is_null() {
+            return libsql_php_error(ERR_NULL_STATEMENT_PTR, ErrorCode::NullStatementPtr);
+        }
+
+        let statement = unsafe { &mut *(stmt_ptr as *mut PreparedStatement) };
+
+        let params = match statement.params() {
+            Ok(params) => params,
+            Err(e) => return libsql_php_error(&e, ErrorCode::InvalidParameter),
+        };
+
+        statement.stmt.reset();
+
+        match runtime().block_on(statement.stmt.execute(params)) {
+            Ok(rows_affected) => {
+                if !out_rows_affected.is_null() {
+                    unsafe { *out_rows_affected = rows_affected as u64 };
+                }
+                LIBSQL_PHP_OK
+            }
+            Err(e) => libsql_php_libsql_error(&e, ErrorCode::QueryExecution),
+        }
+    })
+}
@@ -0,0 +1,34 @@
+use crate::{
+    types::statement::PreparedStatement,
+    utils::errors::{catch_panic, libsql_php_error, ErrorCode, LIBSQL_PHP_OK},
+    ERR_NULL_STATEMENT_PTR,
+};
+
+/// Finalizes a prepared statement, releasing it and its bound values.
+///
+/// # Safety
+///
+/// This function is marked as unsafe because it takes ownership of a raw pointer.
+/// The statement must not be used after this call.
+///
+/// # Arguments
+///
+/// * `stmt_ptr` - A raw pointer to the prepared statement returned by `libsql_php_prepare`.
+///
+/// # Returns
+///
+/// Returns `0` if the statement was finalized. If the statement pointer is null, returns the error code
+/// and records the error for `libsql_php_last_error_message`.
+#[no_mangle]
+pub unsafe extern "C" fn libsql_php_stmt_finalize(stmt_ptr: *mut libc::c_void) -> libc::c_int {
+    catch_panic(|| {
+        if stmt_ptr.is_null() {
+            return libsql_php_error(ERR_NULL_STATEMENT_PTR, ErrorCode::NullStatementPtr);
+        }
+
+        let mut statement = unsafe { Box::from_raw(stmt_ptr as *mut PreparedStatement) };
+
+        statement.stmt.finalize();
+        LIBSQL_PHP_OK
+    })
+}
@@ -0,0 +1,84 @@
+use crate::{
+    types::statement::PreparedStatement,
+    utils::errors::{catch_panic, libsql_php_error, ErrorCode, LIBSQL_PHP_OK},
+    ERR_NULL_OUT_PTR, ERR_NULL_STATEMENT_PTR,
+};
+
+/// Retrieves the number of parameters of a prepared statement.
+///
+/// # Safety
+///
+/// This function is marked as unsafe because it dereferences raw pointers.
+///
+/// # Arguments
+///
+/// * `stmt_ptr` - A raw pointer to the prepared statement.
+/// * `out_count` - A pointer where the number of parameters is written.
+///
+/// # Returns
+///
+/// Returns `0` on success. If a pointer is null, returns the error code and records the error
+/// for `libsql_php_last_error_message`.
+#[no_mangle]
+pub unsafe extern "C" fn libsql_php_stmt_parameter_count(
+    stmt_ptr: *mut libc::c_void,
+    out_count: *mut libc::c_int,
+) -> libc::c_int {
+    catch_panic(|| {
+        if stmt_ptr.is_null() {
+            return libsql_php_error(ERR_NULL_STATEMENT_PTR, ErrorCode::NullStatementPtr);
+        }
+
+        if out_count.is_null() {
+            return libsql_php_error(ERR_NULL_OUT_PTR, ErrorCode::InvalidArguments);
+        }
+
+        let statement = unsafe { &*(stmt_ptr as *mut PreparedStatement) };
+
+        unsafe { *out_count = statement.stmt.parameter_count() as libc::c_int };
+        LIBSQL_PHP_OK
+    })
+}
+
+/// Retrieves the name of a parameter of a prepared statement, including its `:`, `@` or `$` prefix.
+///
+/// # Safety
+///
+/// This function is marked as unsafe because it dereferences raw pointers.
+///
+/// # Arguments
+///
+/// * `stmt_ptr` - A raw pointer to the prepared statement.
+/// * `index` - The 1-based position of the parameter.
+/// * `out_name` - A pointer where the parameter name is written, or a null pointer when the parameter is positional.
+///
+/// # Returns
+///
+/// Returns `0` on success. If a pointer is null, returns the error code and records the error
+/// for `libsql_php_last_error_message`. The name is owned by the statement and stays valid until it is finalized.
+#[no_mangle]
+pub unsafe extern "C" fn libsql_php_stmt_parameter_name(
+    stmt_ptr: *mut libc::c_void,
+    index: libc::c_int,
+    out_name: *mut *const libc::c_char,
+) -> libc::c_int {
+    catch_panic(|| {
+        if stmt_ptr.is_null() {
+            return libsql_php_error(ERR_NULL_STATEMENT_PTR, ErrorCode::NullStatementPtr);
+        }
+
+        if out_name.is_null() {
+            return libsql_php_error(ERR_NULL_OUT_PTR, ErrorCode::InvalidArguments);
+        }
+
+        let statement = unsafe { &*(stmt_ptr as *mut PreparedStatement) };
+
+        let name = usize::try_from(index)
+            .ok()
+            .and_then(|index| statement.parameter_name(index))
+            .map_or(std::ptr::null(), |name| name.as_ptr());
+
+        unsafe { *out_name = name };
+        LIBSQL_PHP_OK
+    })
+}
@@ -0,0 +1,71 @@
+use crate::{
+    types::statement::PreparedStatement,
+    utils::{
+        errors::{
+            catch_panic, libsql_php_error, libsql_php_libsql_error, ErrorCode, LIBSQL_PHP_OK,
+        },
+        query_result::{collect_rows, column_info, into_c_json},
+        runtime::runtime,
+    },
+    ERR_NULL_OUT_PTR, ERR_NULL_STATEMENT_PTR,
+};
+
+/// Runs a prepared statement with its bound values, returning the query result in JSON format.
+///
+/// # Safety
+///
+/// This function is marked as unsafe because it dereferences raw pointers.
+///
+/// # Arguments
+///
+/// * `stmt_ptr` - A raw pointer to the prepared statement.
+/// * `out_json` - A pointer where the null-terminated C string holding the JSON-formatted query result is written.
+///   Free it with `libsql_php_free_string`.
+///
+/// # Returns
+///
+/// Returns `0` on success. If an error occurs, returns the error code and records the error for `libsql_php_last_error_message`.
+/// The statement is reset before it runs, so it can be queried again right away.
+#[no_mangle]
+pub unsafe extern "C" fn libsql_php_stmt_query(
+    stmt_ptr: *mut libc::c_void,
+    out_json: *mut *const libc::c_char,
+) -> libc::c_int {
+    catch_panic(|| {
+        if stmt_ptr.is_null() {
+            return libsql_php_error(ERR_NULL_STATEMENT_PTR, ErrorCode::NullStatementPtr);
+        }
+
+        if out_json.is_null() {
+            return libsql_php_error(ERR_NULL_OUT_PTR, ErrorCode::InvalidArguments);
+        }
+
+        let statement = unsafe { &mut *(stmt_ptr as *mut PreparedStatement) };
+
+        let params = match statement.params() {
+            Ok(params) => params,
+            Err(e) => return libsql_php_error(&e, ErrorCode::InvalidParameter),
+        };
+
+        statement.stmt.reset();
+
+        let query_result = runtime().block_on(async {
+            let columns = column_info(&statement.stmt);
+            let rows = statement.stmt.query(params).await?;
+            collect_rows(columns, rows).await
+        });
+
+        let results = match query_result {
+            Ok(results) => results,
+            Err(e) => return libsql_php_libsql_error(&e, ErrorCode::QueryExecution),
+        };
+
+        match into_c_json(&results) {
+            Ok(c_json) => {
+                unsafe { *out_json = c_json };
+                LIBSQL_PHP_OK
+            }
+            Err(e) => libsql_php_error(&e, ErrorCode::Serialization),
+        }
+    })
+}
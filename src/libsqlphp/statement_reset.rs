@@ -0,0 +1,63 @@
+use crate::{
+    types::statement::PreparedStatement,
+    utils::errors::{catch_panic, libsql_php_error, ErrorCode, LIBSQL_PHP_OK},
+    ERR_NULL_STATEMENT_PTR,
+};
+
+/// Resets a prepared statement to its initial state, keeping the bound values.
+///
+/// # Safety
+///
+/// This function is marked as unsafe because it dereferences raw pointers.
+///
+/// # Arguments
+///
+/// * `stmt_ptr` - A raw pointer to the prepared statement.
+///
+/// # Returns
+///
+/// Returns `0` if the statement was reset. If the statement pointer is null, returns the error code
+/// and records the error for `libsql_php_last_error_message`.
+#[no_mangle]
+pub unsafe extern "C" fn libsql_php_stmt_reset(stmt_ptr: *mut libc::c_void) -> libc::c_int {
+    catch_panic(|| {
+        if stmt_ptr.is_null() {
+            return libsql_php_error(ERR_NULL_STATEMENT_PTR, ErrorCode::NullStatementPtr);
+        }
+
+        let statement = unsafe { &mut *(stmt_ptr as *mut PreparedStatement) };
+
+        statement.stmt.reset();
+        LIBSQL_PHP_OK
+    })
+}
+
+/// Forgets every value bound to a prepared statement.
+///
+/// # Safety
+///
+/// This function is marked as unsafe because it dereferences raw pointers.
+///
+/// # Arguments
+///
+/// * `stmt_ptr` - A raw pointer to the prepared statement.
+///
+/// # Returns
+///
+/// Returns `0` if the bindings were cleared. If the statement pointer is null, returns the error code
+/// and records the error for `libsql_php_last_error_message`.
+#[no_mangle]
+pub unsafe extern "C" fn libsql_php_stmt_clear_bindings(
+    stmt_ptr: *mut libc::c_void,
+) -> libc::c_int {
+    catch_panic(|| {
+        if stmt_ptr.is_null() {
+            return libsql_php_error(ERR_NULL_STATEMENT_PTR, ErrorCode::NullStatementPtr);
+        }
+
+        let statement = unsafe { &mut *(stmt_ptr as *mut PreparedStatement) };
+
+        statement.clear_bindings();
+        LIBSQL_PHP_OK
+    })
+}
@@ -1,10 +1,30 @@
+use std::{
+    sync::Arc,
+    time::{Duration, Instant},
+};
+
 use crate::{
-    types::db_pair::DbConnPair,
-    utils::{errors::libsql_php_error, runtime::runtime},
-    ERR_DATABASE_PAIR_NOT_FOUND,
+    types::{
+        handle::{ConnectionMode, LibsqlHandle},
+        replica::{LibsqlPhpReplicaStatus, ReplicaState, ReplicaSyncError},
+    },
+    utils::{
+        errors::{
+            catch_panic, libsql_php_error, libsql_php_libsql_error, ErrorCode, LIBSQL_PHP_OK,
+        },
+        runtime::runtime,
+    },
+    ERR_NULL_CLIENT_PTR, ERR_NULL_OUT_PTR, ERR_PRIMARY_TIMEOUT, ERR_SYNC_TIMEOUT,
+    ERR_UNSUPPORTED_MODE,
 };
 
-/// Synchronizes the remote replica database associated with the provided `DbConnPair`.
+/// How long `libsql_php_sync_until` waits between two syncs that did not reach the frame yet.
+const SYNC_UNTIL_POLL_INTERVAL: Duration = Duration::from_millis(50);
+
+/// Synchronizes the remote replica database associated with the provided `LibsqlHandle`.
+///
+/// A connection opened read-only by `libsql_php_connect_offline_replica` is switched to the replica
+/// once the sync succeeds.
 ///
 /// This function is marked with #[no_mangle] to ensure its symbol is preserved for
 /// use in external C code.
@@ -16,38 +36,311 @@ use crate::{
 ///
 /// # Arguments
 ///
-/// * `pair` - A raw pointer to a `DbConnPair` struct containing database and connection pointers.
+/// * `client_ptr` - A raw pointer to the `LibsqlHandle` of a remote replica connection.
+/// * `out_frame_no` - A pointer where the frame number the replica reached is written, or `-1`
+///   if it has not replicated any frame yet. May be null.
+/// * `out_frames_applied` - A pointer where the number of frames applied by this sync is written.
+///   May be null.
 ///
 /// # Returns
 ///
 /// * `0` - If synchronization is successful.
-/// * `-1` - If the provided database pair pointer is null.
-/// * `-2` - If there is an error during synchronization.
+/// * Otherwise the error code, with the error recorded for `libsql_php_last_error_message`.
 ///
 /// # Examples
 ///
-/// ```
+/// ```no_run
 /// use std::ptr;
+/// use sql_php_client::libsqlphp::sync::libsql_php_sync;
 ///
-/// let pair: *mut DbConnPair = ptr::null_mut();
-/// let result = libsql_php_sync(pair);
-/// assert_eq!(result, -1);
+/// let client_ptr: *mut std::ffi::c_void = ptr::null_mut();
+/// let result = unsafe { libsql_php_sync(client_ptr, ptr::null_mut(), ptr::null_mut()) };
+/// assert_ne!(result, 0);
 /// ```
 #[no_mangle]
-pub extern "C" fn libsql_php_sync(pair: *mut DbConnPair) -> i32 {
-    if pair.is_null() {
-        libsql_php_error(ERR_DATABASE_PAIR_NOT_FOUND, "ERR_DATABASE_PAIR_NOT_FOUND");
-        return -1;
+pub unsafe extern "C" fn libsql_php_sync(
+    client_ptr: *mut libc::c_void,
+    out_frame_no: *mut i64,
+    out_frames_applied: *mut u64,
+) -> libc::c_int {
+    catch_panic(|| {
+        let (handle, state) = match unsafe { replica(client_ptr) } {
+            Ok(replica) => replica,
+            Err(status) => return status,
+        };
+
+        let db = &handle.db;
+
+        let rt = runtime();
+        let result = rt.block_on(async {
+            let before = db
+                .replication_index()
+                .await
+                .map_err(ReplicaSyncError::Sync)?;
+            let after = state.sync(db, None).await?;
+            Ok((before, after))
+        });
+
+        match result {
+            Ok((before, after)) => {
+                if !out_frame_no.is_null() {
+                    unsafe { *out_frame_no = frame_no_to_c(after) };
+                }
+                if !out_frames_applied.is_null() {
+                    unsafe { *out_frames_applied = frames_between(before, after) };
+                }
+                unsafe { leave_read_only(client_ptr) };
+                LIBSQL_PHP_OK
+            }
+            Err(e) => sync_error(&e),
+        }
+    })
+}
+
+/// Synchronizes a remote replica until it has reached a frame number, such as the one
+/// returned for a write made through the primary.
+///
+/// Like `libsql_php_sync`, a connection opened read-only by `libsql_php_connect_offline_replica` is
+/// switched to the replica once the frame has been reached.
+///
+/// # Safety
+///
+/// This function manipulates raw pointers and interfaces with asynchronous code,
+/// requiring careful handling to ensure memory safety and avoid undefined behavior.
+///
+/// # Arguments
+///
+/// * `client_ptr` - A raw pointer to the `LibsqlHandle` of a remote replica connection.
+/// * `frame_no` - The frame number to wait for.
+/// * `timeout_ms` - How long, in milliseconds, to keep syncing before giving up. A replica that has
+///   already reached the frame returns without syncing, even with a timeout of `0`.
+/// * `out_frame_no` - A pointer where the frame number the replica reached is written, or `-1`
+///   if it has not replicated any frame yet. May be null.
+///
+/// # Returns
+///
+/// Returns `0` once the frame has been reached. Otherwise returns the error code, after the
+/// timeout when the frame was not reached, and records the error for `libsql_php_last_error_message`.
+#[no_mangle]
+pub unsafe extern "C" fn libsql_php_sync_until(
+    client_ptr: *mut libc::c_void,
+    frame_no: u64,
+    timeout_ms: libc::c_uint,
+    out_frame_no: *mut i64,
+) -> libc::c_int {
+    catch_panic(|| {
+        let (handle, state) = match unsafe { replica(client_ptr) } {
+            Ok(replica) => replica,
+            Err(status) => return status,
+        };
+
+        let db = &handle.db;
+        let deadline = Instant::now() + Duration::from_millis(timeout_ms.into());
+
+        let caught_up = |reached: Option<u64>| reached.is_some_and(|reached| reached >= frame_no);
+
+        let rt = runtime();
+        let result = rt.block_on(async {
+            let mut reached = db
+                .replication_index()
+                .await
+                .map_err(ReplicaSyncError::Sync)?;
+
+            loop {
+                if caught_up(reached) {
+                    return Ok((reached, true));
+                }
+
+                let now = Instant::now();
+                if now >= deadline {
+                    return Ok((reached, false));
+                }
+
+                match state.sync(db, Some(deadline - now)).await {
+                    Ok(index) => reached = index,
+                    // A background sync may have applied the frame while this one timed out.
+                    Err(ReplicaSyncError::Timeout) => {
+                        reached = db
+                            .replication_index()
+                            .await
+                            .map_err(ReplicaSyncError::Sync)?;
+                        continue;
+                    }
+                    Err(e) => return Err(e),
+                }
+
+                if !caught_up(reached) {
+                    let wait = deadline.saturating_duration_since(Instant::now());
+                    tokio::time::sleep(SYNC_UNTIL_POLL_INTERVAL.min(wait)).await;
+                }
+            }
+        });
+
+        match result {
+            Ok((reached, done)) => {
+                if !out_frame_no.is_null() {
+                    unsafe { *out_frame_no = frame_no_to_c(reached) };
+                }
+                if done {
+                    state.record_primary_frame(frame_no);
+                    unsafe { leave_read_only(client_ptr) };
+                    LIBSQL_PHP_OK
+                } else {
+                    libsql_php_error(ERR_SYNC_TIMEOUT, ErrorCode::SyncTimeout)
+                }
+            }
+            Err(e) => sync_error(&e),
+        }
+    })
+}
+
+/// Retrieves the frame number a remote replica has committed locally, without contacting the primary.
+///
+/// # Safety
+///
+/// This function is marked as unsafe because it dereferences raw pointers.
+///
+/// # Arguments
+///
+/// * `client_ptr` - A raw pointer to the `LibsqlHandle` of a remote replica connection.
+/// * `out_frame_no` - A pointer where the frame number is written, or `-1` if the replica has not
+///   replicated any frame yet.
+///
+/// # Returns
+///
+/// Returns `0` on success. If an error occurs, returns the error code and records the error for
+/// `libsql_php_last_error_message`.
+#[no_mangle]
+pub unsafe extern "C" fn libsql_php_replication_index(
+    client_ptr: *mut libc::c_void,
+    out_frame_no: *mut i64,
+) -> libc::c_int {
+    catch_panic(|| {
+        let (handle, _) = match unsafe { replica(client_ptr) } {
+            Ok(replica) => replica,
+            Err(status) => return status,
+        };
+
+        if out_frame_no.is_null() {
+            return libsql_php_error(ERR_NULL_OUT_PTR, ErrorCode::InvalidArguments);
+        }
+
+        match runtime().block_on(handle.db.replication_index()) {
+            Ok(frame_no) => {
+                unsafe { *out_frame_no = frame_no_to_c(frame_no) };
+                LIBSQL_PHP_OK
+            }
+            Err(e) => libsql_php_libsql_error(&e, ErrorCode::SyncFailed),
+        }
+    })
+}
+
+/// Reports whether a remote replica is stale and how far behind its primary it is, without
+/// contacting the primary.
+///
+/// The status reflects the syncs made through this extension: `libsql_php_sync`,
+/// `libsql_php_sync_until` and the background sync.
+///
+/// # Safety
+///
+/// This function is marked as unsafe because it dereferences raw pointers.
+///
+/// # Arguments
+///
+/// * `client_ptr` - A raw pointer to the `LibsqlHandle` of a remote replica connection.
+/// * `out_status` - A pointer to the `LibsqlPhpReplicaStatus` to fill in.
+///
+/// # Returns
+///
+/// Returns `0` on success. If an error occurs, returns the error code and records the error for
+/// `libsql_php_last_error_message`.
+#[no_mangle]
+pub unsafe extern "C" fn libsql_php_replica_status(
+    client_ptr: *mut libc::c_void,
+    out_status: *mut LibsqlPhpReplicaStatus,
+) -> libc::c_int {
+    catch_panic(|| {
+        let (handle, state) = match unsafe { replica(client_ptr) } {
+            Ok(replica) => replica,
+            Err(status) => return status,
+        };
+
+        if out_status.is_null() {
+            return libsql_php_error(ERR_NULL_OUT_PTR, ErrorCode::InvalidArguments);
+        }
+
+        match runtime().block_on(handle.db.replication_index()) {
+            Ok(frame_no) => {
+                unsafe { *out_status = state.status(frame_no) };
+                LIBSQL_PHP_OK
+            }
+            Err(e) => libsql_php_libsql_error(&e, ErrorCode::SyncFailed),
+        }
+    })
+}
+
+/// Returns the handle behind `client_ptr` and its sync state if it is a remote replica connection,
+/// or the status to return after the error has been recorded.
+pub(crate) unsafe fn replica<'a>(
+    client_ptr: *mut libc::c_void,
+) -> Result<(&'a LibsqlHandle, &'a Arc<ReplicaState>), libc::c_int> {
+    if client_ptr.is_null() {
+        return Err(libsql_php_error(
+            ERR_NULL_CLIENT_PTR,
+            ErrorCode::NullClientPtr,
+        ));
+    }
+
+    let handle = unsafe { &*(client_ptr as *mut LibsqlHandle) };
+
+    match (handle.mode, &handle.replica) {
+        (ConnectionMode::RemoteReplica, Some(state)) => Ok((handle, state)),
+        _ => Err(libsql_php_error(
+            ERR_UNSUPPORTED_MODE,
+            ErrorCode::UnsupportedMode,
+        )),
     }
+}
 
-    let pair = unsafe { &mut *pair };
-    let db = unsafe { &mut *pair.db };
+/// Switches a replica connection that fell back to reading the local replica file read-only over
+/// to the replica itself, once a sync has succeeded. A connection inside a transaction is kept.
+///
+/// # Safety
+///
+/// `client_ptr` must point to a `LibsqlHandle` that no other reference is in use for.
+unsafe fn leave_read_only(client_ptr: *mut libc::c_void) {
+    let handle = unsafe { &mut *(client_ptr as *mut LibsqlHandle) };
+    let Some(state) = &handle.replica else {
+        return;
+    };
 
-    let rt = runtime();
-    let result = rt.block_on(async { db.sync().await });
+    if !state.is_read_only() || !handle.conn.is_autocommit() {
+        return;
+    }
+
+    if let Ok(conn) = handle.db.connect() {
+        handle.conn = conn;
+        state.clear_read_only();
+    }
+}
+
+/// Records a failed sync and returns its status.
+fn sync_error(e: &ReplicaSyncError) -> libc::c_int {
+    match e {
+        ReplicaSyncError::Timeout => libsql_php_error(ERR_PRIMARY_TIMEOUT, ErrorCode::SyncTimeout),
+        ReplicaSyncError::Sync(e) => libsql_php_libsql_error(e, ErrorCode::SyncFailed),
+    }
+}
+
+fn frame_no_to_c(frame_no: Option<u64>) -> i64 {
+    frame_no.map_or(-1, |frame_no| frame_no as i64)
+}
 
-    match result {
-        Ok(_) => 0,   // Success
-        Err(_) => -2, // Indicate sync error
+/// Counts the frames between two replication indexes. Frames are numbered from `0`.
+fn frames_between(before: Option<u64>, after: Option<u64>) -> u64 {
+    match (before, after) {
+        (Some(before), Some(after)) => after.saturating_sub(before),
+        (None, Some(after)) => after + 1,
+        (_, None) => 0,
     }
 }
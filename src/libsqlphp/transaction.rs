@@ -1,6 +1,14 @@
 use crate::{
-    utils::{errors::libsql_php_error, runtime::runtime},
-    ERR_NULL_CLIENT_PTR,
+    types::{handle::LibsqlHandle, transaction::TransactionHandle},
+    utils::{
+        errors::{
+            catch_panic, libsql_php_error, libsql_php_libsql_error, ErrorCode, LIBSQL_PHP_OK,
+        },
+        retry::with_retry,
+        runtime::runtime,
+        transaction_behavior::{copy_behavior, transaction_behavior},
+    },
+    ERR_NULL_CLIENT_PTR, ERR_NULL_OUT_PTR, ERR_STRING_CONVERTION,
 };
 
 /// Initiates a LibSQL transaction in a PHP extension with the specified behavior.
@@ -11,51 +19,205 @@ use crate::{
 ///
 /// # Arguments
 ///
-/// * `client_ptr` - A raw pointer to the LibSQL connection client.
-/// * `behavior` - A raw pointer to a C-style string representing the transaction behavior. Can be "DEFERRED", "WRITE", "READ", or null.
+/// * `client_ptr` - A raw pointer to the `LibsqlHandle` of the connection.
+/// * `behavior` - A raw pointer to a C-style string representing the transaction behavior. Can be "DEFERRED", "IMMEDIATE",
+///   "EXCLUSIVE", "READONLY", or null for "DEFERRED". "WRITE" and "READ" are accepted for "IMMEDIATE" and "READONLY". Names are case-sensitive.
+/// * `out_trx` - A pointer where the raw pointer to the transaction handle is written. Release it with
+///   `libsql_php_transaction_free`.
 ///
 /// # Returns
 ///
-/// Returns a pointer to a memory location containing the transaction object if successful. If an error occurs or the client pointer is null, returns a null pointer.
+/// Returns `0` if the transaction was started. If an error occurs, the behavior is unknown or the client pointer is null,
+/// returns the error code and records the error for `libsql_php_last_error_message`.
 #[no_mangle]
-pub extern "C" fn libsql_php_transaction(
+pub unsafe extern "C" fn libsql_php_transaction(
     client_ptr: *mut libc::c_void,
     behavior: *const libc::c_char,
-) -> *mut libsql::Transaction {
-    if client_ptr.is_null() {
-        libsql_php_error(ERR_NULL_CLIENT_PTR, "ERR_INVALID_ARGUMENTS");
-        return std::ptr::null_mut();
-    }
+    out_trx: *mut *mut TransactionHandle,
+) -> libc::c_int {
+    catch_panic(|| {
+        if client_ptr.is_null() {
+            return libsql_php_error(ERR_NULL_CLIENT_PTR, ErrorCode::NullClientPtr);
+        }
 
-    let client = unsafe { &mut *(client_ptr as *mut libsql::Connection) };
+        if out_trx.is_null() {
+            return libsql_php_error(ERR_NULL_OUT_PTR, ErrorCode::InvalidArguments);
+        }
 
-    let behavior_str = if behavior.is_null() {
-        None
-    } else {
-        Some(
-            unsafe { std::ffi::CStr::from_ptr(behavior) }
-                .to_str()
-                .unwrap_or(""),
-        )
-    };
+        let handle = unsafe { &*(client_ptr as *mut LibsqlHandle) };
+        let client = &handle.conn;
 
-    let trx_behavior = match behavior_str {
-        Some("DEFERRED") => libsql::TransactionBehavior::Deferred,
-        Some("WRITE") => libsql::TransactionBehavior::Immediate,
-        Some("READ") => libsql::TransactionBehavior::ReadOnly,
-        _ => libsql::TransactionBehavior::Deferred,
+        let trx_behavior = if behavior.is_null() {
+            libsql::TransactionBehavior::Deferred
+        } else {
+            let behavior_str = match unsafe { std::ffi::CStr::from_ptr(behavior) }.to_str() {
+                Ok(str) => str,
+                Err(_) => {
+                    return libsql_php_error(ERR_STRING_CONVERTION, ErrorCode::StringConvertion);
+                }
+            };
+
+            match transaction_behavior(behavior_str) {
+                Ok(trx_behavior) => trx_behavior,
+                Err(e) => return libsql_php_error(&e, ErrorCode::InvalidTransactionBehavior),
+            }
+        };
+
+        let trx = runtime().block_on(with_retry(&handle.retry, &handle.conn, || {
+            client.transaction_with_behavior(copy_behavior(&trx_behavior))
+        }));
+
+        match trx {
+            Ok(trx) => {
+                unsafe { *out_trx = Box::into_raw(Box::new(TransactionHandle::new(trx))) };
+                LIBSQL_PHP_OK
+            }
+            Err(e) => libsql_php_libsql_error(&e, ErrorCode::InitiateTransaction),
+        }
+    })
+}
+
+#[cfg(test)]
+mod tests {
+    use std::{ffi::CString, mem::discriminant};
+
+    use libsql::TransactionBehavior;
+
+    use super::*;
+    use crate::{
+        libsqlphp::{
+            execute_batch::libsql_php_execute_batch, transaction_exec::libsql_php_transaction_exec,
+            transaction_free::libsql_php_transaction_free,
+        },
+        utils::testing::{close, connect_local, exec, last_error, temp_path},
+        ERR_TRANSACTION_BEHAVIOR,
     };
 
-    let trx = runtime().block_on(async {
-        let transaction = client.transaction_with_behavior(trx_behavior).await;
-        transaction
-    });
+    fn start(conn: *mut libc::c_void, behavior: Option<&str>) -> (libc::c_int, *mut libc::c_void) {
+        let behavior = behavior.map(|behavior| CString::new(behavior).unwrap());
+        let mut trx = std::ptr::null_mut();
+        let status = unsafe {
+            libsql_php_transaction(
+                conn,
+                behavior.as_ref().map_or(std::ptr::null(), |b| b.as_ptr()),
+                &mut trx,
+            )
+        };
+        (status, trx as *mut libc::c_void)
+    }
+
+    fn run(conn: *mut libc::c_void, sql: &str) -> libc::c_int {
+        let sql = CString::new(sql).unwrap();
+        unsafe { libsql_php_execute_batch(conn, sql.as_ptr()) }
+    }
 
-    match trx {
-        Ok(trx) => Box::into_raw(Box::new(trx)),
-        Err(e) => {
-            libsql_php_error(&format!("{e}"), "ERR_INICIATE_TRANSACTION");
-            std::ptr::null_mut()
+    fn trx_run(trx: *mut libc::c_void, sql: &str) -> libc::c_int {
+        let sql = CString::new(sql).unwrap();
+        unsafe { libsql_php_transaction_exec(trx, sql.as_ptr(), std::ptr::null(), 0) }
+    }
+
+    #[test]
+    fn starts_every_behavior() {
+        let path = temp_path("transaction_behavior");
+        let conn = connect_local(path.to_str().unwrap());
+        let other = connect_local(path.to_str().unwrap());
+        exec(conn, "CREATE TABLE t (id INTEGER)");
+
+        // (name, behavior, takes the write lock, blocks readers)
+        for (name, behavior, locks, exclusive) in [
+            (None, TransactionBehavior::Deferred, false, false),
+            (
+                Some("DEFERRED"),
+                TransactionBehavior::Deferred,
+                false,
+                false,
+            ),
+            (
+                Some("IMMEDIATE"),
+                TransactionBehavior::Immediate,
+                true,
+                false,
+            ),
+            (Some("WRITE"), TransactionBehavior::Immediate, true, false),
+            (
+                Some("EXCLUSIVE"),
+                TransactionBehavior::Exclusive,
+                true,
+                true,
+            ),
+            (
+                Some("READONLY"),
+                TransactionBehavior::ReadOnly,
+                false,
+                false,
+            ),
+            (Some("READ"), TransactionBehavior::ReadOnly, false, false),
+        ] {
+            if let Some(name) = name {
+                let parsed = transaction_behavior(name).unwrap();
+                assert_eq!(discriminant(&parsed), discriminant(&behavior), "{name}");
+            }
+
+            let (status, trx) = start(conn, name);
+            assert_eq!(status, LIBSQL_PHP_OK, "{name:?}: {}", last_error());
+
+            let locked = run(other, "BEGIN IMMEDIATE") != LIBSQL_PHP_OK;
+            if !locked {
+                exec(other, "ROLLBACK");
+            }
+            assert_eq!(locked, locks, "{name:?} write lock");
+
+            let blocked = run(other, "SELECT * FROM t") != LIBSQL_PHP_OK;
+            assert_eq!(blocked, exclusive, "{name:?} read lock");
+
+            // A local database runs READONLY as a deferred transaction; only a server enforces it.
+            assert_eq!(
+                trx_run(trx, "SELECT * FROM t"),
+                LIBSQL_PHP_OK,
+                "{}",
+                last_error()
+            );
+            assert_eq!(unsafe { libsql_php_transaction_free(trx) }, LIBSQL_PHP_OK);
         }
+
+        close(other);
+        close(conn);
+        let _ = std::fs::remove_file(path);
+    }
+
+    #[test]
+    fn rejects_unknown_behaviors() {
+        let path = temp_path("transaction_behavior_unknown");
+        let conn = connect_local(path.to_str().unwrap());
+
+        for behavior in [
+            "deferred",
+            "Immediate",
+            "exclusive",
+            "readonly",
+            " DEFERRED",
+            "SHARED",
+            "",
+        ] {
+            let (status, trx) = start(conn, Some(behavior));
+            assert_eq!(
+                status,
+                ErrorCode::InvalidTransactionBehavior as libc::c_int,
+                "{behavior:?}"
+            );
+            assert_eq!(
+                last_error(),
+                format!("{ERR_TRANSACTION_BEHAVIOR}: {behavior}")
+            );
+            assert!(trx.is_null());
+        }
+
+        // Nothing was started, so the connection is still in autocommit mode.
+        let (status, trx) = start(conn, Some("IMMEDIATE"));
+        assert_eq!(status, LIBSQL_PHP_OK, "{}", last_error());
+        assert_eq!(unsafe { libsql_php_transaction_free(trx) }, LIBSQL_PHP_OK);
+
+        close(conn);
+        let _ = std::fs::remove_file(path);
     }
 }
@@ -0,0 +1,44 @@
+use crate::{
+    types::transaction::TransactionHandle,
+    utils::errors::{catch_panic, libsql_php_error, ErrorCode, LIBSQL_PHP_OK},
+    ERR_NULL_CLIENT_PTR, ERR_NULL_OUT_PTR,
+};
+
+/// Retrieves the number of rows affected by the last statement executed within a transaction.
+///
+/// # Safety
+///
+/// This function is marked as unsafe because it dereferences raw pointers.
+///
+/// # Arguments
+///
+/// * `trx_ptr` - A raw pointer to the transaction handle returned by `libsql_php_transaction`.
+/// * `out_rows` - A pointer where the number of affected rows is written.
+///
+/// # Returns
+///
+/// Returns `0` on success. If the transaction pointer or the output pointer is null, or the transaction was already
+/// committed or rolled back, returns the error code and records the error for `libsql_php_last_error_message`.
+#[no_mangle]
+pub unsafe extern "C" fn libsql_php_transaction_changes(
+    trx_ptr: *mut libc::c_void,
+    out_rows: *mut u64,
+) -> libc::c_int {
+    catch_panic(|| {
+        if trx_ptr.is_null() {
+            return libsql_php_error(ERR_NULL_CLIENT_PTR, ErrorCode::NullClientPtr);
+        }
+
+        if out_rows.is_null() {
+            return libsql_php_error(ERR_NULL_OUT_PTR, ErrorCode::InvalidArguments);
+        }
+
+        let transaction = match unsafe { &*(trx_ptr as *mut TransactionHandle) }.get() {
+            Ok(transaction) => transaction,
+            Err(e) => return libsql_php_error(&e, ErrorCode::TransactionFinished),
+        };
+
+        unsafe { *out_rows = transaction.changes() };
+        LIBSQL_PHP_OK
+    })
+}
@@ -1,5 +1,8 @@
 use crate::{
-    utils::{errors::libsql_php_error, runtime::runtime},
+    types::transaction::TransactionHandle,
+    utils::errors::{
+        catch_panic, libsql_php_error, libsql_php_libsql_error_in, ErrorCode, LIBSQL_PHP_OK,
+    },
     ERR_NULL_CLIENT_PTR, ERR_TRANSACTION_COMMIT,
 };
 
@@ -8,30 +11,83 @@ use crate::{
 /// # Safety
 ///
 /// This function is marked as unsafe because it dereferences raw pointers.
+/// The handle stays valid and must still be released with `libsql_php_transaction_free`.
 ///
 /// # Arguments
 ///
-/// * `trx_ptr` - A raw pointer to the transaction object.
+/// * `trx_ptr` - A raw pointer to the transaction handle returned by `libsql_php_transaction`.
 ///
 /// # Returns
 ///
-/// Returns `1` if the transaction is successfully committed. If an error occurs or a null pointer is provided, returns `0`.
+/// Returns `0` if the transaction is successfully committed. If an error occurs, a null pointer is provided or the
+/// transaction was already committed or rolled back, returns the error code and records the error for
+/// `libsql_php_last_error_message`. A transaction whose commit fails is rolled back.
 #[no_mangle]
-pub extern "C" fn libsql_php_transaction_commit(trx_ptr: *mut libc::c_void) -> i64 {
-    if trx_ptr.is_null() {
-        libsql_php_error(ERR_NULL_CLIENT_PTR, "ERR_NULL_CLIENT_PTR");
-        return 0;
-    }
-
-    let transaction = unsafe { std::ptr::read(trx_ptr as *mut libsql::Transaction) };
+pub unsafe extern "C" fn libsql_php_transaction_commit(trx_ptr: *mut libc::c_void) -> libc::c_int {
+    catch_panic(|| {
+        if trx_ptr.is_null() {
+            return libsql_php_error(ERR_NULL_CLIENT_PTR, ErrorCode::NullClientPtr);
+        }
 
-    let commited = runtime().block_on(async { transaction.commit().await });
+        let handle = unsafe { &mut *(trx_ptr as *mut TransactionHandle) };
 
-    match commited {
-        Ok(_) => 1,
-        Err(_) => {
-            libsql_php_error(ERR_TRANSACTION_COMMIT, "ERR_TRANSACTION_COMMIT");
-            0
+        match handle.commit() {
+            Ok(Ok(())) => LIBSQL_PHP_OK,
+            Ok(Err(e)) => {
+                libsql_php_libsql_error_in(ERR_TRANSACTION_COMMIT, &e, ErrorCode::TransactionCommit)
+            }
+            Err(e) => libsql_php_error(&e, ErrorCode::TransactionFinished),
         }
+    })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::{
+        libsqlphp::{
+            is_autocommit::libsql_php_is_autocommit,
+            last_error::libsql_php_last_error_extended_code,
+            transaction_free::libsql_php_transaction_free,
+        },
+        utils::testing::{begin, close, connect_memory, exec, last_error, query, trx_exec},
+    };
+
+    #[test]
+    fn failed_commit_rolls_back() {
+        let conn = connect_memory();
+        exec(
+            conn,
+            "PRAGMA foreign_keys = ON;
+             CREATE TABLE parent (id INTEGER PRIMARY KEY);
+             CREATE TABLE child (parent_id INTEGER REFERENCES parent (id) DEFERRABLE INITIALLY DEFERRED);",
+        );
+
+        let trx = begin(conn);
+        trx_exec(trx, "INSERT INTO child VALUES (1)");
+
+        let status = unsafe { libsql_php_transaction_commit(trx) };
+        assert_eq!(status, ErrorCode::TransactionCommit as libc::c_int);
+        assert!(last_error().starts_with(ERR_TRANSACTION_COMMIT));
+        // SQLITE_CONSTRAINT_FOREIGNKEY
+        assert_eq!(libsql_php_last_error_extended_code(), 787);
+
+        let mut autocommit = 0;
+        assert_eq!(
+            unsafe { libsql_php_is_autocommit(conn, &mut autocommit) },
+            LIBSQL_PHP_OK
+        );
+        assert_eq!(autocommit, 1);
+        assert_eq!(
+            query(conn, "SELECT * FROM child")["rows"],
+            serde_json::json!([])
+        );
+
+        let status = unsafe { libsql_php_transaction_commit(trx) };
+        assert_eq!(status, ErrorCode::TransactionFinished as libc::c_int);
+        assert!(last_error().ends_with("it was rolled back"));
+
+        assert_eq!(unsafe { libsql_php_transaction_free(trx) }, LIBSQL_PHP_OK);
+        close(conn);
     }
 }
@@ -0,0 +1,68 @@
+use crate::{
+    types::{transaction::TransactionHandle, value::LibsqlPhpValue},
+    utils::{
+        errors::{
+            catch_panic, libsql_php_error, libsql_php_libsql_error, ErrorCode, LIBSQL_PHP_OK,
+        },
+        params::values_from_raw,
+        runtime::runtime,
+    },
+    ERR_NULL_CLIENT_PTR, ERR_STRING_CONVERTION,
+};
+
+/// Executes a LibSQL query with typed positional parameters within a transaction in a PHP extension.
+///
+/// # Safety
+///
+/// This function is marked as unsafe because it dereferences raw pointers.
+///
+/// # Arguments
+///
+/// * `trx_ptr` - A raw pointer to the transaction handle returned by `libsql_php_transaction`.
+/// * `query` - A raw pointer to a C-style string representing the LibSQL query to execute.
+/// * `values` - A pointer to an array of `LibsqlPhpValue` representing query parameters.
+/// * `values_len` - The length of the `values` array.
+///
+/// # Returns
+///
+/// Returns `0` if the query was executed. If an error occurs or a null pointer is provided, returns the error code
+/// and records the error for `libsql_php_last_error_message`.
+#[no_mangle]
+pub unsafe extern "C" fn libsql_php_transaction_exec_values(
+    trx_ptr: *mut libc::c_void,
+    query: *const libc::c_char,
+    values: *const LibsqlPhpValue,
+    values_len: usize,
+) -> libc::c_int {
+    catch_panic(|| {
+        if trx_ptr.is_null() || query.is_null() {
+            return libsql_php_error(ERR_NULL_CLIENT_PTR, ErrorCode::NullClientPtr);
+        }
+
+        let transaction = match unsafe { &*(trx_ptr as *mut TransactionHandle) }.get() {
+            Ok(transaction) => transaction,
+            Err(e) => return libsql_php_error(&e, ErrorCode::TransactionFinished),
+        };
+
+        let c_str_query = unsafe { std::ffi::CStr::from_ptr(query) };
+
+        let query_str = match c_str_query.to_str() {
+            Ok(str) => str,
+            Err(_) => {
+                return libsql_php_error(ERR_STRING_CONVERTION, ErrorCode::StringConvertion);
+            }
+        };
+
+        let params = match unsafe { values_from_raw(values, values_len) } {
+            Ok(params) => params,
+            Err(e) => return libsql_php_error(&e, ErrorCode::InvalidParameter),
+        };
+
+        let result = runtime().block_on(async { transaction.execute(query_str, params).await });
+
+        match result {
+            Ok(_) => LIBSQL_PHP_OK,
+            Err(e) => libsql_php_libsql_error(&e, ErrorCode::ExecutionFailed),
+        }
+    })
+}
@@ -0,0 +1,57 @@
+use crate::{
+    types::transaction::TransactionHandle,
+    utils::{
+        errors::{
+            catch_panic, libsql_php_error, libsql_php_libsql_error, ErrorCode, LIBSQL_PHP_OK,
+        },
+        runtime::runtime,
+    },
+    ERR_INVALID_ARGUMENTS, ERR_INVALID_QUERY_CONVERT,
+};
+
+/// Executes a batch of SQL statements within a transaction in a PHP extension.
+///
+/// # Safety
+///
+/// This function is marked as unsafe because it dereferences raw pointers and performs FFI operations.
+///
+/// # Arguments
+///
+/// * `trx_ptr` - A raw pointer to the transaction handle returned by `libsql_php_transaction`.
+/// * `query` - A raw pointer to a C-style string representing the batch SQL query to execute.
+///
+/// # Returns
+///
+/// Returns `0` if the batch was executed. If an error occurs, returns the error code and records the error for `libsql_php_last_error_message`.
+#[no_mangle]
+pub unsafe extern "C" fn libsql_php_transaction_execute_batch(
+    trx_ptr: *mut libc::c_void,
+    query: *const libc::c_char,
+) -> libc::c_int {
+    catch_panic(|| {
+        if trx_ptr.is_null() || query.is_null() {
+            return libsql_php_error(ERR_INVALID_ARGUMENTS, ErrorCode::InvalidArguments);
+        }
+
+        let transaction = match unsafe { &*(trx_ptr as *mut TransactionHandle) }.get() {
+            Ok(transaction) => transaction,
+            Err(e) => return libsql_php_error(&e, ErrorCode::TransactionFinished),
+        };
+
+        let c_str_query = unsafe { std::ffi::CStr::from_ptr(query) };
+
+        let query_str = match c_str_query.to_str() {
+            Ok(str) => str,
+            Err(_) => {
+                return libsql_php_error(ERR_INVALID_QUERY_CONVERT, ErrorCode::InvalidQueryConvert);
+            }
+        };
+
+        let exec_result = runtime().block_on(async { transaction.execute_batch(query_str).await });
+
+        match exec_result {
+            Ok(_) => LIBSQL_PHP_OK,
+            Err(e) => libsql_php_libsql_error(&e, ErrorCode::QueryExecution),
+        }
+    })
+}
@@ -0,0 +1,33 @@
+use crate::{
+    types::transaction::TransactionHandle,
+    utils::errors::{catch_panic, libsql_php_error, ErrorCode, LIBSQL_PHP_OK},
+    ERR_NULL_CLIENT_PTR,
+};
+
+/// Releases a transaction handle, rolling the transaction back if it was neither committed nor
+/// rolled back.
+///
+/// # Safety
+///
+/// This function is marked as unsafe because it takes ownership of a raw pointer.
+/// The handle must not be used after this call.
+///
+/// # Arguments
+///
+/// * `trx_ptr` - A raw pointer to the transaction handle returned by `libsql_php_transaction`.
+///
+/// # Returns
+///
+/// Returns `0` if the handle was released. If the transaction pointer is null, returns the error code
+/// and records the error for `libsql_php_last_error_message`.
+#[no_mangle]
+pub unsafe extern "C" fn libsql_php_transaction_free(trx_ptr: *mut libc::c_void) -> libc::c_int {
+    catch_panic(|| {
+        if trx_ptr.is_null() {
+            return libsql_php_error(ERR_NULL_CLIENT_PTR, ErrorCode::NullClientPtr);
+        }
+
+        drop(unsafe { Box::from_raw(trx_ptr as *mut TransactionHandle) });
+        LIBSQL_PHP_OK
+    })
+}
@@ -0,0 +1,68 @@
+use crate::{
+    types::{statement::PreparedStatement, transaction::TransactionHandle},
+    utils::{
+        errors::{
+            catch_panic, libsql_php_error, libsql_php_libsql_error, ErrorCode, LIBSQL_PHP_OK,
+        },
+        runtime::runtime,
+    },
+    ERR_INVALID_ARGUMENTS, ERR_INVALID_QUERY_CONVERT, ERR_NULL_OUT_PTR,
+};
+
+/// Prepares a SQL statement within a transaction so it can be executed many times without re-parsing.
+///
+/// The statement runs on the connection of the transaction, so its executions are part of the transaction
+/// until it is committed or rolled back.
+///
+/// # Safety
+///
+/// This function is marked as unsafe because it dereferences raw pointers.
+///
+/// # Arguments
+///
+/// * `trx_ptr` - A raw pointer to the transaction handle returned by `libsql_php_transaction`.
+/// * `query` - A raw pointer to a C-style string representing the SQL statement to prepare.
+/// * `out_stmt` - A pointer where the prepared statement handle is written.
+///
+/// # Returns
+///
+/// Returns `0` on success. If an error occurs, returns the error code and records the error for `libsql_php_last_error_message`.
+/// The statement must be released with `libsql_php_stmt_finalize`.
+#[no_mangle]
+pub unsafe extern "C" fn libsql_php_transaction_prepare(
+    trx_ptr: *mut libc::c_void,
+    query: *const libc::c_char,
+    out_stmt: *mut *mut PreparedStatement,
+) -> libc::c_int {
+    catch_panic(|| {
+        if trx_ptr.is_null() || query.is_null() {
+            return libsql_php_error(ERR_INVALID_ARGUMENTS, ErrorCode::InvalidArguments);
+        }
+
+        if out_stmt.is_null() {
+            return libsql_php_error(ERR_NULL_OUT_PTR, ErrorCode::InvalidArguments);
+        }
+
+        let transaction = match unsafe { &*(trx_ptr as *mut TransactionHandle) }.get() {
+            Ok(transaction) => transaction,
+            Err(e) => return libsql_php_error(&e, ErrorCode::TransactionFinished),
+        };
+
+        let c_str_query = unsafe { std::ffi::CStr::from_ptr(query) };
+
+        let query_str = match c_str_query.to_str() {
+            Ok(str) => str,
+            Err(_) => {
+                return libsql_php_error(ERR_INVALID_QUERY_CONVERT, ErrorCode::InvalidQueryConvert);
+            }
+        };
+
+        match runtime().block_on(transaction.prepare(query_str)) {
+            Ok(stmt) => {
+                unsafe { *out_stmt = Box::into_raw(Box::new(PreparedStatement::new(stmt))) };
+                LIBSQL_PHP_OK
+            }
+            Err(e) => libsql_php_libsql_error(&e, ErrorCode::QueryExecution),
+        }
+    })
+}
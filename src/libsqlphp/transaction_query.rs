@@ -0,0 +1,92 @@
+use crate::{
+    types::transaction::TransactionHandle,
+    utils::{
+        errors::{
+            catch_panic, libsql_php_error, libsql_php_libsql_error, ErrorCode, LIBSQL_PHP_OK,
+        },
+        params::text_from_raw,
+        query_result::{collect_rows, column_info, into_c_json},
+        runtime::runtime,
+    },
+    ERR_INVALID_QUERY_CONVERT, ERR_NULL_CLIENT_PTR, ERR_NULL_OUT_PTR,
+};
+
+/// Executes a LibSQL query within a transaction, returning the query result in the JSON format of
+/// `libsql_php_query`.
+///
+/// # Safety
+///
+/// This function manipulates raw pointers and interfaces with asynchronous code,
+/// requiring careful handling to ensure memory safety and avoid undefined behavior.
+///
+/// # Arguments
+///
+/// * `trx_ptr` - A raw pointer to the transaction handle returned by `libsql_php_transaction`.
+/// * `query` - A pointer to a null-terminated C string representing the SQL query.
+/// * `query_params` - A pointer to an array of null-terminated C strings representing query parameters,
+///   bound as text. A null or non-UTF-8 parameter is rejected with `InvalidParameter`.
+/// * `query_params_len` - The number of query parameters in the array.
+/// * `out_json` - A pointer where the null-terminated C string holding the JSON-formatted query result is written.
+///   Free it with `libsql_php_free_string`.
+///
+/// # Returns
+///
+/// Returns `0` on success. If an error occurs, returns the error code and records the error
+/// for `libsql_php_last_error_message`.
+#[no_mangle]
+pub unsafe extern "C" fn libsql_php_transaction_query(
+    trx_ptr: *mut libc::c_void,
+    query: *const libc::c_char,
+    query_params: *const *const libc::c_char,
+    query_params_len: usize,
+    out_json: *mut *const libc::c_char,
+) -> libc::c_int {
+    catch_panic(|| {
+        if trx_ptr.is_null() || query.is_null() {
+            return libsql_php_error(ERR_NULL_CLIENT_PTR, ErrorCode::NullClientPtr);
+        }
+
+        if out_json.is_null() {
+            return libsql_php_error(ERR_NULL_OUT_PTR, ErrorCode::InvalidArguments);
+        }
+
+        let transaction = match unsafe { &*(trx_ptr as *mut TransactionHandle) }.get() {
+            Ok(transaction) => transaction,
+            Err(e) => return libsql_php_error(&e, ErrorCode::TransactionFinished),
+        };
+
+        let c_str_query = unsafe { std::ffi::CStr::from_ptr(query) };
+
+        let query_str = match c_str_query.to_str() {
+            Ok(str) => str,
+            Err(_) => {
+                return libsql_php_error(ERR_INVALID_QUERY_CONVERT, ErrorCode::InvalidQueryConvert);
+            }
+        };
+
+        let params = match unsafe { text_from_raw(query_params, query_params_len) } {
+            Ok(params) => params,
+            Err(e) => return libsql_php_error(&e, ErrorCode::InvalidParameter),
+        };
+
+        let query_result = runtime().block_on(async {
+            let mut stmt = transaction.prepare(query_str).await?;
+            let columns = column_info(&stmt);
+            let rows = stmt.query(params).await?;
+            collect_rows(columns, rows).await
+        });
+
+        let results = match query_result {
+            Ok(results) => results,
+            Err(e) => return libsql_php_libsql_error(&e, ErrorCode::QueryExecution),
+        };
+
+        match into_c_json(&results) {
+            Ok(c_json) => {
+                unsafe { *out_json = c_json };
+                LIBSQL_PHP_OK
+            }
+            Err(e) => libsql_php_error(&e, ErrorCode::Serialization),
+        }
+    })
+}
@@ -1,5 +1,11 @@
 use crate::{
-    utils::{errors::libsql_php_error, runtime::runtime},
+    types::transaction::TransactionHandle,
+    utils::{
+        errors::{
+            catch_panic, libsql_php_error, libsql_php_libsql_error_in, ErrorCode, LIBSQL_PHP_OK,
+        },
+        runtime::runtime,
+    },
     ERR_NULL_CLIENT_PTR, ERR_TRANSACTION_ROLLBACK,
 };
 
@@ -8,30 +14,42 @@ use crate::{
 /// # Safety
 ///
 /// This function is marked as unsafe because it dereferences raw pointers.
+/// The handle stays valid and must still be released with `libsql_php_transaction_free`.
 ///
 /// # Arguments
 ///
-/// * `trx_ptr` - A raw pointer to the transaction object.
+/// * `trx_ptr` - A raw pointer to the transaction handle returned by `libsql_php_transaction`.
 ///
 /// # Returns
 ///
-/// Returns `1` if the transaction is successfully rolled back. If an error occurs or a null pointer is provided, returns `0`.
+/// Returns `0` if the transaction is successfully rolled back. If an error occurs, a null pointer is provided or the
+/// transaction was already committed or rolled back, returns the error code and records the error for
+/// `libsql_php_last_error_message`.
 #[no_mangle]
-pub extern "C" fn libsql_php_transaction_rollback(trx_ptr: *mut libc::c_void) -> i64 {
-    if trx_ptr.is_null() {
-        libsql_php_error(ERR_NULL_CLIENT_PTR, "ERR_NULL_CLIENT_PTR");
-        return 0;
-    }
+pub unsafe extern "C" fn libsql_php_transaction_rollback(
+    trx_ptr: *mut libc::c_void,
+) -> libc::c_int {
+    catch_panic(|| {
+        if trx_ptr.is_null() {
+            return libsql_php_error(ERR_NULL_CLIENT_PTR, ErrorCode::NullClientPtr);
+        }
+
+        let handle = unsafe { &mut *(trx_ptr as *mut TransactionHandle) };
 
-    let transaction = unsafe { std::ptr::read(trx_ptr as *mut libsql::Transaction) };
+        let transaction = match handle.take_for_rollback() {
+            Ok(transaction) => transaction,
+            Err(e) => return libsql_php_error(&e, ErrorCode::TransactionFinished),
+        };
 
-    let rollback = runtime().block_on(async { transaction.rollback().await });
+        let rollback = runtime().block_on(async { transaction.rollback().await });
 
-    match rollback {
-        Ok(_) => 1,
-        Err(_) => {
-            libsql_php_error(ERR_TRANSACTION_ROLLBACK, "ERR_TRANSACTION_ROLLBACK");
-            0
+        match rollback {
+            Ok(_) => LIBSQL_PHP_OK,
+            Err(e) => libsql_php_libsql_error_in(
+                ERR_TRANSACTION_ROLLBACK,
+                &e,
+                ErrorCode::TransactionRollback,
+            ),
         }
-    }
+    })
 }
@@ -1,12 +1,29 @@
+use std::ffi::CString;
+
+use once_cell::sync::OnceCell;
+
+use crate::utils::errors::catch_panic_or;
+
 /// Retrieves the version of the LibSQL library in use.
 ///
 /// # Returns
 ///
 /// Returns a pointer to a C-style string containing the version information of the LibSQL library.
+/// The string is owned by the library for its whole lifetime and must not be freed.
 #[no_mangle]
 pub extern "C" fn libsql_version() -> *const libc::c_char {
-    let version =
-        "LibSQL version ".to_string() + ": " + &libsql::version() + "-" + &libsql::version_number().to_string();
-    let c_version = std::ffi::CString::new(version).expect("CString::new failed");
-    c_version.into_raw() as *const libc::c_char
+    static VERSION: OnceCell<CString> = OnceCell::new();
+
+    catch_panic_or(std::ptr::null(), || {
+        VERSION
+            .get_or_init(|| {
+                let version = format!(
+                    "LibSQL version : {}-{}",
+                    libsql::version(),
+                    libsql::version_number()
+                );
+                CString::new(version).unwrap_or_default()
+            })
+            .as_ptr()
+    })
 }
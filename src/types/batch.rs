@@ -0,0 +1,174 @@
+use libsql::params::Params;
+use serde::Serialize;
+
+use super::{handle::ConnectionMode, value::LibsqlPhpValue};
+use crate::utils::{
+    params::{named_from_raw, resolve_named, values_from_raw},
+    query_result::{collect_rows, column_info, QueryResult},
+};
+
+/// Represents one statement of a batch passed from C code to `libsql_php_batch`.
+///
+/// This struct is marked with #[repr(C)] to ensure its memory layout is compatible
+/// with C code.
+#[repr(C)]
+pub struct LibsqlPhpBatchStatement {
+    /// A pointer to a null-terminated C string holding the SQL statement.
+    pub sql: *const libc::c_char,
+    /// A pointer to an array of C-style strings holding the parameter names, or null for positional parameters.
+    pub names: *const *const libc::c_char,
+    /// A pointer to an array of `LibsqlPhpValue` holding the parameters of the statement.
+    pub values: *const LibsqlPhpValue,
+    /// The length of the `values` array, and of the `names` array when it is given.
+    pub values_len: usize,
+}
+
+/// One statement of a batch, read from a `LibsqlPhpBatchStatement`.
+pub struct BatchStatement {
+    /// The SQL statement.
+    pub sql: String,
+    /// The parameters of the statement, with names as given by the caller.
+    pub params: Params,
+}
+
+/// Why a `LibsqlPhpBatchStatement` could not be read.
+pub enum BatchStatementError {
+    /// The SQL statement is null or not valid UTF-8.
+    Sql,
+    /// A parameter could not be converted, with the reason.
+    Parameter(String),
+}
+
+impl LibsqlPhpBatchStatement {
+    /// Reads the statement and converts its parameters.
+    ///
+    /// # Safety
+    ///
+    /// `sql` must be null or a null-terminated C string, and `names` and `values` must follow the
+    /// rules of `named_from_raw` and `values_from_raw`.
+    pub unsafe fn to_statement(&self) -> Result<BatchStatement, BatchStatementError> {
+        if self.sql.is_null() {
+            return Err(BatchStatementError::Sql);
+        }
+
+        let sql = unsafe { std::ffi::CStr::from_ptr(self.sql) }
+            .to_str()
+            .map_err(|_| BatchStatementError::Sql)?
+            .to_string();
+
+        let params = if self.names.is_null() {
+            unsafe { values_from_raw(self.values, self.values_len) }.map(Params::Positional)
+        } else {
+            unsafe { named_from_raw(self.names, self.values, self.values_len) }.map(Params::Named)
+        }
+        .map_err(BatchStatementError::Parameter)?;
+
+        Ok(BatchStatement { sql, params })
+    }
+}
+
+impl BatchStatement {
+    /// Runs the statement on `conn` and reads every row it returns.
+    ///
+    /// The rows affected and last insert rowid are measured around the statement rather than read from
+    /// the connection alone, which keeps reporting the last write: a statement that writes nothing reports
+    /// `0` rows affected and the rowid of the last insert before it.
+    ///
+    /// On a remote connection the measurement needs the statement to run on the same stream as the
+    /// `SELECT` around it, so `conn` must be inside a transaction.
+    ///
+    /// # Returns
+    ///
+    /// The rows of the statement with its rows affected and last insert rowid, or the error reported by libsql.
+    pub async fn run(
+        self,
+        conn: &libsql::Connection,
+        mode: ConnectionMode,
+    ) -> libsql::Result<BatchStepResult> {
+        let mut stmt = conn.prepare(&self.sql).await?;
+        let columns = column_info(&stmt);
+        let params = match self.params {
+            Params::Named(params) => Params::Named(resolve_named(&stmt, params)),
+            params => params,
+        };
+
+        // A replica delegates writes to the primary, so its local counters never see them. Statements
+        // that return no rows report the count of the side that ran them, and the others are reads.
+        if mode == ConnectionMode::RemoteReplica {
+            if columns.is_empty() {
+                let rows_affected = stmt.execute(params).await? as u64;
+                return Ok(BatchStepResult {
+                    rows_affected,
+                    last_insert_rowid: conn.last_insert_rowid(),
+                    result: QueryResult {
+                        columns,
+                        rows: Vec::new(),
+                    },
+                });
+            }
+
+            let rows = stmt.query(params).await?;
+            return Ok(BatchStepResult {
+                rows_affected: 0,
+                last_insert_rowid: conn.last_insert_rowid(),
+                result: collect_rows(columns, rows).await?,
+            });
+        }
+
+        let before = Counters::read(conn).await?;
+        let rows = stmt.query(params).await?;
+        let result = collect_rows(columns, rows).await?;
+        let after = Counters::read(conn).await?;
+
+        Ok(BatchStepResult {
+            rows_affected: if after.total_changes == before.total_changes {
+                0
+            } else {
+                after.changes as u64
+            },
+            last_insert_rowid: after.last_insert_rowid,
+            result,
+        })
+    }
+}
+
+/// The change counters SQLite keeps for a connection.
+struct Counters {
+    /// Rows changed since the connection was opened, including by triggers.
+    total_changes: i64,
+    /// Rows changed by the last statement that wrote any.
+    changes: i64,
+    /// The rowid of the last row inserted.
+    last_insert_rowid: i64,
+}
+
+impl Counters {
+    async fn read(conn: &libsql::Connection) -> libsql::Result<Self> {
+        let mut rows = conn
+            .query("SELECT total_changes(), changes(), last_insert_rowid()", ())
+            .await?;
+        let row = rows
+            .next()
+            .await?
+            .ok_or_else(|| libsql::Error::Misuse("Change counters returned no row".to_string()))?;
+
+        Ok(Self {
+            total_changes: row.get(0)?,
+            changes: row.get(1)?,
+            last_insert_rowid: row.get(2)?,
+        })
+    }
+}
+
+/// The result of one statement of a batch, serialized like a `QueryResult` with the
+/// `rows_affected` and `last_insert_rowid` of the statement added.
+#[derive(Serialize)]
+pub struct BatchStepResult {
+    /// The number of rows inserted, updated or deleted by the statement.
+    pub rows_affected: u64,
+    /// The rowid of the last row inserted on the connection.
+    pub last_insert_rowid: i64,
+    /// The columns and rows returned by the statement.
+    #[serde(flatten)]
+    pub result: QueryResult,
+}
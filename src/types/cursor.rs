@@ -0,0 +1,34 @@
+use std::ffi::CString;
+
+use crate::utils::query_result::ColumnInfo;
+
+/// Represents an open query whose rows are read one at a time.
+///
+/// The cursor owns the JSON of the row it returned last, so reading a row never
+/// requires the caller to free anything.
+pub struct RowCursor {
+    /// The rows returned by libsql.
+    pub rows: libsql::Rows,
+    /// The column metadata read from the statement before it ran.
+    pub column_info: Vec<ColumnInfo>,
+    /// The JSON of the row returned by the latest call to `libsql_php_rows_next`.
+    pub current: Option<CString>,
+    /// The JSON of the columns, once `libsql_php_rows_columns` has been called.
+    pub columns: Option<CString>,
+    /// `true` once every row has been read. SQLite runs a statement again when it is stepped
+    /// after its last row, so the rows are not read past that point.
+    pub done: bool,
+}
+
+impl RowCursor {
+    /// Wraps the rows returned by libsql, along with the column metadata of their statement.
+    pub fn new(rows: libsql::Rows, column_info: Vec<ColumnInfo>) -> Self {
+        Self {
+            rows,
+            column_info,
+            current: None,
+            columns: None,
+            done: false,
+        }
+    }
+}
@@ -0,0 +1,106 @@
+use std::num::NonZeroU32;
+
+use ring::pbkdf2;
+
+/// AES-256 in CBC mode, matching `LIBSQLPHP_CIPHER_AES256CBC` on the PHP side.
+///
+/// This is the only cipher libsql can configure for encryption at rest.
+pub const LIBSQL_PHP_CIPHER_AES256CBC: libc::c_int = 1;
+
+/// The key bytes are handed to the cipher as they are, matching `LIBSQLPHP_KEY_RAW` on the PHP side.
+pub const LIBSQL_PHP_KEY_RAW: libc::c_int = 1;
+/// The key is a string of hex digits decoded into the key bytes, matching `LIBSQLPHP_KEY_HEX` on the PHP side.
+pub const LIBSQL_PHP_KEY_HEX: libc::c_int = 2;
+/// The key is a passphrase stretched into 32 key bytes with 100 000 rounds of PBKDF2-HMAC-SHA256
+/// salted with `libsql-php-passphrase`, matching `LIBSQLPHP_KEY_PASSPHRASE` on the PHP side.
+pub const LIBSQL_PHP_KEY_PASSPHRASE: libc::c_int = 3;
+
+/// The salt used to derive keys from passphrases. It is fixed so the same passphrase
+/// always opens the same file.
+const PASSPHRASE_KDF_SALT: &[u8] = b"libsql-php-passphrase";
+/// The number of PBKDF2 rounds used to derive keys from passphrases.
+const PASSPHRASE_KDF_ITERATIONS: u32 = 100_000;
+
+/// Represents the encryption settings of a database passed from C code.
+///
+/// This struct is marked with #[repr(C)] to ensure its memory layout is compatible
+/// with C code. The key is given with its length, so raw keys may contain null bytes.
+#[repr(C)]
+pub struct LibsqlPhpEncryption {
+    /// One of the `LIBSQL_PHP_CIPHER_*` ciphers.
+    pub cipher: libc::c_int,
+    /// One of the `LIBSQL_PHP_KEY_*` formats `key` is given in.
+    pub key_format: libc::c_int,
+    /// A raw pointer to the key bytes.
+    pub key: *const u8,
+    /// The number of bytes pointed to by `key`.
+    pub key_len: usize,
+}
+
+impl LibsqlPhpEncryption {
+    /// Converts the C settings into a `libsql::EncryptionConfig`.
+    ///
+    /// # Safety
+    ///
+    /// `key` must point to `key_len` readable bytes.
+    pub unsafe fn to_config(&self) -> Result<libsql::EncryptionConfig, String> {
+        let cipher = match self.cipher {
+            LIBSQL_PHP_CIPHER_AES256CBC => libsql::Cipher::Aes256Cbc,
+            cipher => return Err(format!("Unsupported encryption cipher: {cipher}")),
+        };
+
+        let key = unsafe { self.key_bytes() }?;
+
+        Ok(libsql::EncryptionConfig::new(cipher, key.into()))
+    }
+
+    /// Returns the bytes handed to the cipher, decoded or derived from the key as its format requires.
+    ///
+    /// # Safety
+    ///
+    /// `key` must point to `key_len` readable bytes.
+    pub unsafe fn key_bytes(&self) -> Result<Vec<u8>, String> {
+        if self.key.is_null() || self.key_len == 0 {
+            return Err("Encryption key is empty".to_string());
+        }
+
+        let key = unsafe { std::slice::from_raw_parts(self.key, self.key_len) };
+
+        match self.key_format {
+            LIBSQL_PHP_KEY_RAW => Ok(key.to_vec()),
+            LIBSQL_PHP_KEY_HEX => decode_hex(key),
+            LIBSQL_PHP_KEY_PASSPHRASE => {
+                let mut derived = vec![0u8; 32];
+                pbkdf2::derive(
+                    pbkdf2::PBKDF2_HMAC_SHA256,
+                    NonZeroU32::new(PASSPHRASE_KDF_ITERATIONS).unwrap_or(NonZeroU32::MIN),
+                    PASSPHRASE_KDF_SALT,
+                    key,
+                    &mut derived,
+                );
+                Ok(derived)
+            }
+            format => Err(format!("Unknown encryption key format: {format}")),
+        }
+    }
+}
+
+fn decode_hex(hex: &[u8]) -> Result<Vec<u8>, String> {
+    let digit = |c: u8| match c {
+        b'0'..=b'9' => Some(c - b'0'),
+        b'a'..=b'f' => Some(c - b'a' + 10),
+        b'A'..=b'F' => Some(c - b'A' + 10),
+        _ => None,
+    };
+
+    if !hex.len().is_multiple_of(2) {
+        return Err("Hex encryption key has an odd number of digits".to_string());
+    }
+
+    hex.chunks(2)
+        .map(|pair| match (digit(pair[0]), digit(pair[1])) {
+            (Some(high), Some(low)) => Ok(high << 4 | low),
+            _ => Err("Hex encryption key contains a non-hex character".to_string()),
+        })
+        .collect()
+}
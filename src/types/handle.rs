@@ -0,0 +1,73 @@
+use std::sync::{Arc, Weak};
+
+use super::{pool::PoolSlots, replica::ReplicaState, retry::LibsqlPhpRetryPolicy};
+
+/// The kind of database a `LibsqlHandle` is connected to.
+///
+/// The values are reported by `libsql_php_connection_mode`.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+#[repr(C)]
+pub enum ConnectionMode {
+    /// A database file on the local disk.
+    Local = 1,
+    /// A database that only lives in memory.
+    Memory = 2,
+    /// A remote database reached over HTTP, without a local copy.
+    Remote = 3,
+    /// A local copy of a remote database, kept up to date by syncing.
+    RemoteReplica = 4,
+}
+
+/// Represents an open database that connections can be made to.
+///
+/// Returned by `libsql_php_db_open` and released with `libsql_php_db_close`.
+/// Connections share the database, so it stays open until the last of them is closed.
+pub struct LibsqlDatabase {
+    /// The database shared by every connection made to it.
+    pub db: Arc<libsql::Database>,
+    /// How the database was opened.
+    pub mode: ConnectionMode,
+}
+
+/// Represents an open database together with its connection.
+///
+/// Freeing the handle with `libsql_php_close` closes its connection, and the database too
+/// once no other connection or `LibsqlDatabase` holds it. C code only ever sees it as an opaque pointer.
+pub struct LibsqlHandle {
+    /// The database the connection was opened on, shared with other connections to it.
+    pub db: Arc<libsql::Database>,
+    /// The connection used by every call made with this handle.
+    pub conn: libsql::Connection,
+    /// How the database was opened.
+    pub mode: ConnectionMode,
+    /// How a remote replica connection has been syncing, or `None` for other connections.
+    pub replica: Option<Arc<ReplicaState>>,
+    /// How statements that failed with a transient error are retried.
+    pub retry: LibsqlPhpRetryPolicy,
+    /// The pool the connection was checked out of, or `None` if it does not belong to one.
+    pub pool: Option<Weak<PoolSlots>>,
+}
+
+impl Drop for LibsqlHandle {
+    fn drop(&mut self) {
+        // Stop the background sync before the database is released, so a sync in progress does
+        // not hold the last reference to it and drop it on a runtime thread.
+        if let Some(replica) = &self.replica {
+            replica.stop_background_sync();
+        }
+    }
+}
+
+impl LibsqlHandle {
+    /// Opens a new connection to a database.
+    pub fn connect(database: &LibsqlDatabase) -> libsql::Result<Self> {
+        Ok(Self {
+            conn: database.db.connect()?,
+            db: Arc::clone(&database.db),
+            mode: database.mode,
+            replica: None,
+            retry: LibsqlPhpRetryPolicy::default(),
+            pool: None,
+        })
+    }
+}
@@ -1 +1,10 @@
-pub mod db_pair;
+pub mod batch;
+pub mod cursor;
+pub mod encryption;
+pub mod handle;
+pub mod pool;
+pub mod replica;
+pub mod retry;
+pub mod statement;
+pub mod transaction;
+pub mod value;
@@ -0,0 +1,222 @@
+use std::{
+    collections::HashSet,
+    sync::{Arc, Condvar, Mutex, MutexGuard},
+    time::{Duration, Instant},
+};
+
+use super::{
+    handle::{ConnectionMode, LibsqlDatabase, LibsqlHandle},
+    retry::LibsqlPhpRetryPolicy,
+};
+use crate::utils::runtime::runtime;
+
+/// A bounded pool of connections to one database, kept alive between PHP requests.
+///
+/// Connections are opened lazily up to `max_size`. A connection that is checked in waits in the
+/// pool until it is checked out again or has been idle longer than the idle timeout, and is
+/// health checked before it is handed out again.
+pub struct ConnectionPool {
+    database: LibsqlDatabase,
+    max_size: usize,
+    idle_timeout: Option<Duration>,
+    slots: Arc<PoolSlots>,
+}
+
+/// The connections a pool keeps track of, shared with the handles it checked out so that closing
+/// one of them gives its slot back.
+pub struct PoolSlots {
+    state: Mutex<PoolState>,
+    available: Condvar,
+}
+
+struct PoolState {
+    idle: Vec<IdleConnection>,
+    /// Addresses of the connections that are checked out, so only they can be checked in.
+    checked_out: HashSet<usize>,
+    /// Connections checked out or being opened or health checked.
+    in_use: usize,
+}
+
+struct IdleConnection {
+    handle: Box<LibsqlHandle>,
+    since: Instant,
+}
+
+/// Why a connection could not be checked out of a pool.
+pub enum CheckoutError {
+    /// Every connection stayed in use until the timeout.
+    Exhausted,
+    /// Opening a new connection failed.
+    Connect(libsql::Error),
+}
+
+impl ConnectionPool {
+    /// Creates an empty pool for a database. An `idle_timeout` of `None` keeps idle connections forever.
+    pub fn new(database: &LibsqlDatabase, max_size: usize, idle_timeout: Option<Duration>) -> Self {
+        Self {
+            database: LibsqlDatabase {
+                db: Arc::clone(&database.db),
+                mode: database.mode,
+            },
+            max_size,
+            idle_timeout,
+            slots: Arc::new(PoolSlots {
+                state: Mutex::new(PoolState {
+                    idle: Vec::new(),
+                    checked_out: HashSet::new(),
+                    in_use: 0,
+                }),
+                available: Condvar::new(),
+            }),
+        }
+    }
+
+    /// Takes a healthy connection from the pool, opening one if the pool is below its max size,
+    /// or waits up to `timeout` for another caller to check one in.
+    pub fn checkout(&self, timeout: Duration) -> Result<Box<LibsqlHandle>, CheckoutError> {
+        let deadline = Instant::now() + timeout;
+
+        loop {
+            let mut state = self.slots.lock();
+            self.prune_expired(&mut state);
+
+            if let Some(idle) = state.idle.pop() {
+                state.in_use += 1;
+                drop(state);
+
+                if is_healthy(&idle.handle) {
+                    return Ok(self.hand_out(idle.handle));
+                }
+
+                self.slots.release_slot();
+                continue;
+            }
+
+            if state.in_use < self.max_size {
+                state.in_use += 1;
+                drop(state);
+
+                return match LibsqlHandle::connect(&self.database) {
+                    Ok(handle) => Ok(self.hand_out(Box::new(handle))),
+                    Err(e) => {
+                        self.slots.release_slot();
+                        Err(CheckoutError::Connect(e))
+                    }
+                };
+            }
+
+            let now = Instant::now();
+            if now >= deadline {
+                return Err(CheckoutError::Exhausted);
+            }
+
+            drop(
+                self.slots
+                    .available
+                    .wait_timeout(state, deadline - now)
+                    .unwrap_or_else(|e| e.into_inner()),
+            );
+        }
+    }
+
+    /// Returns `true` if the connection was checked out of this pool and not checked in yet.
+    pub fn owns(&self, handle: *const LibsqlHandle) -> bool {
+        self.slots.lock().checked_out.contains(&(handle as usize))
+    }
+
+    /// Puts a checked out connection back into the pool, resetting its retry policy and busy timeout.
+    ///
+    /// A connection that is still inside a transaction, or whose settings cannot be reset, is
+    /// closed instead, so the next caller never inherits it.
+    pub fn checkin(&self, mut handle: Box<LibsqlHandle>) {
+        let reusable = handle.conn.is_autocommit() && reset_settings(&mut handle);
+
+        let mut state = self.slots.lock();
+        state
+            .checked_out
+            .remove(&(&*handle as *const LibsqlHandle as usize));
+        state.in_use = state.in_use.saturating_sub(1);
+
+        if reusable {
+            state.idle.push(IdleConnection {
+                handle,
+                since: Instant::now(),
+            });
+        }
+
+        drop(state);
+        self.slots.available.notify_one();
+    }
+
+    /// Returns the number of idle connections and of connections checked out.
+    pub fn stats(&self) -> (usize, usize) {
+        let mut state = self.slots.lock();
+        self.prune_expired(&mut state);
+        (state.idle.len(), state.in_use)
+    }
+
+    fn hand_out(&self, mut handle: Box<LibsqlHandle>) -> Box<LibsqlHandle> {
+        handle.pool = Some(Arc::downgrade(&self.slots));
+        self.slots
+            .lock()
+            .checked_out
+            .insert(&*handle as *const LibsqlHandle as usize);
+        handle
+    }
+
+    fn prune_expired(&self, state: &mut PoolState) {
+        if let Some(idle_timeout) = self.idle_timeout {
+            state
+                .idle
+                .retain(|idle| idle.since.elapsed() < idle_timeout);
+        }
+    }
+}
+
+impl PoolSlots {
+    /// Gives back the slot of a checked out connection that is being closed instead of checked in.
+    pub fn release_closed(&self, handle: *const LibsqlHandle) {
+        let mut state = self.lock();
+        if state.checked_out.remove(&(handle as usize)) {
+            state.in_use = state.in_use.saturating_sub(1);
+            drop(state);
+            self.available.notify_one();
+        }
+    }
+
+    fn release_slot(&self) {
+        let mut state = self.lock();
+        state.in_use = state.in_use.saturating_sub(1);
+        drop(state);
+        self.available.notify_one();
+    }
+
+    fn lock(&self) -> MutexGuard<'_, PoolState> {
+        self.state.lock().unwrap_or_else(|e| e.into_inner())
+    }
+}
+
+fn is_healthy(handle: &LibsqlHandle) -> bool {
+    runtime()
+        .block_on(async {
+            let mut rows = handle.conn.query("SELECT 1", ()).await?;
+            rows.next().await.map(|_| ())
+        })
+        .is_ok()
+}
+
+/// Puts back the settings `libsql_php_set_retry_policy` and `libsql_php_busy_timeout` change.
+fn reset_settings(handle: &mut LibsqlHandle) -> bool {
+    handle.retry = LibsqlPhpRetryPolicy::default();
+
+    if handle.mode != ConnectionMode::Local {
+        return true;
+    }
+
+    runtime()
+        .block_on(async {
+            let mut rows = handle.conn.query("PRAGMA busy_timeout = 0", ()).await?;
+            rows.next().await.map(|_| ())
+        })
+        .is_ok()
+}
@@ -0,0 +1,366 @@
+use std::{
+    ffi::CString,
+    sync::{
+        atomic::{AtomicBool, Ordering},
+        Arc, Mutex, MutexGuard,
+    },
+    time::{Duration, Instant, SystemTime},
+};
+
+use tokio::task::JoinHandle;
+
+use crate::utils::{
+    errors::{ErrorCode, LIBSQL_PHP_OK},
+    runtime::runtime,
+};
+
+/// Called after each background sync with the `user_data` it was registered with, the status of
+/// the sync (`0` or an error code) and the frame number the replica reached, or `-1`.
+///
+/// It runs on a thread of the shared runtime, not on the thread that started the background sync,
+/// and is never called once the background sync has been stopped. It must not stop or restart the
+/// background sync itself.
+pub type LibsqlPhpSyncCallback =
+    Option<unsafe extern "C" fn(user_data: *mut libc::c_void, status: libc::c_int, frame_no: i64)>;
+
+/// The sync status of a remote replica connection, filled in by `libsql_php_replica_status`.
+///
+/// Fields holding `-1` are unknown.
+#[repr(C)]
+pub struct LibsqlPhpReplicaStatus {
+    /// `1` if the last attempt to sync reached the primary, `0` if it failed or none was made yet.
+    pub online: libc::c_int,
+    /// `1` if the connection reads the local replica file read-only because the primary could not
+    /// be reached when it was opened and no sync made with `libsql_php_sync` or
+    /// `libsql_php_sync_until` has succeeded since, `0` otherwise.
+    pub read_only: libc::c_int,
+    /// `1` if the replica may be missing writes made on the primary, `0` if it is up to date.
+    pub stale: libc::c_int,
+    /// The frame number the replica has committed locally.
+    pub frame_no: i64,
+    /// How many frames the replica is behind the last frame number known for the primary.
+    pub frames_behind: i64,
+    /// The number of seconds since the replica last synced with the primary.
+    pub seconds_since_sync: i64,
+}
+
+/// The state of the background sync of a remote replica connection, filled in by
+/// `libsql_php_background_sync_status`.
+#[repr(C)]
+pub struct LibsqlPhpBackgroundSyncStatus {
+    /// `1` if the background sync is running, `0` otherwise.
+    pub running: libc::c_int,
+    /// When the replica last synced with the primary, in milliseconds since the Unix epoch, or `-1`.
+    pub last_sync_unix_ms: i64,
+    /// The error of the most recent failed sync, or a null pointer if none failed. It is owned by
+    /// the connection and stays valid until the next status call on it or until it is closed.
+    pub last_error: *const libc::c_char,
+    /// How many syncs failed in a row since the last one that succeeded.
+    pub consecutive_failures: u32,
+}
+
+/// Tracks how a remote replica connection has been syncing with its primary.
+pub struct ReplicaState {
+    read_only: AtomicBool,
+    /// Shared with the background sync task, which records its syncs without holding the state.
+    progress: Arc<Mutex<SyncProgress>>,
+    background: Mutex<Option<BackgroundSync>>,
+    /// The last error handed out by `background_status`, kept alive for the caller.
+    reported_error: Mutex<Option<CString>>,
+}
+
+#[derive(Default)]
+struct SyncProgress {
+    online: bool,
+    last_sync: Option<Instant>,
+    last_sync_at: Option<SystemTime>,
+    last_error: Option<String>,
+    consecutive_failures: u32,
+    /// The highest frame number the primary is known to have reached.
+    primary_frame_no: Option<u64>,
+}
+
+impl SyncProgress {
+    fn record(&mut self, result: &Result<Option<u64>, ReplicaSyncError>) {
+        self.online = result.is_ok();
+        match result {
+            Ok(frame_no) => {
+                self.last_sync = Some(Instant::now());
+                self.last_sync_at = Some(SystemTime::now());
+                self.consecutive_failures = 0;
+                self.primary_frame_no = self.primary_frame_no.max(*frame_no);
+            }
+            Err(e) => {
+                self.last_error = Some(e.to_string());
+                self.consecutive_failures = self.consecutive_failures.saturating_add(1);
+            }
+        }
+    }
+}
+
+/// A running background sync task.
+struct BackgroundSync {
+    task: JoinHandle<()>,
+    /// `true` until the background sync is stopped. The task only calls the callback while holding
+    /// this lock with the flag set, so no callback is running or starts once `stop` has cleared it.
+    delivering: Arc<Mutex<bool>>,
+}
+
+impl BackgroundSync {
+    /// Stops the task, waiting for a callback that is being called to return.
+    ///
+    /// Outside the runtime threads it also waits for the task to end, so the database the task
+    /// upgraded for a sync in progress is released before this returns.
+    fn stop(self) {
+        *lock(&self.delivering) = false;
+        self.task.abort();
+        if tokio::runtime::Handle::try_current().is_err() {
+            let _ = runtime().block_on(self.task);
+        }
+    }
+}
+
+/// A `LibsqlPhpSyncCallback` with its user data, moved to the background sync task.
+struct SyncCallback {
+    callback: LibsqlPhpSyncCallback,
+    user_data: *mut libc::c_void,
+}
+
+// SAFETY: The caller registering the callback guarantees it and its user data can be used from
+// the runtime threads.
+unsafe impl Send for SyncCallback {}
+
+impl SyncCallback {
+    fn call(&self, result: &Result<Option<u64>, ReplicaSyncError>) {
+        if let Some(callback) = self.callback {
+            let (status, frame_no) = match result {
+                Ok(frame_no) => (
+                    LIBSQL_PHP_OK,
+                    frame_no.map_or(-1, |frame_no| frame_no as i64),
+                ),
+                Err(e) => (e.code() as libc::c_int, -1),
+            };
+            unsafe { callback(self.user_data, status, frame_no) };
+        }
+    }
+}
+
+/// Why a replica could not be synced with its primary.
+pub enum ReplicaSyncError {
+    /// The primary did not answer before the timeout.
+    Timeout,
+    /// Syncing failed.
+    Sync(libsql::Error),
+}
+
+impl ReplicaSyncError {
+    /// The error code reported for the error.
+    pub fn code(&self) -> ErrorCode {
+        match self {
+            Self::Timeout => ErrorCode::SyncTimeout,
+            Self::Sync(_) => ErrorCode::SyncFailed,
+        }
+    }
+}
+
+impl std::fmt::Display for ReplicaSyncError {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            Self::Timeout => f.write_str(crate::ERR_PRIMARY_TIMEOUT),
+            Self::Sync(e) => e.fmt(f),
+        }
+    }
+}
+
+impl ReplicaState {
+    /// Creates the state of a replica that has not synced yet. `read_only` is set when its
+    /// connection was opened on the local replica file because the primary could not be reached.
+    pub fn new(read_only: bool) -> Self {
+        Self {
+            read_only: AtomicBool::new(read_only),
+            progress: Arc::new(Mutex::new(SyncProgress::default())),
+            background: Mutex::new(None),
+            reported_error: Mutex::new(None),
+        }
+    }
+
+    /// Syncs the replica with its primary, giving up after `timeout` if there is one, and records
+    /// the outcome.
+    pub async fn sync(
+        &self,
+        db: &libsql::Database,
+        timeout: Option<Duration>,
+    ) -> Result<Option<u64>, ReplicaSyncError> {
+        let result = sync_with_timeout(db, timeout).await;
+        self.record_sync(&result);
+        result
+    }
+
+    /// Records the outcome of an attempt to sync with the primary.
+    pub fn record_sync(&self, result: &Result<Option<u64>, ReplicaSyncError>) {
+        self.progress().record(result);
+    }
+
+    /// Returns `true` while the connection reads the local replica file read-only.
+    pub fn is_read_only(&self) -> bool {
+        self.read_only.load(Ordering::Acquire)
+    }
+
+    /// Records that the connection was switched from the local replica file to the replica.
+    pub fn clear_read_only(&self) {
+        self.read_only.store(false, Ordering::Release);
+    }
+
+    /// Records that the primary has reached at least `frame_no`.
+    pub fn record_primary_frame(&self, frame_no: u64) {
+        let mut progress = self.progress();
+        progress.primary_frame_no = progress.primary_frame_no.max(Some(frame_no));
+    }
+
+    /// Reports the status of a replica that has committed `frame_no` locally.
+    pub fn status(&self, frame_no: Option<u64>) -> LibsqlPhpReplicaStatus {
+        let progress = self.progress();
+
+        let frames_behind = match (progress.primary_frame_no, frame_no) {
+            (Some(primary), Some(replica)) => Some(primary.saturating_sub(replica)),
+            (Some(primary), None) => Some(primary + 1),
+            (None, _) => None,
+        };
+
+        let stale = !progress.online || progress.last_sync.is_none() || frames_behind != Some(0);
+
+        LibsqlPhpReplicaStatus {
+            online: progress.online.into(),
+            read_only: self.is_read_only().into(),
+            stale: stale.into(),
+            frame_no: frame_no.map_or(-1, |frame_no| frame_no as i64),
+            frames_behind: frames_behind.map_or(-1, |frames| frames as i64),
+            seconds_since_sync: progress
+                .last_sync
+                .map_or(-1, |last_sync| last_sync.elapsed().as_secs() as i64),
+        }
+    }
+
+    /// Reports the state of the background sync.
+    pub fn background_status(&self) -> LibsqlPhpBackgroundSyncStatus {
+        let running = lock(&self.background)
+            .as_ref()
+            .is_some_and(|background| !background.task.is_finished());
+
+        let progress = self.progress();
+
+        let mut reported_error = lock(&self.reported_error);
+        *reported_error = progress
+            .last_error
+            .as_ref()
+            .map(|e| CString::new(e.replace('\0', "")).unwrap_or_default());
+
+        LibsqlPhpBackgroundSyncStatus {
+            running: running.into(),
+            last_sync_unix_ms: progress
+                .last_sync_at
+                .and_then(|at| at.duration_since(SystemTime::UNIX_EPOCH).ok())
+                .map_or(-1, |since| since.as_millis() as i64),
+            last_error: reported_error
+                .as_ref()
+                .map_or(std::ptr::null(), |e| e.as_ptr()),
+            consecutive_failures: progress.consecutive_failures,
+        }
+    }
+
+    /// Starts syncing the replica every `interval` on the shared runtime, giving each attempt up
+    /// to `timeout`, and calls `callback` after each sync. A background sync that was already
+    /// running is replaced. The task stops once the database has been dropped.
+    ///
+    /// The task only holds the database while a sync is in progress, and never holds the state, so
+    /// neither is dropped on a runtime thread when the connection is closed.
+    ///
+    /// # Safety
+    ///
+    /// `callback` and `user_data` must be usable from the runtime threads until the background
+    /// sync is stopped.
+    pub unsafe fn start_background_sync(
+        self: &Arc<Self>,
+        db: &Arc<libsql::Database>,
+        interval: Duration,
+        timeout: Duration,
+        callback: LibsqlPhpSyncCallback,
+        user_data: *mut libc::c_void,
+    ) {
+        let db = Arc::downgrade(db);
+        let progress = Arc::clone(&self.progress);
+        let callback = SyncCallback {
+            callback,
+            user_data,
+        };
+        let delivering = Arc::new(Mutex::new(true));
+        let gate = Arc::clone(&delivering);
+
+        let task = runtime().spawn(async move {
+            loop {
+                tokio::time::sleep(interval).await;
+
+                let Some(db) = db.upgrade() else {
+                    return;
+                };
+
+                let result = sync_with_timeout(&db, Some(timeout)).await;
+                drop(db);
+                lock(&progress).record(&result);
+
+                let delivering = lock(&gate);
+                if !*delivering {
+                    return;
+                }
+                callback.call(&result);
+            }
+        });
+
+        let background = BackgroundSync { task, delivering };
+        let previous = lock(&self.background).replace(background);
+        if let Some(previous) = previous {
+            previous.stop();
+        }
+    }
+
+    /// Stops the background sync, waiting for a callback that is being called to return. Returns
+    /// `false` if it was not running.
+    pub fn stop_background_sync(&self) -> bool {
+        let background = lock(&self.background).take();
+        match background {
+            Some(background) => {
+                background.stop();
+                true
+            }
+            None => false,
+        }
+    }
+
+    fn progress(&self) -> MutexGuard<'_, SyncProgress> {
+        lock(&self.progress)
+    }
+}
+
+impl Drop for ReplicaState {
+    fn drop(&mut self) {
+        self.stop_background_sync();
+    }
+}
+
+fn lock<T>(mutex: &Mutex<T>) -> MutexGuard<'_, T> {
+    mutex.lock().unwrap_or_else(|e| e.into_inner())
+}
+
+/// Syncs a replica with its primary, giving up after `timeout` if there is one.
+pub async fn sync_with_timeout(
+    db: &libsql::Database,
+    timeout: Option<Duration>,
+) -> Result<Option<u64>, ReplicaSyncError> {
+    match timeout {
+        Some(timeout) => match tokio::time::timeout(timeout, db.sync()).await {
+            Ok(result) => result.map_err(ReplicaSyncError::Sync),
+            Err(_) => Err(ReplicaSyncError::Timeout),
+        },
+        None => db.sync().await.map_err(ReplicaSyncError::Sync),
+    }
+}
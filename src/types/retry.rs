@@ -0,0 +1,149 @@
+use std::time::Duration;
+
+use ring::rand::{SecureRandom, SystemRandom};
+
+use crate::{ERR_RETRY_ATTEMPTS, ERR_RETRY_BACKOFF, ERR_RETRY_CLASSES, ERR_RETRY_JITTER};
+
+/// Retries statements that failed with `SQLITE_BUSY`, matching `LIBSQLPHP_RETRY_BUSY` on the PHP side.
+pub const LIBSQL_PHP_RETRY_BUSY: libc::c_int = 0x1;
+/// Retries statements that failed with `SQLITE_LOCKED`, matching `LIBSQLPHP_RETRY_LOCKED` on the PHP side.
+pub const LIBSQL_PHP_RETRY_LOCKED: libc::c_int = 0x2;
+/// Retries statements that failed because the connection to the remote database could not be opened,
+/// matching `LIBSQLPHP_RETRY_REMOTE` on the PHP side. Only failures raised before the request was sent
+/// are retried, so a write that may have reached the database is never sent twice.
+pub const LIBSQL_PHP_RETRY_REMOTE: libc::c_int = 0x4;
+
+const KNOWN_CLASSES: libc::c_int =
+    LIBSQL_PHP_RETRY_BUSY | LIBSQL_PHP_RETRY_LOCKED | LIBSQL_PHP_RETRY_REMOTE;
+
+const SQLITE_BUSY: i32 = 5;
+const SQLITE_LOCKED: i32 = 6;
+
+/// How the HTTP client describes a request that failed because no connection could be opened.
+const HTTP_CONNECT_ERROR: &str = "error trying to connect";
+
+/// How a connection retries statements that failed with a transient error, set with
+/// `libsql_php_set_retry_policy`.
+///
+/// The delay before retry `n` is `initial_backoff_ms * 2^(n - 1)`, capped at `max_backoff_ms`,
+/// then shortened by a random share of up to `jitter_percent` percent.
+#[derive(Debug, Clone, Copy)]
+#[repr(C)]
+pub struct LibsqlPhpRetryPolicy {
+    /// How many times a statement is run at most, counting the first attempt. `1` disables retries.
+    pub max_attempts: u32,
+    /// The delay before the first retry, in milliseconds.
+    pub initial_backoff_ms: u32,
+    /// The longest delay between two attempts, in milliseconds.
+    pub max_backoff_ms: u32,
+    /// How much of each delay, in percent from `0` to `100`, is randomly skipped.
+    pub jitter_percent: u32,
+    /// The `LIBSQL_PHP_RETRY_*` error classes that are retried.
+    pub retry_on: libc::c_int,
+}
+
+impl Default for LibsqlPhpRetryPolicy {
+    /// A policy that does not retry.
+    fn default() -> Self {
+        Self {
+            max_attempts: 1,
+            initial_backoff_ms: 50,
+            max_backoff_ms: 2000,
+            jitter_percent: 20,
+            retry_on: LIBSQL_PHP_RETRY_BUSY | LIBSQL_PHP_RETRY_LOCKED,
+        }
+    }
+}
+
+impl LibsqlPhpRetryPolicy {
+    /// Checks that the policy can be used.
+    ///
+    /// # Returns
+    ///
+    /// The reason the policy was rejected, if it was.
+    pub fn validate(&self) -> Result<(), &'static str> {
+        if self.max_attempts == 0 {
+            return Err(ERR_RETRY_ATTEMPTS);
+        }
+
+        if self.max_backoff_ms < self.initial_backoff_ms {
+            return Err(ERR_RETRY_BACKOFF);
+        }
+
+        if self.jitter_percent > 100 {
+            return Err(ERR_RETRY_JITTER);
+        }
+
+        if self.retry_on & !KNOWN_CLASSES != 0 {
+            return Err(ERR_RETRY_CLASSES);
+        }
+
+        Ok(())
+    }
+
+    /// Returns whether a statement that failed with `err` on attempt `attempt` is run again.
+    ///
+    /// A busy or locked statement is not retried when `in_transaction` is set, because running one
+    /// statement of an open transaction again does not replay the ones before it.
+    pub fn retries(&self, err: &libsql::Error, attempt: u32, in_transaction: bool) -> bool {
+        let class = match error_class(err) {
+            LIBSQL_PHP_RETRY_REMOTE => LIBSQL_PHP_RETRY_REMOTE,
+            _ if in_transaction => 0,
+            class => class,
+        };
+
+        attempt < self.max_attempts && self.retry_on & class != 0
+    }
+
+    /// Returns how long to wait after attempt `attempt` failed.
+    pub fn backoff(&self, attempt: u32) -> Duration {
+        let exponent = attempt.saturating_sub(1).min(31);
+        let backoff = (self.initial_backoff_ms as u64) << exponent;
+        let backoff = backoff.min(self.max_backoff_ms as u64);
+
+        let skipped = backoff as u128 * self.jitter_percent as u128 * random_u32() as u128
+            / (100 * u32::MAX as u128);
+
+        Duration::from_millis(backoff - skipped as u64)
+    }
+}
+
+/// Classifies an error into the `LIBSQL_PHP_RETRY_*` class it can be retried under, or `0`.
+fn error_class(err: &libsql::Error) -> libc::c_int {
+    match err {
+        libsql::Error::SqliteFailure(code, _) | libsql::Error::RemoteSqliteFailure(_, code, _) => {
+            sqlite_class(*code)
+        }
+        // Statements sent over HTTP report their SQLite error by name. A failed request is only
+        // known not to have reached the database when the connection could not be opened.
+        libsql::Error::Hrana(e) => {
+            let message = e.to_string();
+            if message.starts_with("http error") && message.contains(HTTP_CONNECT_ERROR) {
+                LIBSQL_PHP_RETRY_REMOTE
+            } else if message.contains("SQLITE_BUSY") {
+                LIBSQL_PHP_RETRY_BUSY
+            } else if message.contains("SQLITE_LOCKED") {
+                LIBSQL_PHP_RETRY_LOCKED
+            } else {
+                0
+            }
+        }
+        _ => 0,
+    }
+}
+
+fn sqlite_class(extended_code: i32) -> libc::c_int {
+    match extended_code & 0xff {
+        SQLITE_BUSY => LIBSQL_PHP_RETRY_BUSY,
+        SQLITE_LOCKED => LIBSQL_PHP_RETRY_LOCKED,
+        _ => 0,
+    }
+}
+
+fn random_u32() -> u32 {
+    let mut bytes = [0u8; 4];
+    match SystemRandom::new().fill(&mut bytes) {
+        Ok(()) => u32::from_ne_bytes(bytes),
+        Err(_) => 0,
+    }
+}
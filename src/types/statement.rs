@@ -0,0 +1,108 @@
+use std::ffi::CString;
+
+use libsql::params::Params;
+
+use crate::utils::params::resolve_name;
+
+/// Represents a statement prepared on a connection together with the values bound to it.
+///
+/// The statement is parsed once by `libsql_php_prepare` and can be executed many times,
+/// binding new values between executions.
+pub struct PreparedStatement {
+    /// The statement prepared by libsql.
+    pub stmt: libsql::Statement,
+    /// Values bound by position, where index `0` is parameter `1`.
+    positional: Vec<Option<libsql::Value>>,
+    /// Values bound by name, with the prefix used in the statement.
+    named: Vec<(String, libsql::Value)>,
+    /// The name of each parameter as a C string, where index `0` is parameter `1`.
+    parameter_names: Vec<Option<CString>>,
+}
+
+impl PreparedStatement {
+    /// Wraps a statement prepared by libsql.
+    pub fn new(stmt: libsql::Statement) -> Self {
+        let parameter_names = (1..=stmt.parameter_count() as i32)
+            .map(|idx| {
+                stmt.parameter_name(idx)
+                    .and_then(|name| CString::new(name).ok())
+            })
+            .collect();
+
+        Self {
+            stmt,
+            positional: Vec::new(),
+            named: Vec::new(),
+            parameter_names,
+        }
+    }
+
+    /// Binds a value to the parameter at the 1-based `index`.
+    pub fn bind(&mut self, index: usize, value: libsql::Value) -> Result<(), String> {
+        if index == 0 {
+            return Err("Parameter index starts at 1".to_string());
+        }
+
+        if self.positional.len() < index {
+            self.positional.resize(index, None);
+        }
+        self.positional[index - 1] = Some(value);
+
+        Ok(())
+    }
+
+    /// Binds a value to the parameter called `name`, with or without its `:`, `@` or `$` prefix.
+    pub fn bind_named(&mut self, name: String, value: libsql::Value) {
+        let name = resolve_name(&self.stmt, name);
+
+        match self.named.iter_mut().find(|(bound, _)| *bound == name) {
+            Some(bound) => bound.1 = value,
+            None => self.named.push((name, value)),
+        }
+    }
+
+    /// Forgets every bound value.
+    pub fn clear_bindings(&mut self) {
+        self.positional.clear();
+        self.named.clear();
+    }
+
+    /// Returns the name of the parameter at the 1-based `index`, if it has one.
+    pub fn parameter_name(&self, index: usize) -> Option<&CString> {
+        index
+            .checked_sub(1)
+            .and_then(|idx| self.parameter_names.get(idx))
+            .and_then(Option::as_ref)
+    }
+
+    /// Builds the parameters for the next execution from the bound values.
+    ///
+    /// Every parameter left unbound is sent as NULL, like SQLite does for unbound parameters, so no
+    /// value bound for a previous execution is reused.
+    pub fn params(&self) -> Result<Params, String> {
+        let count = self.stmt.parameter_count();
+
+        match (self.positional.is_empty(), self.named.is_empty()) {
+            (true, true) if count == 0 => Ok(Params::None),
+            (_, true) => Ok(Params::Positional(
+                (0..count.max(self.positional.len()))
+                    .map(|idx| self.positional.get(idx).cloned().flatten())
+                    .map(|value| value.unwrap_or(libsql::Value::Null))
+                    .collect(),
+            )),
+            (true, false) => Ok(Params::Positional(
+                (1..=count as i32)
+                    .map(|idx| {
+                        self.stmt
+                            .parameter_name(idx)
+                            .and_then(|name| self.named.iter().find(|(bound, _)| bound == name))
+                            .map_or(libsql::Value::Null, |(_, value)| value.clone())
+                    })
+                    .collect(),
+            )),
+            (false, false) => {
+                Err("Positional and named parameters cannot be bound together".to_string())
+            }
+        }
+    }
+}
@@ -0,0 +1,150 @@
+use crate::utils::runtime::runtime;
+
+/// Represents a transaction started by `libsql_php_transaction`.
+///
+/// The transaction is consumed by the first commit or rollback. The handle stays valid afterwards
+/// so that later calls on it report an error, and is released by `libsql_php_transaction_free`.
+///
+/// The handle also keeps the savepoints opened inside the transaction, innermost last.
+pub struct TransactionHandle {
+    state: TransactionState,
+    savepoints: Vec<String>,
+    /// How many savepoint names were generated, used to make the next one unique.
+    generated_savepoints: u64,
+}
+
+enum TransactionState {
+    Active(libsql::Transaction),
+    Committed,
+    RolledBack,
+}
+
+impl TransactionHandle {
+    /// Wraps a transaction started by libsql.
+    pub fn new(trx: libsql::Transaction) -> Self {
+        Self {
+            state: TransactionState::Active(trx),
+            savepoints: Vec::new(),
+            generated_savepoints: 0,
+        }
+    }
+
+    /// Returns the transaction, or the reason it can no longer be used.
+    pub fn get(&self) -> Result<&libsql::Transaction, String> {
+        match &self.state {
+            TransactionState::Active(trx) => Ok(trx),
+            state => Err(state.finished_error()),
+        }
+    }
+
+    /// Commits the transaction, marking the handle as committed once the commit succeeded.
+    ///
+    /// A transaction whose commit fails is rolled back, so that the connection does not stay inside it,
+    /// and the handle is marked as rolled back.
+    ///
+    /// # Returns
+    ///
+    /// The result of the commit, or the reason the transaction can no longer be used.
+    pub fn commit(&mut self) -> Result<libsql::Result<()>, String> {
+        let trx = self.take(TransactionState::RolledBack)?;
+        let conn = (*trx).clone();
+
+        let committed = runtime().block_on(async {
+            let committed = trx.commit().await;
+            if committed.is_err() && !conn.is_autocommit() {
+                let _ = conn.execute("ROLLBACK", ()).await;
+            }
+            committed
+        });
+
+        if committed.is_ok() {
+            self.state = TransactionState::Committed;
+        }
+        Ok(committed)
+    }
+
+    /// Takes the transaction out of the handle to roll it back, marking the handle as rolled back.
+    pub fn take_for_rollback(&mut self) -> Result<libsql::Transaction, String> {
+        self.take(TransactionState::RolledBack)
+    }
+
+    /// Returns how many savepoints are open inside the transaction.
+    pub fn savepoint_depth(&self) -> usize {
+        self.savepoints.len()
+    }
+
+    /// Generates a name for a savepoint opened without one.
+    pub fn generate_savepoint_name(&mut self) -> String {
+        self.generated_savepoints += 1;
+        format!("libsql_php_sp_{}", self.generated_savepoints)
+    }
+
+    /// Records that the savepoint `name` was opened inside the innermost one.
+    pub fn push_savepoint(&mut self, name: String) {
+        self.savepoints.push(name);
+    }
+
+    /// Finds the innermost open savepoint called `name`, or the innermost one when `name` is
+    /// `None`, and returns its depth, where `0` is the outermost savepoint.
+    pub fn find_savepoint(&self, name: Option<&str>) -> Result<usize, String> {
+        let found = match name {
+            Some(name) => self.savepoints.iter().rposition(|open| open == name),
+            None => self.savepoints.len().checked_sub(1),
+        };
+
+        found.ok_or_else(|| match name {
+            Some(name) => format!("{}: {name}", crate::ERR_SAVEPOINT_UNKNOWN),
+            None => crate::ERR_SAVEPOINT_NONE.to_string(),
+        })
+    }
+
+    /// Returns the name of the savepoint open at `depth`.
+    pub fn savepoint_name(&self, depth: usize) -> &str {
+        &self.savepoints[depth]
+    }
+
+    /// Forgets the savepoints opened deeper than `depth` savepoints.
+    pub fn truncate_savepoints(&mut self, depth: usize) {
+        self.savepoints.truncate(depth);
+    }
+
+    fn take(&mut self, next: TransactionState) -> Result<libsql::Transaction, String> {
+        match std::mem::replace(&mut self.state, next) {
+            TransactionState::Active(trx) => {
+                self.savepoints.clear();
+                Ok(trx)
+            }
+            state => {
+                let e = state.finished_error();
+                self.state = state;
+                Err(e)
+            }
+        }
+    }
+}
+
+impl TransactionState {
+    fn finished_error(&self) -> String {
+        match self {
+            Self::Active(_) => unreachable!("an active transaction is not finished"),
+            Self::Committed => format!("{}: it was committed", crate::ERR_TRANSACTION_FINISHED),
+            Self::RolledBack => format!("{}: it was rolled back", crate::ERR_TRANSACTION_FINISHED),
+        }
+    }
+}
+
+impl Drop for TransactionHandle {
+    /// Rolls back a transaction that was neither committed nor rolled back.
+    fn drop(&mut self) {
+        if let TransactionState::Active(trx) =
+            std::mem::replace(&mut self.state, TransactionState::RolledBack)
+        {
+            let _ = runtime().block_on(trx.rollback());
+        }
+    }
+}
+
+/// Quotes a savepoint name so it can be used in a `SAVEPOINT`, `RELEASE` or `ROLLBACK TO` statement.
+pub fn quote_savepoint(name: &str) -> String {
+    format!("\"{}\"", name.replace('"', "\"\""))
+}
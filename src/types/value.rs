@@ -0,0 +1,56 @@
+/// Tag of a `LibsqlPhpValue` holding an integer, matching `LIBSQLPHP_INTEGER` on the PHP side.
+pub const LIBSQL_PHP_INTEGER: libc::c_int = 1;
+/// Tag of a `LibsqlPhpValue` holding a float, matching `LIBSQLPHP_FLOAT` on the PHP side.
+pub const LIBSQL_PHP_FLOAT: libc::c_int = 2;
+/// Tag of a `LibsqlPhpValue` holding UTF-8 text, matching `LIBSQLPHP_TEXT` on the PHP side.
+pub const LIBSQL_PHP_TEXT: libc::c_int = 3;
+/// Tag of a `LibsqlPhpValue` holding raw bytes, matching `LIBSQLPHP_BLOB` on the PHP side.
+pub const LIBSQL_PHP_BLOB: libc::c_int = 4;
+/// Tag of a `LibsqlPhpValue` holding SQL NULL, matching `LIBSQLPHP_NULL` on the PHP side.
+pub const LIBSQL_PHP_NULL: libc::c_int = 5;
+
+/// Represents a typed parameter value passed from C code.
+///
+/// This struct is marked with #[repr(C)] to ensure its memory layout is compatible
+/// with C code. Only the field selected by `tag` is read.
+#[repr(C)]
+pub struct LibsqlPhpValue {
+    /// One of the `LIBSQL_PHP_*` type tags.
+    pub tag: libc::c_int,
+    /// The value when `tag` is `LIBSQL_PHP_INTEGER`.
+    pub int_value: i64,
+    /// The value when `tag` is `LIBSQL_PHP_FLOAT`.
+    pub real_value: f64,
+    /// A raw pointer to the bytes when `tag` is `LIBSQL_PHP_TEXT` or `LIBSQL_PHP_BLOB`.
+    pub ptr: *const u8,
+    /// The number of bytes pointed to by `ptr`.
+    pub len: usize,
+}
+
+impl LibsqlPhpValue {
+    /// Converts the C value into a `libsql::Value`.
+    ///
+    /// # Safety
+    ///
+    /// When `tag` is `LIBSQL_PHP_TEXT` or `LIBSQL_PHP_BLOB`, `ptr` must point to `len` readable bytes.
+    pub unsafe fn to_value(&self) -> Result<libsql::Value, String> {
+        let bytes = || {
+            if self.ptr.is_null() || self.len == 0 {
+                &[][..]
+            } else {
+                unsafe { std::slice::from_raw_parts(self.ptr, self.len) }
+            }
+        };
+
+        match self.tag {
+            LIBSQL_PHP_INTEGER => Ok(libsql::Value::Integer(self.int_value)),
+            LIBSQL_PHP_FLOAT => Ok(libsql::Value::Real(self.real_value)),
+            LIBSQL_PHP_TEXT => std::str::from_utf8(bytes())
+                .map(|text| libsql::Value::Text(text.to_string()))
+                .map_err(|e| format!("Text parameter is not valid UTF-8: {e}")),
+            LIBSQL_PHP_BLOB => Ok(libsql::Value::Blob(bytes().to_vec())),
+            LIBSQL_PHP_NULL => Ok(libsql::Value::Null),
+            tag => Err(format!("Unknown parameter type tag: {tag}")),
+        }
+    }
+}
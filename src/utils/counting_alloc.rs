@@ -0,0 +1,89 @@
+use std::{
+    alloc::{GlobalAlloc, Layout, System},
+    cell::Cell,
+};
+
+/// How many bytes a measured round may leave allocated on top of the settled count, for buffers
+/// that grow once to a size they keep.
+const SLACK_BYTES: isize = 1024;
+
+/// The allocator of the test binary. It counts the bytes allocated and not yet freed by the current
+/// thread while tracking is on, so each test only sees its own allocations.
+struct Counting;
+
+thread_local! {
+    static TRACK: Cell<bool> = const { Cell::new(false) };
+    static LIVE: Cell<isize> = const { Cell::new(0) };
+}
+
+fn count(delta: isize) {
+    let _ = TRACK.try_with(|track| {
+        if track.get() {
+            let _ = LIVE.try_with(|live| live.set(live.get() + delta));
+        }
+    });
+}
+
+unsafe impl GlobalAlloc for Counting {
+    unsafe fn alloc(&self, layout: Layout) -> *mut u8 {
+        let ptr = unsafe { System.alloc(layout) };
+        if !ptr.is_null() {
+            count(layout.size() as isize);
+        }
+        ptr
+    }
+
+    unsafe fn alloc_zeroed(&self, layout: Layout) -> *mut u8 {
+        let ptr = unsafe { System.alloc_zeroed(layout) };
+        if !ptr.is_null() {
+            count(layout.size() as isize);
+        }
+        ptr
+    }
+
+    unsafe fn dealloc(&self, ptr: *mut u8, layout: Layout) {
+        count(-(layout.size() as isize));
+        unsafe { System.dealloc(ptr, layout) }
+    }
+
+    unsafe fn realloc(&self, ptr: *mut u8, layout: Layout, new_size: usize) -> *mut u8 {
+        let new_ptr = unsafe { System.realloc(ptr, layout, new_size) };
+        if !new_ptr.is_null() {
+            count(new_size as isize - layout.size() as isize);
+        }
+        new_ptr
+    }
+}
+
+#[global_allocator]
+static ALLOCATOR: Counting = Counting;
+
+/// Runs `round` until the bytes it leaves allocated settle, then asserts that two more rounds do
+/// not leave more than a few bytes allocated on top of them.
+///
+/// Every round runs `iterations` calls of `round` on the current thread.
+pub(crate) fn assert_no_growth(iterations: usize, mut round: impl FnMut()) {
+    let mut run = || {
+        for _ in 0..iterations {
+            round();
+        }
+        LIVE.with(Cell::get)
+    };
+
+    // Let caches and the runtime settle before counting.
+    run();
+    TRACK.with(|track| track.set(true));
+    let settled = run();
+    let live = [run(), run()];
+    TRACK.with(|track| track.set(false));
+    LIVE.with(|live| live.set(0));
+
+    for (measured, live) in live.into_iter().enumerate() {
+        assert!(
+            live <= settled + SLACK_BYTES,
+            "{} bytes still live after measured round {}",
+            live - settled,
+            measured + 1
+        );
+    }
+}
@@ -1,18 +1,242 @@
-/// Prints an error message related to LibSQL operations in PHP format and exits the program.
-/// 
+use std::{
+    any::Any,
+    cell::RefCell,
+    ffi::CString,
+    panic::{catch_unwind, AssertUnwindSafe},
+};
+
+/// Status returned by the exported functions when the call succeeded.
+pub const LIBSQL_PHP_OK: libc::c_int = 0;
+
+/// Numeric error codes returned by the exported functions and reported by
+/// `libsql_php_last_error_code`.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+#[repr(i32)]
+pub enum ErrorCode {
+    NullClientPtr = 1,
+    PathIsEmpty = 2,
+    InvalidPathConvert = 3,
+    InvalidArguments = 4,
+    InvalidQueryConvert = 5,
+    RemoteReplicaConfiguration = 6,
+    UnsupportedMode = 7,
+    TransactionCommit = 8,
+    TransactionRollback = 9,
+    StringConvertion = 10,
+    QueryExecution = 11,
+    InitiateTransaction = 12,
+    ExecutionFailed = 13,
+    ConnectionFailed = 14,
+    SyncFailed = 15,
+    Serialization = 16,
+    InvalidParameter = 17,
+    NullStatementPtr = 18,
+    NullRowsPtr = 19,
+    RemoteConfiguration = 20,
+    NullDatabasePtr = 21,
+    NullPoolPtr = 22,
+    PoolExhausted = 23,
+    InvalidOpenFlags = 24,
+    InvalidEncryption = 25,
+    RekeyFailed = 26,
+    SyncTimeout = 27,
+    BootstrapFailed = 28,
+    Panic = 29,
+    TransactionFinished = 30,
+    UnknownSavepoint = 31,
+    InvalidTransactionBehavior = 32,
+    InvalidRetryPolicy = 33,
+}
+
+/// The error recorded by the most recent failing call on the current thread.
+struct LastError {
+    code: ErrorCode,
+    extended_code: libc::c_int,
+    message: CString,
+}
+
+thread_local! {
+    static LAST_ERROR: RefCell<Option<LastError>> = const { RefCell::new(None) };
+}
+
+/// Records an error related to LibSQL operations so the PHP side can turn it into an exception.
+///
 /// # Arguments
-/// 
+///
 /// * `msg` - A string slice representing the error message.
-/// * `code` - A string slice representing the error code.
-/// 
+/// * `code` - The error code to report.
+///
+/// # Returns
+///
+/// The numeric value of `code`, ready to be returned as the status of an exported function.
+///
 /// # Examples
-/// 
+///
 /// ```
-/// libsql_php_error("Connection failed", "CONN_ERR");
+/// return libsql_php_error("Connection failed", ErrorCode::ConnectionFailed);
 /// ```
-pub fn libsql_php_error(msg: &str, code: &str) {
-    let code_upper = code.to_uppercase();
-    let message = format!("Error LibSQLPHP - {msg} - {code_upper}");
-    eprintln!("{}", message);
-    std::process::exit(1);
+pub fn libsql_php_error(msg: &str, code: ErrorCode) -> libc::c_int {
+    set_last_error(msg, code, 0)
+}
+
+/// Records an error returned by libsql, keeping the SQLite extended result code when there is one.
+///
+/// # Arguments
+///
+/// * `err` - The error returned by libsql.
+/// * `code` - The error code to report.
+///
+/// # Returns
+///
+/// The numeric value of `code`, ready to be returned as the status of an exported function.
+pub fn libsql_php_libsql_error(err: &libsql::Error, code: ErrorCode) -> libc::c_int {
+    set_last_error(&format!("{err}"), code, extended_code(err))
+}
+
+/// Like `libsql_php_libsql_error`, prefixing the message with `context`, such as the statement of a
+/// batch that failed.
+pub fn libsql_php_libsql_error_in(
+    context: &str,
+    err: &libsql::Error,
+    code: ErrorCode,
+) -> libc::c_int {
+    set_last_error(&format!("{context}: {err}"), code, extended_code(err))
+}
+
+fn extended_code(err: &libsql::Error) -> libc::c_int {
+    match err {
+        libsql::Error::SqliteFailure(extended_code, _) => *extended_code,
+        libsql::Error::RemoteSqliteFailure(_, extended_code, _) => *extended_code,
+        _ => 0,
+    }
+}
+
+/// Runs the body of an exported function, turning a panic into an `ErrorCode::Panic` error
+/// instead of letting it unwind into the C caller, which is undefined behavior.
+///
+/// # Returns
+///
+/// The status returned by `f`, or the numeric value of `ErrorCode::Panic` if it panicked.
+pub fn catch_panic(f: impl FnOnce() -> libc::c_int) -> libc::c_int {
+    catch_unwind(AssertUnwindSafe(f)).unwrap_or_else(|payload| record_panic(&*payload))
+}
+
+/// Like `catch_panic`, for exported functions that do not return a status. Returns `fallback`
+/// after recording the panic.
+pub fn catch_panic_or<T>(fallback: T, f: impl FnOnce() -> T) -> T {
+    catch_unwind(AssertUnwindSafe(f)).unwrap_or_else(|payload| {
+        record_panic(&*payload);
+        fallback
+    })
+}
+
+fn record_panic(payload: &(dyn Any + Send)) -> libc::c_int {
+    let message = payload
+        .downcast_ref::<&str>()
+        .copied()
+        .or_else(|| payload.downcast_ref::<String>().map(String::as_str))
+        .unwrap_or("unknown panic");
+
+    libsql_php_error(&format!("Internal panic: {message}"), ErrorCode::Panic)
+}
+
+fn set_last_error(msg: &str, code: ErrorCode, extended_code: libc::c_int) -> libc::c_int {
+    let message = CString::new(msg.replace('\0', "")).unwrap_or_default();
+
+    LAST_ERROR.with(|last_error| {
+        *last_error.borrow_mut() = Some(LastError {
+            code,
+            extended_code,
+            message,
+        });
+    });
+
+    code as libc::c_int
+}
+
+/// Returns the error code of the most recent failing call on the current thread, or `0`.
+pub fn last_error_code() -> libc::c_int {
+    LAST_ERROR.with(|last_error| {
+        last_error
+            .borrow()
+            .as_ref()
+            .map_or(LIBSQL_PHP_OK, |e| e.code as libc::c_int)
+    })
+}
+
+/// Returns the SQLite extended result code of the most recent failing call on the current thread, or `0`.
+pub fn last_error_extended_code() -> libc::c_int {
+    LAST_ERROR.with(|last_error| last_error.borrow().as_ref().map_or(0, |e| e.extended_code))
+}
+
+/// Returns the message of the most recent failing call on the current thread, or a null pointer.
+///
+/// The pointer stays valid until the next error is recorded or cleared on the same thread.
+pub fn last_error_message() -> *const libc::c_char {
+    LAST_ERROR.with(|last_error| {
+        last_error
+            .borrow()
+            .as_ref()
+            .map_or(std::ptr::null(), |e| e.message.as_ptr())
+    })
+}
+
+/// Forgets the error recorded on the current thread.
+pub fn clear_last_error() {
+    LAST_ERROR.with(|last_error| *last_error.borrow_mut() = None);
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::{
+        libsqlphp::last_error::{libsql_php_last_error_code, libsql_php_last_error_message},
+        utils::testing::last_error,
+    };
+
+    #[test]
+    fn catch_panic_reports_a_str_panic() {
+        clear_last_error();
+
+        let status = catch_panic(|| panic!("boom"));
+
+        assert_eq!(status, ErrorCode::Panic as libc::c_int);
+        assert_eq!(
+            libsql_php_last_error_code(),
+            ErrorCode::Panic as libc::c_int
+        );
+        assert!(!libsql_php_last_error_message().is_null());
+        assert_eq!(last_error(), "Internal panic: boom");
+    }
+
+    #[test]
+    fn catch_panic_reports_a_formatted_panic() {
+        let status = catch_panic(|| panic!("boom at {}", 42));
+
+        assert_eq!(status, ErrorCode::Panic as libc::c_int);
+        assert_eq!(last_error(), "Internal panic: boom at 42");
+    }
+
+    #[test]
+    fn catch_panic_or_returns_the_fallback() {
+        let value = catch_panic_or(std::ptr::null::<libc::c_char>(), || panic!("no value"));
+
+        assert!(value.is_null());
+        assert_eq!(
+            libsql_php_last_error_code(),
+            ErrorCode::Panic as libc::c_int
+        );
+        assert_eq!(last_error(), "Internal panic: no value");
+    }
+
+    #[test]
+    fn calls_succeed_after_a_panic() {
+        assert_eq!(
+            catch_panic(|| panic!("first")),
+            ErrorCode::Panic as libc::c_int
+        );
+
+        assert_eq!(catch_panic(|| LIBSQL_PHP_OK), LIBSQL_PHP_OK);
+        assert_eq!(catch_panic_or(7, || 8), 8);
+    }
 }
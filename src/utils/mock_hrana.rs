@@ -0,0 +1,275 @@
+//! A Hrana over HTTP server for the tests, running statements on an in-memory database.
+//!
+//! It answers the `/v3/pipeline` and `/v3/cursor` requests libsql sends for statements executed
+//! and queried outside a transaction, over plain HTTP/1.1, so remote connections can be tested
+//! without a sqld server or network access.
+
+use base64::{engine::general_purpose::STANDARD_NO_PAD, Engine as _};
+use serde_json::{json, Value};
+use tokio::{
+    io::{AsyncReadExt, AsyncWriteExt},
+    net::{TcpListener, TcpStream},
+};
+
+use super::runtime::runtime;
+
+/// Starts a server on a free local port and returns its URL. It runs until the test process exits.
+pub(crate) fn start() -> String {
+    let rt = runtime();
+    let listener = rt.block_on(TcpListener::bind("127.0.0.1:0")).unwrap();
+    let url = format!("http://{}", listener.local_addr().unwrap());
+
+    let db = rt
+        .block_on(libsql::Builder::new_local(":memory:").build())
+        .unwrap();
+    let conn = db.connect().unwrap();
+
+    rt.spawn(async move {
+        let _db = db;
+        while let Ok((socket, _)) = listener.accept().await {
+            tokio::spawn(serve(socket, conn.clone()));
+        }
+    });
+
+    url
+}
+
+/// Answers the requests sent on one HTTP connection until the client closes it.
+async fn serve(mut socket: TcpStream, conn: libsql::Connection) {
+    let mut buf = Vec::new();
+
+    while let Some((path, body)) = read_request(&mut socket, &mut buf).await {
+        let (status, body) = match path.as_str() {
+            "/v3/pipeline" => ("200 OK", pipeline(&conn, &body).await.to_string()),
+            "/v3/cursor" => ("200 OK", cursor(&conn, &body).await),
+            _ => ("404 Not Found", String::new()),
+        };
+
+        let response = format!(
+            "HTTP/1.1 {status}\r\nContent-Type: application/json\r\nContent-Length: {}\r\n\r\n{body}",
+            body.len()
+        );
+        if socket.write_all(response.as_bytes()).await.is_err() {
+            return;
+        }
+    }
+}
+
+/// Reads the next request from `socket`, returning its path and JSON body.
+async fn read_request(socket: &mut TcpStream, buf: &mut Vec<u8>) -> Option<(String, Value)> {
+    let header_end = loop {
+        if let Some(end) = buf.windows(4).position(|window| window == b"\r\n\r\n") {
+            break end + 4;
+        }
+        read_more(socket, buf).await?;
+    };
+
+    let head = String::from_utf8_lossy(&buf[..header_end]).into_owned();
+    let path = head.split_whitespace().nth(1)?.to_string();
+    let content_length = head
+        .lines()
+        .filter_map(|line| line.split_once(':'))
+        .find(|(name, _)| name.eq_ignore_ascii_case("content-length"))
+        .and_then(|(_, value)| value.trim().parse::<usize>().ok())
+        .unwrap_or(0);
+
+    while buf.len() < header_end + content_length {
+        read_more(socket, buf).await?;
+    }
+
+    let body: Vec<u8> = buf
+        .drain(..header_end + content_length)
+        .skip(header_end)
+        .collect();
+    Some((path, serde_json::from_slice(&body).unwrap_or(Value::Null)))
+}
+
+async fn read_more(socket: &mut TcpStream, buf: &mut Vec<u8>) -> Option<()> {
+    let mut chunk = [0; 4096];
+    match socket.read(&mut chunk).await {
+        Ok(0) | Err(_) => None,
+        Ok(read) => {
+            buf.extend_from_slice(&chunk[..read]);
+            Some(())
+        }
+    }
+}
+
+/// Answers a pipeline request. Every stream is closed after the request, so no baton is handed out.
+async fn pipeline(conn: &libsql::Connection, body: &Value) -> Value {
+    let mut results = Vec::new();
+
+    for request in body["requests"].as_array().into_iter().flatten() {
+        let result = match request["type"].as_str() {
+            Some("execute") => match execute(conn, &request["stmt"]).await {
+                Ok(result) => {
+                    json!({"type": "ok", "response": {"type": "execute", "result": result}})
+                }
+                Err(e) => error(&e),
+            },
+            Some("batch") => json!({
+                "type": "ok",
+                "response": {"type": "batch", "result": batch(conn, &request["batch"]).await},
+            }),
+            Some("close") => json!({"type": "ok", "response": {"type": "close"}}),
+            Some("get_autocommit") => json!({
+                "type": "ok",
+                "response": {"type": "get_autocommit", "is_autocommit": conn.is_autocommit()},
+            }),
+            other => json!({
+                "type": "error",
+                "error": {"message": format!("unsupported request {other:?}"), "code": "UNSUPPORTED"},
+            }),
+        };
+        results.push(result);
+    }
+
+    json!({"baton": null, "base_url": null, "results": results})
+}
+
+/// Runs the steps of a Hrana batch whose condition holds, and returns the batch result.
+async fn batch(conn: &libsql::Connection, batch: &Value) -> Value {
+    let mut results: Vec<Option<Value>> = Vec::new();
+    let mut errors: Vec<Option<Value>> = Vec::new();
+
+    for step in batch["steps"].as_array().into_iter().flatten() {
+        let run = step["condition"].is_null() || holds(conn, &step["condition"], &results, &errors);
+        let (result, error) = match run {
+            false => (None, None),
+            true => match execute(conn, &step["stmt"]).await {
+                Ok(result) => (Some(result), None),
+                Err(e) => (None, Some(error(&e)["error"].clone())),
+            },
+        };
+        results.push(result);
+        errors.push(error);
+    }
+
+    json!({"step_results": results, "step_errors": errors, "replication_index": null})
+}
+
+fn holds(
+    conn: &libsql::Connection,
+    cond: &Value,
+    results: &[Option<Value>],
+    errors: &[Option<Value>],
+) -> bool {
+    let step = cond["step"].as_u64().unwrap_or_default() as usize;
+    let conds = || cond["conds"].as_array().into_iter().flatten();
+
+    match cond["type"].as_str() {
+        Some("ok") => results.get(step).is_some_and(Option::is_some),
+        Some("error") => errors.get(step).is_some_and(Option::is_some),
+        Some("not") => !holds(conn, &cond["cond"], results, errors),
+        Some("and") => conds().all(|cond| holds(conn, cond, results, errors)),
+        Some("or") => conds().any(|cond| holds(conn, cond, results, errors)),
+        Some("is_autocommit") => conn.is_autocommit(),
+        _ => false,
+    }
+}
+
+/// Answers a cursor request with one line of JSON per entry.
+async fn cursor(conn: &libsql::Connection, body: &Value) -> String {
+    let mut lines = vec![json!({"baton": null, "base_url": null})];
+
+    for (step, batch_step) in body["batch"]["steps"]
+        .as_array()
+        .into_iter()
+        .flatten()
+        .enumerate()
+    {
+        match execute(conn, &batch_step["stmt"]).await {
+            Ok(result) => {
+                lines.push(json!({"type": "step_begin", "step": step, "cols": result["cols"]}));
+                for row in result["rows"].as_array().into_iter().flatten() {
+                    lines.push(json!({"type": "row", "row": row}));
+                }
+                lines.push(json!({
+                    "type": "step_end",
+                    "affected_row_count": result["affected_row_count"],
+                    "last_insert_rowid": conn.last_insert_rowid(),
+                }));
+            }
+            Err(e) => {
+                lines
+                    .push(json!({"type": "step_error", "step": step, "error": error(&e)["error"]}));
+            }
+        }
+    }
+
+    lines.iter().map(|line| format!("{line}\n")).collect()
+}
+
+/// Runs a Hrana statement and returns its result in the Hrana format.
+async fn execute(conn: &libsql::Connection, stmt: &Value) -> libsql::Result<Value> {
+    let sql = stmt["sql"].as_str().unwrap_or_default();
+    let args: Vec<libsql::Value> = stmt["args"]
+        .as_array()
+        .into_iter()
+        .flatten()
+        .map(from_hrana)
+        .collect();
+
+    let mut prepared = conn.prepare(sql).await?;
+    let cols: Vec<Value> = prepared
+        .columns()
+        .iter()
+        .map(|col| json!({"name": col.name(), "decltype": col.decl_type()}))
+        .collect();
+
+    let mut rows = prepared.query(args).await?;
+    let mut values = Vec::new();
+    while let Some(row) = rows.next().await? {
+        let row: Vec<Value> = (0..cols.len() as i32)
+            .map(|idx| row.get_value(idx).map(|value| to_hrana(&value)))
+            .collect::<libsql::Result<_>>()?;
+        values.push(Value::Array(row));
+    }
+
+    let affected_row_count = if cols.is_empty() { conn.changes() } else { 0 };
+
+    Ok(json!({
+        "cols": cols,
+        "rows": values,
+        "affected_row_count": affected_row_count,
+        "last_insert_rowid": conn.last_insert_rowid().to_string(),
+        "replication_index": null,
+    }))
+}
+
+fn error(e: &libsql::Error) -> Value {
+    json!({"type": "error", "error": {"message": e.to_string(), "code": "SQLITE_ERROR"}})
+}
+
+fn from_hrana(value: &Value) -> libsql::Value {
+    match value["type"].as_str() {
+        Some("integer") => libsql::Value::Integer(
+            value["value"]
+                .as_str()
+                .and_then(|value| value.parse().ok())
+                .unwrap_or_default(),
+        ),
+        Some("float") => libsql::Value::Real(value["value"].as_f64().unwrap_or_default()),
+        Some("text") => {
+            libsql::Value::Text(value["value"].as_str().unwrap_or_default().to_string())
+        }
+        Some("blob") => libsql::Value::Blob(
+            STANDARD_NO_PAD
+                .decode(value["base64"].as_str().unwrap_or_default())
+                .unwrap_or_default(),
+        ),
+        _ => libsql::Value::Null,
+    }
+}
+
+fn to_hrana(value: &libsql::Value) -> Value {
+    match value {
+        libsql::Value::Null => json!({"type": "null"}),
+        libsql::Value::Integer(value) => json!({"type": "integer", "value": value.to_string()}),
+        libsql::Value::Real(value) => json!({"type": "float", "value": value}),
+        libsql::Value::Text(value) => json!({"type": "text", "value": value}),
+        libsql::Value::Blob(value) => {
+            json!({"type": "blob", "base64": STANDARD_NO_PAD.encode(value)})
+        }
+    }
+}
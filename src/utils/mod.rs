@@ -1,2 +1,13 @@
 pub mod runtime;
 pub mod errors;
+pub mod open_flags;
+pub mod params;
+pub mod query_result;
+pub mod transaction_behavior;
+pub mod retry;
+#[cfg(test)]
+pub mod testing;
+#[cfg(test)]
+pub mod mock_hrana;
+#[cfg(test)]
+pub mod counting_alloc;
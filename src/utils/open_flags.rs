@@ -0,0 +1,81 @@
+use libsql::OpenFlags;
+
+use crate::{
+    ERR_OPEN_FLAGS_ACCESS, ERR_OPEN_FLAGS_CACHE, ERR_OPEN_FLAGS_CREATE, ERR_OPEN_FLAGS_MUTEX,
+    ERR_OPEN_FLAGS_READONLY_READWRITE, ERR_OPEN_FLAGS_UNKNOWN,
+};
+
+/// Opens the database read-only, matching `LIBSQLPHP_OPEN_READONLY` on the PHP side.
+pub const LIBSQL_PHP_OPEN_READONLY: libc::c_int = 0x0000_0001;
+/// Opens the database for reading and writing, matching `LIBSQLPHP_OPEN_READWRITE` on the PHP side.
+pub const LIBSQL_PHP_OPEN_READWRITE: libc::c_int = 0x0000_0002;
+/// Creates the database if it does not exist, matching `LIBSQLPHP_OPEN_CREATE` on the PHP side.
+pub const LIBSQL_PHP_OPEN_CREATE: libc::c_int = 0x0000_0004;
+/// Interprets the path as a URI filename, matching `LIBSQLPHP_OPEN_URI` on the PHP side.
+pub const LIBSQL_PHP_OPEN_URI: libc::c_int = 0x0000_0040;
+/// Opens the database in memory, matching `LIBSQLPHP_OPEN_MEMORY` on the PHP side.
+pub const LIBSQL_PHP_OPEN_MEMORY: libc::c_int = 0x0000_0080;
+/// Opens the connection in multi-thread mode, matching `LIBSQLPHP_OPEN_NOMUTEX` on the PHP side.
+pub const LIBSQL_PHP_OPEN_NOMUTEX: libc::c_int = 0x0000_8000;
+/// Opens the connection in serialized mode, matching `LIBSQLPHP_OPEN_FULLMUTEX` on the PHP side.
+pub const LIBSQL_PHP_OPEN_FULLMUTEX: libc::c_int = 0x0001_0000;
+/// Enables the shared cache, matching `LIBSQLPHP_OPEN_SHAREDCACHE` on the PHP side.
+pub const LIBSQL_PHP_OPEN_SHAREDCACHE: libc::c_int = 0x0002_0000;
+/// Disables the shared cache, matching `LIBSQLPHP_OPEN_PRIVATECACHE` on the PHP side.
+pub const LIBSQL_PHP_OPEN_PRIVATECACHE: libc::c_int = 0x0004_0000;
+/// Refuses to open a path that is a symbolic link, matching `LIBSQLPHP_OPEN_NOFOLLOW` on the PHP side.
+pub const LIBSQL_PHP_OPEN_NOFOLLOW: libc::c_int = 0x0100_0000;
+
+const KNOWN_FLAGS: libc::c_int = LIBSQL_PHP_OPEN_READONLY
+    | LIBSQL_PHP_OPEN_READWRITE
+    | LIBSQL_PHP_OPEN_CREATE
+    | LIBSQL_PHP_OPEN_URI
+    | LIBSQL_PHP_OPEN_MEMORY
+    | LIBSQL_PHP_OPEN_NOMUTEX
+    | LIBSQL_PHP_OPEN_FULLMUTEX
+    | LIBSQL_PHP_OPEN_SHAREDCACHE
+    | LIBSQL_PHP_OPEN_PRIVATECACHE
+    | LIBSQL_PHP_OPEN_NOFOLLOW;
+
+/// Converts a bitmask of `LIBSQL_PHP_OPEN_*` flags into `OpenFlags`.
+///
+/// `0` selects the default, `LIBSQL_PHP_OPEN_READWRITE | LIBSQL_PHP_OPEN_CREATE`. The bits share
+/// their values with the `SQLITE_OPEN_*` flags, so those without a named `OpenFlags` constant
+/// are passed through to SQLite unchanged.
+///
+/// # Returns
+///
+/// The flags, or the reason the combination was rejected.
+pub fn open_flags(bits: libc::c_int) -> Result<OpenFlags, &'static str> {
+    if bits == 0 {
+        return Ok(OpenFlags::default());
+    }
+
+    let has = |flag: libc::c_int| bits & flag != 0;
+
+    if bits & !KNOWN_FLAGS != 0 {
+        return Err(ERR_OPEN_FLAGS_UNKNOWN);
+    }
+
+    if has(LIBSQL_PHP_OPEN_READONLY) && has(LIBSQL_PHP_OPEN_READWRITE) {
+        return Err(ERR_OPEN_FLAGS_READONLY_READWRITE);
+    }
+
+    if !has(LIBSQL_PHP_OPEN_READONLY) && !has(LIBSQL_PHP_OPEN_READWRITE) {
+        return Err(ERR_OPEN_FLAGS_ACCESS);
+    }
+
+    if has(LIBSQL_PHP_OPEN_CREATE) && !has(LIBSQL_PHP_OPEN_READWRITE) {
+        return Err(ERR_OPEN_FLAGS_CREATE);
+    }
+
+    if has(LIBSQL_PHP_OPEN_NOMUTEX) && has(LIBSQL_PHP_OPEN_FULLMUTEX) {
+        return Err(ERR_OPEN_FLAGS_MUTEX);
+    }
+
+    if has(LIBSQL_PHP_OPEN_SHAREDCACHE) && has(LIBSQL_PHP_OPEN_PRIVATECACHE) {
+        return Err(ERR_OPEN_FLAGS_CACHE);
+    }
+
+    Ok(OpenFlags::from_bits_retain(bits))
+}
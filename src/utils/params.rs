@@ -0,0 +1,241 @@
+use crate::types::value::LibsqlPhpValue;
+
+/// Converts an array of C strings into positional libsql text parameters.
+///
+/// # Safety
+///
+/// `params` must either be null or point to `params_len` items, and every non-null item must be a
+/// null-terminated C string.
+///
+/// # Returns
+///
+/// The converted values, or a message describing the first parameter that is null or not valid UTF-8.
+pub unsafe fn text_from_raw(
+    params: *const *const libc::c_char,
+    params_len: usize,
+) -> Result<Vec<libsql::Value>, String> {
+    if params.is_null() || params_len == 0 {
+        return Ok(Vec::new());
+    }
+
+    let params_slice = unsafe { std::slice::from_raw_parts(params, params_len) };
+    params_slice
+        .iter()
+        .enumerate()
+        .map(|(idx, &param_ptr)| {
+            if param_ptr.is_null() {
+                return Err(format!("Parameter {}: value is null", idx + 1));
+            }
+
+            unsafe { std::ffi::CStr::from_ptr(param_ptr) }
+                .to_str()
+                .map(|param| libsql::Value::from(param.to_string()))
+                .map_err(|e| format!("Parameter {}: value is not valid UTF-8: {e}", idx + 1))
+        })
+        .collect()
+}
+
+/// Converts an array of typed C values into positional libsql parameters.
+///
+/// # Safety
+///
+/// `values` must either be null or point to `values_len` initialized `LibsqlPhpValue` items.
+///
+/// # Returns
+///
+/// The converted values, or a message describing the first value that could not be converted.
+pub unsafe fn values_from_raw(
+    values: *const LibsqlPhpValue,
+    values_len: usize,
+) -> Result<Vec<libsql::Value>, String> {
+    if values.is_null() || values_len == 0 {
+        return Ok(Vec::new());
+    }
+
+    let values_slice = unsafe { std::slice::from_raw_parts(values, values_len) };
+    values_slice
+        .iter()
+        .enumerate()
+        .map(|(idx, value)| {
+            unsafe { value.to_value() }.map_err(|e| format!("Parameter {}: {e}", idx + 1))
+        })
+        .collect()
+}
+
+/// Converts parallel arrays of C names and typed C values into named libsql parameters.
+///
+/// Names may be given with their `:`, `@` or `$` prefix, or without one; see [`resolve_named`].
+///
+/// # Safety
+///
+/// `names` and `values` must either be null or point to `values_len` items each, and every name
+/// must be a null-terminated C string.
+///
+/// # Returns
+///
+/// The converted name/value pairs, or a message describing the first pair that could not be converted.
+pub unsafe fn named_from_raw(
+    names: *const *const libc::c_char,
+    values: *const LibsqlPhpValue,
+    values_len: usize,
+) -> Result<Vec<(String, libsql::Value)>, String> {
+    if values_len == 0 {
+        return Ok(Vec::new());
+    }
+
+    if names.is_null() || values.is_null() {
+        return Err("Parameter names or values are null".to_string());
+    }
+
+    let names_slice = unsafe { std::slice::from_raw_parts(names, values_len) };
+    let values = unsafe { values_from_raw(values, values_len) }?;
+
+    names_slice
+        .iter()
+        .zip(values)
+        .enumerate()
+        .map(|(idx, (&name_ptr, value))| {
+            if name_ptr.is_null() {
+                return Err(format!("Parameter {}: name is null", idx + 1));
+            }
+
+            let name = unsafe { std::ffi::CStr::from_ptr(name_ptr) }
+                .to_str()
+                .map_err(|e| format!("Parameter {}: name is not valid UTF-8: {e}", idx + 1))?;
+
+            Ok((name.to_string(), value))
+        })
+        .collect()
+}
+
+/// Gives every named parameter the prefix it is written with in the prepared statement.
+///
+/// SQLite treats `:name`, `@name` and `$name` as distinct parameters, so a name passed without a
+/// prefix is matched against the statement's parameter names. Names that already carry a prefix
+/// are kept as they are, and unmatched names fall back to `:name`, which libsql ignores when
+/// the statement does not use it.
+pub fn resolve_named(
+    stmt: &libsql::Statement,
+    params: Vec<(String, libsql::Value)>,
+) -> Vec<(String, libsql::Value)> {
+    params
+        .into_iter()
+        .map(|(name, value)| (resolve_name(stmt, name), value))
+        .collect()
+}
+
+/// Gives one parameter name the prefix it is written with in the prepared statement.
+///
+/// See [`resolve_named`].
+pub fn resolve_name(stmt: &libsql::Statement, name: String) -> String {
+    if name.starts_with([':', '@', '$']) {
+        return name;
+    }
+
+    (1..=stmt.parameter_count() as i32)
+        .filter_map(|idx| stmt.parameter_name(idx))
+        .find(|candidate| candidate.get(1..) == Some(name.as_str()))
+        .map(str::to_string)
+        .unwrap_or_else(|| format!(":{name}"))
+}
+
+#[cfg(test)]
+mod tests {
+    use std::ffi::CString;
+
+    use serde_json::json;
+
+    use super::*;
+    use crate::{
+        libsqlphp::query_named::libsql_php_query_named,
+        utils::{
+            errors::LIBSQL_PHP_OK,
+            runtime::runtime,
+            testing::{c_value, close, connect_memory, last_error, take_json},
+        },
+    };
+
+    #[test]
+    fn resolves_names_to_the_prefix_used_in_the_statement() {
+        let stmt = runtime().block_on(async {
+            let db = libsql::Builder::new_local(":memory:")
+                .build()
+                .await
+                .unwrap();
+            let conn = db.connect().unwrap();
+            conn.prepare("SELECT :a, @b, $c").await.unwrap()
+        });
+        let resolve = |name: &str| resolve_name(&stmt, name.to_string());
+
+        assert_eq!(resolve("a"), ":a");
+        assert_eq!(resolve("b"), "@b");
+        assert_eq!(resolve("c"), "$c");
+        assert_eq!(resolve("@b"), "@b");
+        assert_eq!(resolve(":b"), ":b");
+        assert_eq!(resolve("missing"), ":missing");
+    }
+
+    fn query_named(
+        conn: *mut libc::c_void,
+        sql: &str,
+        params: &[(&str, libsql::Value)],
+    ) -> (libc::c_int, Option<serde_json::Value>) {
+        let sql = CString::new(sql).unwrap();
+        let names: Vec<CString> = params
+            .iter()
+            .map(|(name, _)| CString::new(*name).unwrap())
+            .collect();
+        let names: Vec<*const libc::c_char> = names.iter().map(|name| name.as_ptr()).collect();
+        let values: Vec<LibsqlPhpValue> = params.iter().map(|(_, value)| c_value(value)).collect();
+        let mut json = std::ptr::null();
+        let status = unsafe {
+            libsql_php_query_named(
+                conn,
+                sql.as_ptr(),
+                names.as_ptr(),
+                values.as_ptr(),
+                values.len(),
+                &mut json,
+            )
+        };
+        (status, (status == LIBSQL_PHP_OK).then(|| take_json(json)))
+    }
+
+    #[test]
+    fn binds_names_with_and_without_prefix() {
+        let conn = connect_memory();
+
+        let (status, result) = query_named(
+            conn,
+            "SELECT :a, @b, $c",
+            &[
+                ("c", libsql::Value::Integer(3)),
+                ("@b", libsql::Value::Integer(2)),
+                ("a", libsql::Value::Integer(1)),
+            ],
+        );
+        assert_eq!(status, LIBSQL_PHP_OK, "{}", last_error());
+        assert_eq!(
+            result.unwrap()["rows"],
+            json!([[
+                {"type": 1, "value": 1},
+                {"type": 1, "value": 2},
+                {"type": 1, "value": 3},
+            ]])
+        );
+
+        // Names the statement does not use are ignored.
+        let (status, result) = query_named(
+            conn,
+            "SELECT :a",
+            &[
+                ("unknown", libsql::Value::Integer(2)),
+                ("a", libsql::Value::Integer(1)),
+            ],
+        );
+        assert_eq!(status, LIBSQL_PHP_OK, "{}", last_error());
+        assert_eq!(result.unwrap()["rows"], json!([[{"type": 1, "value": 1}]]));
+
+        close(conn);
+    }
+}
@@ -0,0 +1,273 @@
+use base64::{engine::general_purpose::STANDARD, Engine};
+use serde::{ser::SerializeMap, Serialize, Serializer};
+
+use crate::types::value::{
+    LIBSQL_PHP_BLOB, LIBSQL_PHP_FLOAT, LIBSQL_PHP_INTEGER, LIBSQL_PHP_NULL, LIBSQL_PHP_TEXT,
+};
+
+/// The result of a query, with its columns and rows in SELECT order.
+///
+/// Rows are positional, so columns sharing a name (like `a.id, b.id` in a join)
+/// are all kept.
+#[derive(Serialize)]
+pub struct QueryResult {
+    /// Every column, in SELECT order.
+    pub columns: Vec<ColumnInfo>,
+    /// The values of every row, in the same order as `columns`.
+    pub rows: Vec<Vec<Cell>>,
+}
+
+/// Describes one result column.
+///
+/// Everything but the name comes from the prepared statement, so it is `None`
+/// for expressions and for connections that do not report column metadata.
+#[derive(Serialize)]
+pub struct ColumnInfo {
+    /// The name of the column, as given in the SELECT.
+    pub name: String,
+    /// The type the column was declared with in its table.
+    pub decltype: Option<String>,
+    /// The table the column comes from.
+    pub table: Option<String>,
+    /// The name of the column in its table.
+    pub origin: Option<String>,
+}
+
+/// The largest integer a JSON number holds exactly once decoded as a double.
+const MAX_SAFE_INTEGER: i64 = (1 << 53) - 1;
+
+/// One value of a row, serialized with its storage class as
+/// `{"type": <LIBSQL_PHP_* tag>, "value": ...}`.
+///
+/// Blobs are encoded as base64 strings, and integers outside ±(2^53 - 1) as decimal
+/// strings, so both survive JSON decoding without loss.
+pub struct Cell(pub libsql::Value);
+
+impl Serialize for Cell {
+    fn serialize<S: Serializer>(&self, serializer: S) -> Result<S::Ok, S::Error> {
+        let mut map = serializer.serialize_map(Some(2))?;
+        match &self.0 {
+            libsql::Value::Null => {
+                map.serialize_entry("type", &LIBSQL_PHP_NULL)?;
+                map.serialize_entry("value", &())?;
+            }
+            libsql::Value::Integer(value) => {
+                map.serialize_entry("type", &LIBSQL_PHP_INTEGER)?;
+                if value.unsigned_abs() > MAX_SAFE_INTEGER as u64 {
+                    map.serialize_entry("value", &value.to_string())?;
+                } else {
+                    map.serialize_entry("value", value)?;
+                }
+            }
+            libsql::Value::Real(value) => {
+                map.serialize_entry("type", &LIBSQL_PHP_FLOAT)?;
+                map.serialize_entry("value", value)?;
+            }
+            libsql::Value::Text(value) => {
+                map.serialize_entry("type", &LIBSQL_PHP_TEXT)?;
+                map.serialize_entry("value", value)?;
+            }
+            libsql::Value::Blob(value) => {
+                map.serialize_entry("type", &LIBSQL_PHP_BLOB)?;
+                map.serialize_entry("value", &STANDARD.encode(value))?;
+            }
+        }
+        map.end()
+    }
+}
+
+/// Drains the rows of a query into a `QueryResult`.
+///
+/// # Arguments
+///
+/// * `columns` - The columns of the query, as returned by [`column_info`].
+/// * `rows` - The rows returned by libsql.
+///
+/// # Returns
+///
+/// The collected rows, or the first error reported by libsql while stepping through them.
+pub async fn collect_rows(
+    columns: Vec<ColumnInfo>,
+    mut rows: libsql::Rows,
+) -> libsql::Result<QueryResult> {
+    let mut results = Vec::new();
+    while let Some(row) = rows.next().await? {
+        results.push(row_values(&row, rows.column_count())?);
+    }
+
+    Ok(QueryResult {
+        columns: merge_column_names(columns, &rows),
+        rows: results,
+    })
+}
+
+/// Reads the metadata of every result column of a prepared statement.
+pub fn column_info(stmt: &libsql::Statement) -> Vec<ColumnInfo> {
+    stmt.columns()
+        .iter()
+        .map(|column| ColumnInfo {
+            name: column.name().to_string(),
+            decltype: column.decl_type().map(str::to_string),
+            table: column.table_name().map(str::to_string),
+            origin: column.origin_name().map(str::to_string),
+        })
+        .collect()
+}
+
+/// Completes the column metadata with the names reported by the rows.
+///
+/// Remote statements cannot describe their columns before they run, so any column
+/// missing from `columns` is added with its name only.
+pub fn merge_column_names(mut columns: Vec<ColumnInfo>, rows: &libsql::Rows) -> Vec<ColumnInfo> {
+    for idx in columns.len() as i32..rows.column_count() {
+        columns.push(ColumnInfo {
+            name: rows.column_name(idx).unwrap_or_default().to_string(),
+            decltype: None,
+            table: None,
+            origin: None,
+        });
+    }
+    columns
+}
+
+/// Reads the values of one row, in SELECT order.
+///
+/// # Arguments
+///
+/// * `row` - The row returned by libsql.
+/// * `column_count` - The number of columns in the row.
+///
+/// # Returns
+///
+/// The values of the row, or the error reported by libsql while reading a value.
+pub fn row_values(row: &libsql::Row, column_count: i32) -> libsql::Result<Vec<Cell>> {
+    (0..column_count)
+        .map(|idx| row.get_value(idx).map(Cell))
+        .collect()
+}
+
+/// Serializes a query result into a JSON C string.
+///
+/// # Returns
+///
+/// A raw pointer to the null-terminated JSON string, or a message describing why serialization failed.
+pub fn into_c_json(result: &QueryResult) -> Result<*const libc::c_char, String> {
+    to_c_json(result).map(|c_json| c_json.into_raw() as *const libc::c_char)
+}
+
+/// Serializes a value into an owned JSON C string.
+///
+/// # Returns
+///
+/// The null-terminated JSON string, or a message describing why serialization failed.
+pub fn to_c_json<T: serde::Serialize + ?Sized>(value: &T) -> Result<std::ffi::CString, String> {
+    let json = serde_json::to_string(value).map_err(|e| e.to_string())?;
+    std::ffi::CString::new(json).map_err(|e| e.to_string())
+}
+
+#[cfg(test)]
+mod tests {
+    use serde_json::json;
+
+    use super::*;
+    use crate::utils::testing::{close, connect_memory, exec, query};
+
+    #[test]
+    fn keeps_select_order_and_duplicate_names() {
+        let conn = connect_memory();
+        exec(
+            conn,
+            "CREATE TABLE a (id INTEGER, name TEXT);
+             CREATE TABLE b (id INTEGER, a_id INTEGER);
+             INSERT INTO a VALUES (1, 'x');
+             INSERT INTO b VALUES (10, 1);",
+        );
+
+        let result = query(
+            conn,
+            "SELECT b.id, a.name, a.id, 0 AS zero FROM a JOIN b ON b.a_id = a.id",
+        );
+        let names: Vec<&str> = result["columns"]
+            .as_array()
+            .unwrap()
+            .iter()
+            .map(|column| column["name"].as_str().unwrap())
+            .collect();
+        assert_eq!(names, ["id", "name", "id", "zero"]);
+        assert_eq!(
+            result["rows"],
+            json!([[
+                {"type": 1, "value": 10},
+                {"type": 3, "value": "x"},
+                {"type": 1, "value": 1},
+                {"type": 1, "value": 0},
+            ]])
+        );
+
+        close(conn);
+    }
+
+    #[test]
+    fn describes_columns_and_cell_storage_classes() {
+        let conn = connect_memory();
+        exec(
+            conn,
+            "CREATE TABLE t (n INTEGER, s VARCHAR(10), untyped);
+             INSERT INTO t VALUES (1, '1', 1.5);",
+        );
+
+        let result = query(conn, "SELECT n, s AS label, untyped, n + 1 AS calc FROM t");
+        assert_eq!(
+            result["columns"],
+            json!([
+                {"name": "n", "decltype": "INTEGER", "table": "t", "origin": "n"},
+                {"name": "label", "decltype": "VARCHAR(10)", "table": "t", "origin": "s"},
+                {"name": "untyped", "decltype": null, "table": "t", "origin": "untyped"},
+                {"name": "calc", "decltype": null, "table": null, "origin": null},
+            ])
+        );
+        // The text '1' is told apart from the integer 1 by its storage class.
+        assert_eq!(
+            result["rows"],
+            json!([[
+                {"type": 1, "value": 1},
+                {"type": 3, "value": "1"},
+                {"type": 2, "value": 1.5},
+                {"type": 1, "value": 2},
+            ]])
+        );
+
+        close(conn);
+    }
+
+    #[test]
+    fn encodes_blobs_and_big_integers_losslessly() {
+        let conn = connect_memory();
+
+        let result = query(
+            conn,
+            "SELECT X'00FF10', 9007199254740991, 9007199254740992, -9007199254740991,
+                    -9007199254740992, 9223372036854775807, -9223372036854775807 - 1",
+        );
+        assert_eq!(
+            result["rows"],
+            json!([[
+                {"type": 4, "value": "AP8Q"},
+                {"type": 1, "value": 9007199254740991i64},
+                {"type": 1, "value": "9007199254740992"},
+                {"type": 1, "value": -9007199254740991i64},
+                {"type": 1, "value": "-9007199254740992"},
+                {"type": 1, "value": "9223372036854775807"},
+                {"type": 1, "value": "-9223372036854775808"},
+            ]])
+        );
+
+        let bytes: Vec<u8> = (0..=255).collect();
+        let hex: String = bytes.iter().map(|byte| format!("{byte:02X}")).collect();
+        let result = query(conn, &format!("SELECT X'{hex}'"));
+        let encoded = result["rows"][0][0]["value"].as_str().unwrap();
+        assert_eq!(STANDARD.decode(encoded).unwrap(), bytes);
+
+        close(conn);
+    }
+}
@@ -0,0 +1,29 @@
+use std::future::Future;
+
+use crate::types::retry::LibsqlPhpRetryPolicy;
+
+/// Runs `op` on `conn` until it succeeds, fails with an error `policy` does not retry, or runs out
+/// of attempts, waiting the backoff of `policy` between attempts.
+///
+/// `op` must be safe to run again after it failed, like a single statement or a query. Busy and locked
+/// failures are not retried while `conn` is inside a transaction.
+pub async fn with_retry<T, F, Fut>(
+    policy: &LibsqlPhpRetryPolicy,
+    conn: &libsql::Connection,
+    mut op: F,
+) -> libsql::Result<T>
+where
+    F: FnMut() -> Fut,
+    Fut: Future<Output = libsql::Result<T>>,
+{
+    let mut attempt = 1;
+    loop {
+        match op().await {
+            Err(e) if policy.retries(&e, attempt, !conn.is_autocommit()) => {
+                tokio::time::sleep(policy.backoff(attempt)).await;
+                attempt += 1;
+            }
+            result => return result,
+        }
+    }
+}
@@ -7,6 +7,11 @@ use tokio::runtime::Runtime;
 /// 
 /// A reference to the Tokio runtime.
 /// 
+/// # Panics
+/// 
+/// Panics if the runtime cannot be started. Exported functions report the panic as an
+/// `ErrorCode::Panic` error.
+/// 
 /// # Examples
 /// 
 /// ```
@@ -15,5 +20,7 @@ use tokio::runtime::Runtime;
 pub fn runtime() -> &'static Runtime {
     static RUNTIME: OnceCell<Runtime> = OnceCell::new();
 
-    RUNTIME.get_or_try_init(Runtime::new).unwrap()
+    RUNTIME
+        .get_or_try_init(Runtime::new)
+        .expect("failed to start the Tokio runtime")
 }
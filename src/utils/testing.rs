@@ -0,0 +1,232 @@
+//! Helpers shared by the tests, calling the exported functions the way C code does.
+
+use std::ffi::{CStr, CString};
+
+use crate::{
+    libsqlphp::{
+        bootstrap::{libsql_php_bootstrap_replica, LIBSQL_PHP_SEED_SNAPSHOT},
+        connection_close::libsql_php_close,
+        execute_batch::libsql_php_execute_batch,
+        free_string::libsql_php_free_string,
+        last_error::libsql_php_last_error_message,
+        local::libsql_php_connect_local,
+        query_values::libsql_php_query_values,
+        remote::libsql_php_connect_remote,
+        remote_replica::libsql_php_connect_offline_replica,
+        transaction::libsql_php_transaction,
+        transaction_exec::libsql_php_transaction_exec,
+    },
+    types::{
+        handle::LibsqlHandle,
+        value::{
+            LibsqlPhpValue, LIBSQL_PHP_BLOB, LIBSQL_PHP_FLOAT, LIBSQL_PHP_INTEGER, LIBSQL_PHP_NULL,
+            LIBSQL_PHP_TEXT,
+        },
+    },
+    utils::errors::LIBSQL_PHP_OK,
+};
+
+/// Opens a connection to a new in-memory database.
+pub(crate) fn connect_memory() -> *mut libc::c_void {
+    connect_local(":memory:")
+}
+
+/// Opens a connection to the local database at `path`, creating it if needed.
+pub(crate) fn connect_local(path: &str) -> *mut libc::c_void {
+    let path = CString::new(path).unwrap();
+    let mut handle: *mut LibsqlHandle = std::ptr::null_mut();
+    let status =
+        unsafe { libsql_php_connect_local(path.as_ptr(), 0, std::ptr::null(), &mut handle) };
+    assert_eq!(status, LIBSQL_PHP_OK, "{}", last_error());
+    handle as *mut libc::c_void
+}
+
+/// Opens a connection to the remote database at `url`, without a token.
+///
+/// The server is only contacted by the first statement, so `url` does not need to be reachable.
+pub(crate) fn connect_remote(url: &str) -> *mut libc::c_void {
+    let url = CString::new(url).unwrap();
+    let token = CString::default();
+    let mut handle: *mut LibsqlHandle = std::ptr::null_mut();
+    let status = unsafe { libsql_php_connect_remote(url.as_ptr(), token.as_ptr(), &mut handle) };
+    assert_eq!(status, LIBSQL_PHP_OK, "{}", last_error());
+    handle as *mut libc::c_void
+}
+
+/// A primary URL nothing listens on, so connecting to it fails at once.
+pub(crate) const UNREACHABLE_URL: &str = "http://127.0.0.1:9";
+
+/// Opens the replica at `path` with `libsql_php_connect_offline_replica`, giving its unreachable
+/// primary 200 milliseconds to answer, and returns the status and the connection.
+pub(crate) fn connect_offline_replica(path: &std::path::Path) -> (libc::c_int, *mut libc::c_void) {
+    let path = CString::new(path.to_str().unwrap()).unwrap();
+    let url = CString::new(UNREACHABLE_URL).unwrap();
+    let token = CString::default();
+    let mut handle: *mut LibsqlHandle = std::ptr::null_mut();
+    let status = unsafe {
+        libsql_php_connect_offline_replica(
+            path.as_ptr(),
+            url.as_ptr(),
+            token.as_ptr(),
+            0,
+            0,
+            std::ptr::null(),
+            200,
+            &mut handle,
+        )
+    };
+    (status, handle as *mut libc::c_void)
+}
+
+/// Closes a connection opened by one of the `connect_*` helpers.
+pub(crate) fn close(handle: *mut libc::c_void) {
+    assert_eq!(unsafe { libsql_php_close(handle) }, LIBSQL_PHP_OK);
+}
+
+/// Runs statements that take no parameters, failing the test if they fail.
+pub(crate) fn exec(handle: *mut libc::c_void, sql: &str) {
+    let sql = CString::new(sql).unwrap();
+    let status = unsafe { libsql_php_execute_batch(handle, sql.as_ptr()) };
+    assert_eq!(status, LIBSQL_PHP_OK, "{}", last_error());
+}
+
+/// Starts a deferred transaction on `handle` and returns its handle.
+pub(crate) fn begin(handle: *mut libc::c_void) -> *mut libc::c_void {
+    let mut trx = std::ptr::null_mut();
+    let status = unsafe { libsql_php_transaction(handle, std::ptr::null(), &mut trx) };
+    assert_eq!(status, LIBSQL_PHP_OK, "{}", last_error());
+    trx as *mut libc::c_void
+}
+
+/// Runs a statement that takes no parameters inside a transaction, failing the test if it fails.
+pub(crate) fn trx_exec(trx: *mut libc::c_void, sql: &str) {
+    let sql = CString::new(sql).unwrap();
+    let status = unsafe { libsql_php_transaction_exec(trx, sql.as_ptr(), std::ptr::null(), 0) };
+    assert_eq!(status, LIBSQL_PHP_OK, "{}", last_error());
+}
+
+/// Runs a query that takes no parameters and returns its rows as JSON.
+pub(crate) fn query(handle: *mut libc::c_void, sql: &str) -> serde_json::Value {
+    let sql = CString::new(sql).unwrap();
+    let mut json = std::ptr::null();
+    let status =
+        unsafe { libsql_php_query_values(handle, sql.as_ptr(), std::ptr::null(), 0, &mut json) };
+    assert_eq!(status, LIBSQL_PHP_OK, "{}", last_error());
+    take_json(json)
+}
+
+/// Parses a JSON string returned by the library and frees it.
+pub(crate) fn take_json(json: *const libc::c_char) -> serde_json::Value {
+    let value = serde_json::from_slice(unsafe { CStr::from_ptr(json) }.to_bytes()).unwrap();
+    unsafe { libsql_php_free_string(json) };
+    value
+}
+
+/// Describes a libsql value as the `LibsqlPhpValue` C code passes for it, borrowing its bytes.
+pub(crate) fn c_value(value: &libsql::Value) -> LibsqlPhpValue {
+    let (tag, int_value, real_value, bytes) = match value {
+        libsql::Value::Null => (LIBSQL_PHP_NULL, 0, 0.0, &[][..]),
+        libsql::Value::Integer(value) => (LIBSQL_PHP_INTEGER, *value, 0.0, &[][..]),
+        libsql::Value::Real(value) => (LIBSQL_PHP_FLOAT, 0, *value, &[][..]),
+        libsql::Value::Text(value) => (LIBSQL_PHP_TEXT, 0, 0.0, value.as_bytes()),
+        libsql::Value::Blob(value) => (LIBSQL_PHP_BLOB, 0, 0.0, &value[..]),
+    };
+    LibsqlPhpValue {
+        tag,
+        int_value,
+        real_value,
+        ptr: bytes.as_ptr(),
+        len: bytes.len(),
+    }
+}
+
+/// Returns the message of the last error recorded on this thread, or an empty string.
+pub(crate) fn last_error() -> String {
+    let message = libsql_php_last_error_message();
+    if message.is_null() {
+        return String::new();
+    }
+    unsafe { CStr::from_ptr(message) }
+        .to_string_lossy()
+        .into_owned()
+}
+
+/// Returns a path in the temporary directory that no other test uses, removing any file left at it.
+pub(crate) fn temp_path(name: &str) -> std::path::PathBuf {
+    let nanos = std::time::SystemTime::now()
+        .duration_since(std::time::UNIX_EPOCH)
+        .unwrap()
+        .as_nanos();
+    let path = std::env::temp_dir().join(format!(
+        "libsql_php_{name}_{}_{nanos}.db",
+        std::process::id()
+    ));
+    let _ = std::fs::remove_file(&path);
+    path
+}
+
+const SNAPSHOT_PAGE_SIZE: usize = 4096;
+/// The log id of the primary the snapshots written by [`write_snapshot`] come from.
+pub(crate) const SNAPSHOT_LOG_ID: u128 = 0x0123_4567_89ab_cdef;
+/// The frame number of the first page in the snapshots written by [`write_snapshot`].
+const SNAPSHOT_FIRST_FRAME: u64 = 40;
+
+/// Writes a snapshot holding every page of a new database with one row, and returns the frame
+/// number it ends at.
+pub(crate) fn write_snapshot(snapshot: &std::path::Path) -> u64 {
+    let source = temp_path("bootstrap_snapshot_source");
+    let conn = connect_local(source.to_str().unwrap());
+    exec(
+        conn,
+        "PRAGMA page_size = 4096; CREATE TABLE t (name TEXT); INSERT INTO t VALUES ('seeded')",
+    );
+    close(conn);
+
+    let pages = std::fs::read(&source).unwrap();
+    let _ = std::fs::remove_file(&source);
+    let page_count = (pages.len() / SNAPSHOT_PAGE_SIZE) as u64;
+    let end_frame = SNAPSHOT_FIRST_FRAME + page_count - 1;
+
+    let mut file = Vec::new();
+    file.extend_from_slice(&SNAPSHOT_LOG_ID.to_le_bytes());
+    file.extend_from_slice(&SNAPSHOT_FIRST_FRAME.to_le_bytes());
+    file.extend_from_slice(&end_frame.to_le_bytes());
+    file.extend_from_slice(&page_count.to_le_bytes());
+    file.extend_from_slice(&(page_count as u32).to_le_bytes());
+    file.extend_from_slice(&[0; 4]);
+
+    // Snapshot frames go from the newest frame to the oldest.
+    for page_no in (1..=page_count).rev() {
+        file.extend_from_slice(&(SNAPSHOT_FIRST_FRAME + page_no - 1).to_le_bytes());
+        file.extend_from_slice(&0u64.to_le_bytes());
+        file.extend_from_slice(&(page_no as u32).to_le_bytes());
+        file.extend_from_slice(&0u32.to_le_bytes());
+        let start = (page_no as usize - 1) * SNAPSHOT_PAGE_SIZE;
+        file.extend_from_slice(&pages[start..start + SNAPSHOT_PAGE_SIZE]);
+    }
+
+    std::fs::write(snapshot, file).unwrap();
+    end_frame
+}
+
+/// Seeds a replica at `path` from a snapshot written by [`write_snapshot`], as if it had synced
+/// with a primary before, and returns the frame number it reached.
+pub(crate) fn seed_replica(path: &std::path::Path) -> u64 {
+    let snapshot = temp_path("seed_snapshot");
+    let end_frame = write_snapshot(&snapshot);
+
+    let path = CString::new(path.to_str().unwrap()).unwrap();
+    let source = CString::new(snapshot.to_str().unwrap()).unwrap();
+    let status = unsafe {
+        libsql_php_bootstrap_replica(
+            path.as_ptr(),
+            source.as_ptr(),
+            LIBSQL_PHP_SEED_SNAPSHOT,
+            std::ptr::null_mut(),
+        )
+    };
+    assert_eq!(status, LIBSQL_PHP_OK, "{}", last_error());
+
+    let _ = std::fs::remove_file(snapshot);
+    end_frame
+}
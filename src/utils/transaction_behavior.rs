@@ -0,0 +1,32 @@
+use libsql::TransactionBehavior;
+
+use crate::ERR_TRANSACTION_BEHAVIOR;
+
+/// Converts the name of a transaction behavior into `TransactionBehavior`.
+///
+/// The names match the `TransactionBehavior` constants on the PHP side: `DEFERRED`, `IMMEDIATE`,
+/// `EXCLUSIVE` and `READONLY`. `WRITE` and `READ`, the names used for `IMMEDIATE` and `READONLY`
+/// by earlier versions, are still accepted. Names are matched exactly, so lowercase names are rejected.
+///
+/// # Returns
+///
+/// The behavior, or the reason the name was rejected.
+pub fn transaction_behavior(name: &str) -> Result<TransactionBehavior, String> {
+    match name {
+        "DEFERRED" => Ok(TransactionBehavior::Deferred),
+        "IMMEDIATE" | "WRITE" => Ok(TransactionBehavior::Immediate),
+        "EXCLUSIVE" => Ok(TransactionBehavior::Exclusive),
+        "READONLY" | "READ" => Ok(TransactionBehavior::ReadOnly),
+        _ => Err(format!("{ERR_TRANSACTION_BEHAVIOR}: {name}")),
+    }
+}
+
+/// Copies a `TransactionBehavior`, which libsql does not make `Clone`.
+pub fn copy_behavior(behavior: &TransactionBehavior) -> TransactionBehavior {
+    match behavior {
+        TransactionBehavior::Deferred => TransactionBehavior::Deferred,
+        TransactionBehavior::Immediate => TransactionBehavior::Immediate,
+        TransactionBehavior::Exclusive => TransactionBehavior::Exclusive,
+        TransactionBehavior::ReadOnly => TransactionBehavior::ReadOnly,
+    }
+}